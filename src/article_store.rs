@@ -0,0 +1,307 @@
+use crate::types::Article;
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_SPILL_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Parsed articles kept in a temporary append-only log on disk, one JSON record
+/// per line, with only a title → (offset, length) index held in memory. A title
+/// written twice keeps its latest record. The log is deleted when dropped.
+pub struct SpillStore {
+    path: PathBuf,
+    writer: File,
+    reader: Mutex<File>,
+    index: HashMap<String, (u64, u64)>,
+    end: u64,
+}
+
+impl SpillStore {
+    /// Creates an empty log in `dir`.
+    pub fn create(dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        fs::create_dir_all(dir)?;
+        let path = temp_path(dir, "spill");
+        let writer = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .write(true)
+            .open(&path)?;
+        let reader = Mutex::new(File::open(&path)?);
+
+        Ok(Self {
+            path,
+            writer,
+            reader,
+            index: HashMap::new(),
+            end: 0,
+        })
+    }
+
+    /// Appends `article`, returning the copy it replaced, if any.
+    pub fn insert(
+        &mut self,
+        article: &Article,
+    ) -> Result<Option<Article>, Box<dyn std::error::Error>> {
+        let previous = self.get(&article.title)?;
+
+        let mut record = serde_json::to_vec(article)?;
+        record.push(b'\n');
+        self.writer.write_all(&record)?;
+        self.index
+            .insert(article.title.clone(), (self.end, record.len() as u64));
+        self.end += record.len() as u64;
+
+        Ok(previous)
+    }
+
+    pub fn get(&self, title: &str) -> Result<Option<Article>, Box<dyn std::error::Error>> {
+        let Some(&(offset, length)) = self.index.get(title) else {
+            return Ok(None);
+        };
+
+        let mut record = vec![0; length as usize];
+        let mut reader = self
+            .reader
+            .lock()
+            .map_err(|_| "spill store lock poisoned")?;
+        reader.seek(SeekFrom::Start(offset))?;
+        reader.read_exact(&mut record)?;
+        Ok(Some(serde_json::from_slice(&record)?))
+    }
+
+    pub fn contains_key(&self, title: &str) -> bool {
+        self.index.contains_key(title)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.index.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Reads the log front to back, handing `f` the current copy of each article.
+    pub fn for_each(
+        &self,
+        mut f: impl FnMut(&Article) -> Result<(), Box<dyn std::error::Error>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut record = Vec::new();
+        let mut offset = 0;
+        loop {
+            record.clear();
+            let length = reader.read_until(b'\n', &mut record)? as u64;
+            if length == 0 {
+                break;
+            }
+            let article: Article = serde_json::from_slice(&record)?;
+            // Skip records superseded by a later copy of the same title
+            if self.index.get(&article.title) == Some(&(offset, length)) {
+                f(&article)?;
+            }
+            offset += length;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SpillStore {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// A fresh path in `dir` for a temporary file with extension `extension`
+fn temp_path(dir: &Path, extension: &str) -> PathBuf {
+    dir.join(format!(
+        "wikipedia_core-{}-{}.{extension}",
+        std::process::id(),
+        NEXT_SPILL_ID.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// A file deleted when this is dropped
+struct TempFile(PathBuf);
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Titles appended one per line to a temporary file as they arrive, so they
+/// can be listed in sorted order later without holding them all in memory.
+/// The file is deleted when dropped.
+pub(crate) struct TitleLog {
+    dir: PathBuf,
+    // Declared before `file` so it is flushed before the file is deleted
+    writer: BufWriter<File>,
+    file: TempFile,
+}
+
+impl TitleLog {
+    /// Titles sorted in memory at a time by `sorted`
+    const RUN_LENGTH: usize = 100_000;
+
+    /// Creates an empty log in `dir`.
+    pub(crate) fn create(dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        fs::create_dir_all(dir)?;
+        let file = TempFile(temp_path(dir, "titles"));
+        let writer = BufWriter::new(File::create(&file.0)?);
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            writer,
+            file,
+        })
+    }
+
+    pub(crate) fn push(&mut self, title: &str) -> Result<(), Box<dyn std::error::Error>> {
+        writeln!(self.writer, "{title}")?;
+        Ok(())
+    }
+
+    /// The logged titles, sorted and deduplicated by an external merge sort:
+    /// runs of `RUN_LENGTH` titles are sorted into files of their own, which
+    /// are then merged into one.
+    pub(crate) fn sorted(&mut self) -> Result<SortedTitles, Box<dyn std::error::Error>> {
+        self.writer.flush()?;
+
+        let mut runs = Vec::new();
+        let mut lines = BufReader::new(File::open(&self.file.0)?).lines();
+        loop {
+            let mut run = lines
+                .by_ref()
+                .take(Self::RUN_LENGTH)
+                .collect::<Result<Vec<String>, _>>()?;
+            if run.is_empty() {
+                break;
+            }
+            run.sort_unstable();
+            run.dedup();
+            let run_file = TempFile(temp_path(&self.dir, "run"));
+            let mut writer = BufWriter::new(File::create(&run_file.0)?);
+            for title in &run {
+                writeln!(writer, "{title}")?;
+            }
+            writer.flush()?;
+            runs.push(run_file);
+        }
+
+        let mut readers = runs
+            .iter()
+            .map(|run| Ok(BufReader::new(File::open(&run.0)?).lines()))
+            .collect::<Result<Vec<_>, std::io::Error>>()?;
+        let mut heap = BinaryHeap::new();
+        for (index, reader) in readers.iter_mut().enumerate() {
+            if let Some(title) = reader.next() {
+                heap.push(Reverse((title?, index)));
+            }
+        }
+
+        let file = TempFile(temp_path(&self.dir, "sorted"));
+        let mut writer = BufWriter::new(File::create(&file.0)?);
+        let mut len = 0;
+        let mut last = None::<String>;
+        while let Some(Reverse((title, index))) = heap.pop() {
+            if let Some(next) = readers[index].next() {
+                heap.push(Reverse((next?, index)));
+            }
+            if last.as_ref() != Some(&title) {
+                writeln!(writer, "{title}")?;
+                len += 1;
+                last = Some(title);
+            }
+        }
+        writer.flush()?;
+
+        Ok(SortedTitles { file, len })
+    }
+}
+
+/// Sorted, distinct titles in a temporary file, from `TitleLog::sorted`
+pub(crate) struct SortedTitles {
+    file: TempFile,
+    len: usize,
+}
+
+impl SortedTitles {
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Reads the titles back in order, one line at a time.
+    pub(crate) fn iter(
+        &self,
+    ) -> Result<impl Iterator<Item = std::io::Result<String>>, Box<dyn std::error::Error>> {
+        Ok(BufReader::new(File::open(&self.file.0)?).lines())
+    }
+}
+
+/// The generator's articles: those held in memory plus, when the parse spilled
+/// to disk, those in a `SpillStore`. The two never share a title.
+#[derive(Default)]
+pub(crate) struct ArticleStore {
+    memory: HashMap<String, Article>,
+    spilled: Option<SpillStore>,
+}
+
+impl ArticleStore {
+    pub(crate) fn new(memory: HashMap<String, Article>, spilled: Option<SpillStore>) -> Self {
+        Self { memory, spilled }
+    }
+
+    pub(crate) fn get(
+        &self,
+        title: &str,
+    ) -> Result<Option<Cow<'_, Article>>, Box<dyn std::error::Error>> {
+        if let Some(article) = self.memory.get(title) {
+            return Ok(Some(Cow::Borrowed(article)));
+        }
+        match &self.spilled {
+            Some(spilled) => Ok(spilled.get(title)?.map(Cow::Owned)),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn contains_key(&self, title: &str) -> bool {
+        self.memory.contains_key(title)
+            || self
+                .spilled
+                .as_ref()
+                .is_some_and(|spilled| spilled.contains_key(title))
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &String> {
+        self.memory
+            .keys()
+            .chain(self.spilled.iter().flat_map(|spilled| spilled.keys()))
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.memory.len() + self.spilled.as_ref().map_or(0, SpillStore::len)
+    }
+
+    /// Hands `f` every article, in-memory ones first.
+    pub(crate) fn for_each(
+        &self,
+        mut f: impl FnMut(&Article) -> Result<(), Box<dyn std::error::Error>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for article in self.memory.values() {
+            f(article)?;
+        }
+        if let Some(spilled) = &self.spilled {
+            spilled.for_each(f)?;
+        }
+        Ok(())
+    }
+}
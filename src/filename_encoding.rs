@@ -1,12 +1,16 @@
 use unicode_normalization::UnicodeNormalization;
 
-const MAX_FILENAME_LENGTH: usize = 200;
+/// Longest filename (without `.json`) any encoded title or variant gets
+pub const MAX_FILENAME_LENGTH: usize = 200;
 
 pub fn encode_staticmcp_filename(name: &str) -> String {
     let normalized = normalize_unicode(name);
     let safe_chars = make_filename_safe(&normalized);
 
-    if safe_chars.len() <= MAX_FILENAME_LENGTH {
+    if safe_chars.chars().all(|c| c == '_') {
+        // Nothing of the title survived; only its hash tells it apart
+        create_short_filename(name, "")
+    } else if safe_chars.len() <= MAX_FILENAME_LENGTH {
         safe_chars
     } else {
         create_short_filename(name, &safe_chars)
@@ -32,19 +36,38 @@ fn make_filename_safe(text: &str) -> String {
         .collect()
 }
 
-fn create_short_filename(original: &str, encoded: &str) -> String {
+/// Appends `suffix` (e.g. `__disambig_2`) to the encoded filename `base`. When
+/// the result would exceed `MAX_FILENAME_LENGTH`, the base is truncated and
+/// tagged with its hash like `create_short_filename` does, so every variant
+/// fits and variants of different bases stay distinct.
+pub fn encode_variant_filename(base: &str, suffix: &str) -> String {
+    if base.len() + suffix.len() <= MAX_FILENAME_LENGTH {
+        return format!("{base}{suffix}");
+    }
+
+    let prefix_len = MAX_FILENAME_LENGTH.saturating_sub(suffix.len() + 17); // Leave room for _hash
+    format!(
+        "{}_{:016x}{suffix}",
+        &base[..prefix_len.min(base.len())],
+        hash_name(base)
+    )
+}
+
+fn hash_name(name: &str) -> u64 {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
 
     let mut hasher = DefaultHasher::new();
-    original.hash(&mut hasher);
-    let hash = hasher.finish();
+    name.hash(&mut hasher);
+    hasher.finish()
+}
 
+fn create_short_filename(original: &str, encoded: &str) -> String {
     let prefix_len = MAX_FILENAME_LENGTH - 17; // Leave room for _hash
     format!(
         "{}_{:016x}",
         &encoded[..prefix_len.min(encoded.len())],
-        hash
+        hash_name(original)
     )
 }
 
@@ -75,4 +98,18 @@ mod tests {
         assert!(encoded.len() <= MAX_FILENAME_LENGTH);
         assert!(encoded.contains("_"));
     }
+
+    #[test]
+    fn test_symbol_only_titles_get_distinct_names() {
+        let question = encode_staticmcp_filename("???");
+        let bang = encode_staticmcp_filename("!!!");
+        assert!(!question.is_empty());
+        assert!(question.chars().any(|c| c != '_'));
+        assert_ne!(question, bang);
+        assert_eq!(question, encode_staticmcp_filename("???"));
+        assert_ne!(
+            encode_staticmcp_filename(""),
+            encode_staticmcp_filename("\u{301}")
+        );
+    }
 }
@@ -1,4 +1,6 @@
+use crate::types::{ArticleCategorizer, RelevanceScorer};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TopicFilter {
@@ -162,13 +164,22 @@ impl TopicFilter {
         }
     }
 
+    /// Whether any keyword occurs in `title` or `content`. The title is
+    /// scanned first, so `content` is only lowercased when the title misses.
     pub fn is_relevant(&self, title: &str, content: &str) -> bool {
-        let title_lower = title.to_lowercase();
-        let content_lower = content.to_lowercase();
+        self.is_relevant_title(title) || self.matches_text(content)
+    }
+
+    /// Whether any keyword occurs in `title`, the check `parse` applies to every page.
+    pub fn is_relevant_title(&self, title: &str) -> bool {
+        self.matches_text(title)
+    }
 
+    fn matches_text(&self, text: &str) -> bool {
+        let lower = text.to_lowercase();
         self.keywords()
             .iter()
-            .any(|&keyword| title_lower.contains(keyword) || content_lower.contains(keyword))
+            .any(|&keyword| lower.contains(keyword))
     }
 
     pub fn description(&self) -> &'static str {
@@ -190,3 +201,98 @@ impl TopicFilter {
         format!("Wikipedia {} {} StaticMCP", language.to_uppercase(), topic)
     }
 }
+
+impl RelevanceScorer for TopicFilter {
+    /// 1.0 when a keyword occurs in the title or content, 0.0 otherwise.
+    fn score(&self, title: &str, content: &str) -> f32 {
+        if self.is_relevant(title, content) {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Buckets articles by keyword: an article is in every category one of whose
+/// keywords occurs in its title or content as a whole word (or phrase),
+/// ignoring case.
+#[derive(Debug, Clone, Default)]
+pub struct KeywordCategorizer {
+    /// Category → lowercased keywords, in category name order
+    categories: Vec<(String, Vec<String>)>,
+}
+
+impl KeywordCategorizer {
+    pub fn new(categories: HashMap<String, Vec<String>>) -> Self {
+        let mut categories: Vec<(String, Vec<String>)> = categories
+            .into_iter()
+            .map(|(category, keywords)| {
+                let keywords = keywords
+                    .iter()
+                    .map(|keyword| keyword.trim().to_lowercase())
+                    .filter(|keyword| !keyword.is_empty())
+                    .collect();
+                (category, keywords)
+            })
+            .collect();
+        categories.sort_by(|a, b| a.0.cmp(&b.0));
+        Self { categories }
+    }
+}
+
+impl ArticleCategorizer for KeywordCategorizer {
+    /// Categories with a keyword in the title or content, sorted by name.
+    fn categorize(&self, title: &str, content: &str) -> Vec<String> {
+        let title = title.to_lowercase();
+        let content = content.to_lowercase();
+        self.categories
+            .iter()
+            .filter(|(_, keywords)| {
+                keywords.iter().any(|keyword| {
+                    contains_word(&title, keyword) || contains_word(&content, keyword)
+                })
+            })
+            .map(|(category, _)| category.clone())
+            .collect()
+    }
+}
+
+/// Whether `word` occurs in `text` with no letter or digit right before or after it.
+fn contains_word(text: &str, word: &str) -> bool {
+    text.match_indices(word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + word.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Native display name for a Wikipedia language code, used to label servers
+/// built without a topic filter. Returns `None` for codes not in the table.
+pub fn language_display_name(language: &str) -> Option<&'static str> {
+    let name = match language.to_lowercase().as_str() {
+        "ar" => "العربية",
+        "de" => "Deutsch",
+        "en" => "English",
+        "es" => "Español",
+        "fa" => "فارسی",
+        "fr" => "Français",
+        "he" => "עברית",
+        "hi" => "हिन्दी",
+        "id" => "Bahasa Indonesia",
+        "it" => "Italiano",
+        "ja" => "日本語",
+        "ko" => "한국어",
+        "nl" => "Nederlands",
+        "pl" => "Polski",
+        "pt" => "Português",
+        "ru" => "Русский",
+        "simple" => "Simple English",
+        "sv" => "Svenska",
+        "tr" => "Türkçe",
+        "uk" => "Українська",
+        "vi" => "Tiếng Việt",
+        "zh" => "中文",
+        _ => return None,
+    };
+    Some(name)
+}
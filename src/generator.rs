@@ -1,100 +1,1049 @@
+use crate::Config;
+use crate::article_store::{ArticleStore, TitleLog};
 use crate::filters::TopicFilter;
+use crate::layout;
 use crate::parser::WikipediaParser;
+use crate::sink::{FilesystemSink, OutputSink};
 use crate::types::*;
-use std::fs::{self, File};
-use std::io::Write;
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+
+/// Where a chain of redirects ends up, as found by `resolve_redirect`
+enum RedirectChain<'a> {
+    /// The target of the last hop, which is not itself a redirect
+    Resolved(&'a RedirectTarget),
+    /// The chain came back to a title it had already passed
+    Cycle,
+    /// `max_redirect_depth` hops were taken; the target reached so far
+    TooDeep(&'a RedirectTarget),
+}
+
+/// MIME type of the wikitext item `include_raw` adds to `get_article` responses
+const RAW_WIKITEXT_MIME: &str = "text/x-wiki";
+
+/// Opens the index left in the `get_article` file of an article split by `chunk_articles`
+pub(crate) const CHUNKED_ARTICLE_PREFIX: &str = "This article is split into ";
+
+/// Separates the articles that share one `get_article` file after a merged collision
+pub(crate) const MERGED_SECTION: &str = "\n\n---\n\n## ";
+
+/// Entries per page of `list_articles` and of each `search` result set
+const ARTICLES_PER_PAGE: usize = 50;
+
+const CHECKSUMS_PATH: &str = "checksums.json";
 
 pub struct StaticMcpGenerator<C: ArticleCategorizer> {
     output_dir: PathBuf,
+    /// Where files are written; a `FilesystemSink` rooted at `output_dir` unless replaced
+    sink: RefCell<Box<dyn OutputSink>>,
+    /// Files written through `sink` so far, reported if a later write fails
+    files_written: Cell<usize>,
     language: String,
-    articles: std::collections::HashMap<String, Article>,
-    redirects: std::collections::HashMap<String, String>,
+    articles: ArticleStore,
+    redirects: std::collections::HashMap<String, RedirectTarget>,
+    files: std::collections::HashMap<String, FileInfo>,
     article_titles: std::collections::HashSet<String>,
+    /// In streaming mode, every title also appended to a temporary file, from
+    /// which `list_articles` is paginated in sorted order
+    title_log: Option<RefCell<TitleLog>>,
+    /// Whether articles arrive one at a time, as from `new_streaming`
+    streaming: bool,
+    /// Directory for `title_log`; the system temp directory when unset
+    spill_dir: Option<PathBuf>,
     categories: std::collections::HashMap<String, Vec<String>>,
+    /// Title → categories, inverted from a map given to `categories`; while
+    /// set, the categorizer's `categorize` is never called
+    prebuilt_categories: Option<std::collections::HashMap<String, Vec<String>>>,
     categorizer: C,
+    tokenizer: Box<dyn SearchTokenizer>,
+    search_index: bool,
+    report_dangling_links: bool,
+    max_dangling_ratio: Option<f64>,
+    article_header: ArticleHeader,
+    /// Title and rendered header of the article written to each base filename,
+    /// so collisions can recover the original article without reparsing the text.
+    written_headers: std::collections::HashMap<String, (String, String)>,
+    /// Namespace of the page written to each base filename, so pages from
+    /// different namespaces that encode alike are kept apart instead of merged.
+    written_namespaces: std::collections::HashMap<String, i64>,
+    collisions: BTreeMap<String, CollisionRecord>,
+    stats: GenerationStats,
+    generated_at: Option<Timestamp>,
+    /// Explicit `content_mime`; otherwise derived from the content style
+    content_mime: Option<String>,
+    emit_schemas: bool,
+    max_category_entries: Option<usize>,
+    content_addressed: bool,
+    written_files: BTreeSet<String>,
+    server_name: Option<String>,
+    case_variants: Option<CaseVariantPolicy>,
+    article_metadata: bool,
+    enabled_tools: Vec<ToolKind>,
+    frontmatter: bool,
+    content_style: ContentStyle,
+    emit_recent: Option<usize>,
+    emit_summaries: bool,
+    /// Leading sentences kept in `get_summary` responses; the whole lead section when unset
+    summary_sentences: Option<usize>,
+    emit_hosting_meta: bool,
+    emit_well_known: bool,
+    emit_checksums: bool,
+    zero_pad_pages: bool,
+    emit_see_also: bool,
+    compat: McpCompat,
+    shard_depth: usize,
+    /// Lowercased words removed from article bodies
+    stop_words: Option<std::collections::HashSet<String>>,
+    content_transform: Option<ContentTransform>,
+    category_order: CategoryOrder,
+    /// Stops the run before the next article or category file once cancelled
+    cancellation: Option<CancellationToken>,
+    /// Articles written between two `on_progress` reports
+    progress_interval: usize,
+    on_progress: ProgressCallback,
+    follow_redirects: bool,
+    max_redirect_depth: usize,
+    /// Token windows written under `tools/chunks/`
+    chunking: Option<ChunkConfig>,
+    /// Longest `get_article` body, in characters, written as one file
+    chunk_articles: Option<usize>,
+    /// Pages the parser skipped and how many there were in all, when it recorded them
+    excluded: Option<(Vec<ExcludedTitle>, usize)>,
+    /// Redirect titles pointing at each article, collected the first time front matter is rendered
+    aliases: std::sync::OnceLock<std::collections::HashMap<String, Vec<String>>>,
+}
+
+/// Builds a `StaticMcpGenerator` from a `Config`, so every generator option is
+/// declared once on `Config` rather than through constructor arguments.
+/// `Config::input_path` and the parse-only options are ignored.
+pub struct StaticMcpGeneratorBuilder {
+    config: Config,
+    tokenizer: Option<Box<dyn SearchTokenizer>>,
+    sink: Option<Box<dyn OutputSink>>,
+    categories: Option<std::collections::HashMap<String, Vec<String>>>,
+}
+
+impl StaticMcpGeneratorBuilder {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            tokenizer: None,
+            sink: None,
+            categories: None,
+        }
+    }
+
+    /// Replace the tokenizer used to build the search index.
+    pub fn tokenizer(mut self, tokenizer: impl SearchTokenizer + 'static) -> Self {
+        self.tokenizer = Some(Box::new(tokenizer));
+        self
+    }
+
+    /// Write through `sink` instead of to `Config::output_path`.
+    pub fn sink(mut self, sink: impl OutputSink + 'static) -> Self {
+        self.sink = Some(Box::new(sink));
+        self
+    }
+
+    /// Use this category → titles map, built elsewhere, instead of running
+    /// the categorizer over the articles; see `StaticMcpGenerator::categories`.
+    pub fn categories(
+        mut self,
+        categories: std::collections::HashMap<String, Vec<String>>,
+    ) -> Self {
+        self.categories = Some(categories);
+        self
+    }
+
+    /// Builds a generator over the articles already in `parser`. A language
+    /// stated by the dump's `<siteinfo>` replaces an unset `Config::language`.
+    pub fn build<C: ArticleCategorizer>(
+        mut self,
+        mut parser: WikipediaParser,
+        categorizer: C,
+    ) -> StaticMcpGenerator<C> {
+        if self.categories.is_some() {
+            // Stands in for the parse-time categories so `new` doesn't categorize
+            parser.categories = Some(std::collections::HashMap::new());
+        }
+        if let Some(detected) = &parser.site_language
+            && *detected != self.config.language
+        {
+            if self.config.language_configured {
+                println!(
+                    "Warning: configured language '{}' differs from the dump's siteinfo language '{detected}'",
+                    self.config.language
+                );
+            } else {
+                println!("Using language '{detected}' from the dump's siteinfo");
+                self.config.language = detected.clone();
+            }
+        }
+
+        let generator = StaticMcpGenerator::new(
+            self.config.output_path.clone(),
+            self.config.language.clone(),
+            parser,
+            categorizer,
+        );
+        self.apply(generator)
+    }
+
+    /// Builds a generator for `parse_streaming`, with articles written as they arrive.
+    pub fn build_streaming<C: ArticleCategorizer>(self, categorizer: C) -> StaticMcpGenerator<C> {
+        let generator = StaticMcpGenerator::new_streaming(
+            self.config.output_path.clone(),
+            self.config.language.clone(),
+            categorizer,
+        );
+        self.apply(generator)
+    }
+
+    fn apply<C: ArticleCategorizer>(
+        self,
+        generator: StaticMcpGenerator<C>,
+    ) -> StaticMcpGenerator<C> {
+        let config = self.config;
+        let mut generator = generator
+            .search_index(config.search_index)
+            .report_dangling_links(config.report_dangling_links)
+            .article_header(config.article_header)
+            .generated_at(config.generated_at)
+            .content_mime(config.content_mime)
+            .emit_schemas(config.emit_schemas)
+            .content_addressed(config.content_addressed)
+            .article_metadata(config.article_metadata)
+            .enabled_tools(config.enabled_tools)
+            .frontmatter(config.frontmatter)
+            .content_style(config.content_style)
+            .emit_recent(config.emit_recent)
+            .emit_summaries(config.emit_summaries)
+            .summary_sentences(config.summary_sentences)
+            .chunking(config.chunking)
+            .emit_hosting_meta(config.emit_hosting_meta)
+            .emit_well_known(config.emit_well_known)
+            .emit_checksums(config.emit_checksums)
+            .zero_pad_pages(config.zero_pad_pages)
+            .emit_see_also(config.emit_see_also)
+            .compat(config.compat)
+            .shard_depth(config.shard_depth)
+            .stop_words(config.stop_words.clone())
+            .content_transform(config.content_transform.clone())
+            .category_order(config.category_order)
+            .cancellation(config.cancellation.clone())
+            .progress_interval(config.progress_interval)
+            .on_progress(config.on_progress.clone())
+            .follow_redirects(config.follow_redirects)
+            .max_redirect_depth(config.max_redirect_depth)
+            .spill_dir(config.spill_dir.clone());
+        if let Some(ratio) = config.max_dangling_ratio {
+            generator = generator.max_dangling_ratio(ratio);
+        }
+        if let Some(name) = config.server_name {
+            generator = generator.server_name(name);
+        }
+        if let Some(max) = config.max_category_entries {
+            generator = generator.max_category_entries(max);
+        }
+        if let Some(max_chars) = config.chunk_articles {
+            generator = generator.chunk_articles(max_chars);
+        }
+        if let Some(policy) = config.case_variants {
+            generator = generator.case_variants(policy);
+        }
+        if let Some(tokenizer) = self.tokenizer {
+            generator.tokenizer = tokenizer;
+        }
+        if let Some(sink) = self.sink {
+            generator.sink = RefCell::new(sink);
+        }
+        if let Some(categories) = self.categories {
+            generator = generator.categories(categories);
+        }
+        generator
+    }
 }
 
 impl<C: ArticleCategorizer> StaticMcpGenerator<C> {
     pub fn new(
         output_dir: PathBuf,
         language: String,
-        parser: WikipediaParser,
+        mut parser: WikipediaParser,
         categorizer: C,
     ) -> Self {
-        let mut categories: std::collections::HashMap<String, Vec<String>> =
-            std::collections::HashMap::new();
-        for (title, article) in &parser.articles {
-            let category_names = categorizer.categorize(title, &article.content);
-            for category in category_names {
-                categories.entry(category).or_default().push(title.clone());
-            }
-        }
+        parser
+            .articles
+            .retain(|title, article| categorizer.should_include(title, &article.content));
+        let categories = parser
+            .categories
+            .take()
+            .unwrap_or_else(|| categorize_articles(&parser.articles, &categorizer));
+
+        let stats = GenerationStats {
+            invalid_titles: parser.invalid_titles,
+            parse: parser.metrics,
+            ..Default::default()
+        };
+        let excluded = parser
+            .record_excluded
+            .map(|_| (std::mem::take(&mut parser.excluded), parser.excluded_total));
 
         Self {
+            sink: RefCell::new(Box::new(FilesystemSink::new(&output_dir))),
+            files_written: Cell::new(0),
             output_dir,
             language,
-            article_titles: parser.articles.keys().cloned().collect(),
-            articles: parser.articles,
+            article_titles: parser
+                .articles
+                .keys()
+                .chain(parser.spilled.iter().flat_map(|spilled| spilled.keys()))
+                .cloned()
+                .collect(),
+            title_log: None,
+            streaming: false,
+            spill_dir: None,
+            articles: ArticleStore::new(parser.articles, parser.spilled),
             redirects: parser.redirects,
+            files: parser.files,
             categories,
+            prebuilt_categories: None,
             categorizer,
+            tokenizer: Box::new(WhitespaceTokenizer),
+            search_index: false,
+            report_dangling_links: false,
+            max_dangling_ratio: None,
+            article_header: ArticleHeader::default(),
+            written_headers: std::collections::HashMap::new(),
+            written_namespaces: std::collections::HashMap::new(),
+            collisions: BTreeMap::new(),
+            stats,
+            generated_at: None,
+            content_mime: None,
+            emit_schemas: false,
+            max_category_entries: None,
+            content_addressed: false,
+            written_files: BTreeSet::new(),
+            server_name: None,
+            case_variants: None,
+            article_metadata: false,
+            enabled_tools: ToolKind::ALL.to_vec(),
+            frontmatter: false,
+            content_style: ContentStyle::default(),
+            emit_recent: None,
+            emit_summaries: false,
+            summary_sentences: None,
+            emit_hosting_meta: false,
+            emit_well_known: false,
+            emit_checksums: false,
+            zero_pad_pages: false,
+            emit_see_also: false,
+            compat: McpCompat::default(),
+            shard_depth: 0,
+            stop_words: None,
+            content_transform: None,
+            category_order: CategoryOrder::default(),
+            cancellation: None,
+            progress_interval: 1000,
+            on_progress: ProgressCallback::default(),
+            follow_redirects: false,
+            max_redirect_depth: 5,
+            excluded,
+            chunk_articles: None,
+            chunking: None,
+            aliases: std::sync::OnceLock::new(),
         }
     }
 
     pub fn new_streaming(output_dir: PathBuf, language: String, categorizer: C) -> Self {
         Self {
+            sink: RefCell::new(Box::new(FilesystemSink::new(&output_dir))),
+            files_written: Cell::new(0),
             output_dir,
             language,
-            articles: std::collections::HashMap::new(),
+            articles: ArticleStore::default(),
             redirects: std::collections::HashMap::new(),
+            files: std::collections::HashMap::new(),
             article_titles: std::collections::HashSet::new(),
+            title_log: None,
+            streaming: true,
+            spill_dir: None,
             categories: std::collections::HashMap::new(),
+            prebuilt_categories: None,
             categorizer,
+            tokenizer: Box::new(WhitespaceTokenizer),
+            search_index: false,
+            report_dangling_links: false,
+            max_dangling_ratio: None,
+            article_header: ArticleHeader::default(),
+            written_headers: std::collections::HashMap::new(),
+            written_namespaces: std::collections::HashMap::new(),
+            collisions: BTreeMap::new(),
+            stats: GenerationStats::default(),
+            generated_at: None,
+            content_mime: None,
+            emit_schemas: false,
+            max_category_entries: None,
+            content_addressed: false,
+            written_files: BTreeSet::new(),
+            server_name: None,
+            case_variants: None,
+            article_metadata: false,
+            enabled_tools: ToolKind::ALL.to_vec(),
+            frontmatter: false,
+            content_style: ContentStyle::default(),
+            emit_recent: None,
+            emit_summaries: false,
+            summary_sentences: None,
+            emit_hosting_meta: false,
+            emit_well_known: false,
+            emit_checksums: false,
+            zero_pad_pages: false,
+            emit_see_also: false,
+            compat: McpCompat::default(),
+            shard_depth: 0,
+            stop_words: None,
+            content_transform: None,
+            category_order: CategoryOrder::default(),
+            cancellation: None,
+            progress_interval: 1000,
+            on_progress: ProgressCallback::default(),
+            follow_redirects: false,
+            max_redirect_depth: 5,
+            excluded: None,
+            chunk_articles: None,
+            chunking: None,
+            aliases: std::sync::OnceLock::new(),
+        }
+    }
+
+    /// Emit a title search index under `tools/search/`.
+    pub fn search_index(mut self, enabled: bool) -> Self {
+        self.search_index = enabled;
+        self
+    }
+
+    /// Include internal link totals and dangling-link counts in `stats.json`.
+    pub fn report_dangling_links(mut self, enabled: bool) -> Self {
+        self.report_dangling_links = enabled;
+        self
+    }
+
+    /// Fail generation when the share of links pointing outside the corpus exceeds `ratio`.
+    pub fn max_dangling_ratio(mut self, ratio: f64) -> Self {
+        self.max_dangling_ratio = Some(ratio);
+        self
+    }
+
+    /// Counters accumulated by the generation run so far.
+    pub fn stats(&self) -> &GenerationStats {
+        &self.stats
+    }
+
+    /// Count a page the streaming parser dropped for having no usable title.
+    pub fn record_invalid_title(&mut self) {
+        self.stats.invalid_titles += 1;
+    }
+
+    /// Pin the `generated_at` timestamp written to `stats.json` instead of using the current time.
+    pub fn generated_at(mut self, at: Option<Timestamp>) -> Self {
+        self.generated_at = at;
+        self
+    }
+
+    /// MIME type advertised for `get_article` content (default: `text/markdown`,
+    /// or `text/plain` for plain-text content).
+    pub fn content_mime(mut self, mime: Option<String>) -> Self {
+        self.content_mime = mime;
+        self
+    }
+
+    /// Write JSON Schema documents describing the generated files into `schemas/`.
+    pub fn emit_schemas(mut self, enabled: bool) -> Self {
+        self.emit_schemas = enabled;
+        self
+    }
+
+    /// Cap each `tools/categories/<name>.json` at `max` titles, spilling the rest into overflow pages.
+    pub fn max_category_entries(mut self, max: usize) -> Self {
+        self.max_category_entries = Some(max);
+        self
+    }
+
+    /// Name article files `<name>.<sha8>.json` after a hash of their contents and
+    /// publish the title → filename mapping as `resources/article_index.json`.
+    pub fn content_addressed(mut self, enabled: bool) -> Self {
+        self.content_addressed = enabled;
+        self
+    }
+
+    /// Use `name` as the manifest's server name instead of deriving one from
+    /// the language and topic filter.
+    pub fn server_name(mut self, name: impl Into<String>) -> Self {
+        self.server_name = Some(name.into());
+        self
+    }
+
+    /// Resolve titles that differ only by case with `policy` instead of the
+    /// general collision handling.
+    pub fn case_variants(mut self, policy: CaseVariantPolicy) -> Self {
+        self.case_variants = Some(policy);
+        self
+    }
+
+    /// Also write `resources/article_metadata.json`, listing each article's
+    /// filename, length, short description and categories, and give category
+    /// pages an `entries` list with each member's short description and length.
+    pub fn article_metadata(mut self, enabled: bool) -> Self {
+        self.article_metadata = enabled;
+        self
+    }
+
+    /// Only generate and advertise these built-in tools (default: all of them).
+    /// The search tool is controlled separately by `search_index`.
+    pub fn enabled_tools(mut self, tools: Vec<ToolKind>) -> Self {
+        self.enabled_tools = tools;
+        self
+    }
+
+    /// Prepend a YAML front matter block (title, id, url, categories, aliases,
+    /// length) to each `get_article` text, ahead of the header.
+    pub fn frontmatter(mut self, enabled: bool) -> Self {
+        self.frontmatter = enabled;
+        self
+    }
+
+    /// Write article text as Markdown (the default) or as plain text without the
+    /// heading and quote markers. Plain text is advertised as `text/plain`
+    /// unless a content MIME type was set explicitly.
+    pub fn content_style(mut self, style: ContentStyle) -> Self {
+        self.content_style = style;
+        self
+    }
+
+    /// Write `tools/recent.json` listing the `count` most recently edited
+    /// articles, newest first (full parse mode only).
+    pub fn emit_recent(mut self, count: Option<usize>) -> Self {
+        self.emit_recent = count;
+        self
+    }
+
+    /// Write `tools/get_summary/<file>.json` next to each `get_article` file,
+    /// holding the article's lead section: the text before its first heading.
+    pub fn emit_summaries(mut self, enabled: bool) -> Self {
+        self.emit_summaries = enabled;
+        self
+    }
+
+    /// Cut summaries to the first `count` sentences of the lead section.
+    /// Sentences end at `.`, `!` or `?` followed by whitespace, except after
+    /// common abbreviations like `Dr.` or `e.g.` and single-letter initials.
+    pub fn summary_sentences(mut self, count: Option<usize>) -> Self {
+        self.summary_sentences = count;
+        self
+    }
+
+    /// Write each article's content as overlapping windows of `size`
+    /// whitespace-separated tokens, each repeating the last `overlap` tokens
+    /// of the one before, to `tools/chunks/<file>/<n>.json`, with an overview
+    /// at `tools/chunks/<file>.json`. A window ends early at a sentence
+    /// boundary when one leaves it longer than the overlap. `overlap` is
+    /// capped below `size`.
+    pub fn chunking(mut self, chunking: Option<ChunkConfig>) -> Self {
+        self.chunking = chunking.map(|chunking| {
+            let size = chunking.size.max(1);
+            ChunkConfig {
+                size,
+                overlap: chunking.overlap.min(size - 1),
+            }
+        });
+        self
+    }
+
+    /// Write `.well-known/mcp.json`, a discovery document for agents that look
+    /// for an MCP server at the host root. It points at `mcp.json` and at the
+    /// files behind each resource and tool, so the tree must be served from
+    /// the root of its host.
+    pub fn emit_well_known(mut self, enabled: bool) -> Self {
+        self.emit_well_known = enabled;
+        self
+    }
+
+    /// Write `robots.txt` and a `_headers` caching hint file for static hosts.
+    pub fn emit_hosting_meta(mut self, enabled: bool) -> Self {
+        self.emit_hosting_meta = enabled;
+        self
+    }
+
+    /// Write `checksums.json` with the SHA-256 of every generated file, once
+    /// everything else has been written.
+    pub fn emit_checksums(mut self, enabled: bool) -> Self {
+        self.emit_checksums = enabled;
+        self
+    }
+
+    /// Zero-pad `list_articles` page filenames to the width of the last page
+    /// number (`01.json` … `12.json`) so they sort in order.
+    pub fn zero_pad_pages(mut self, enabled: bool) -> Self {
+        self.zero_pad_pages = enabled;
+        self
+    }
+
+    /// Append a "See also" section to each article listing the linked
+    /// articles in the corpus, with the relative path of their `get_article` file.
+    pub fn emit_see_also(mut self, enabled: bool) -> Self {
+        self.emit_see_also = enabled;
+        self
+    }
+
+    /// Write `mcp.json` in the shape a legacy client profile expects.
+    pub fn compat(mut self, compat: McpCompat) -> Self {
+        self.compat = compat;
+        self
+    }
+
+    /// Write each article under `depth` levels of subdirectories named after
+    /// the start of its filename; see `layout::article_shard`.
+    pub fn shard_depth(mut self, depth: usize) -> Self {
+        self.shard_depth = depth;
+        self
+    }
+
+    /// Remove these words from article bodies, matching whole words regardless of case.
+    pub fn stop_words(mut self, words: Option<std::collections::HashSet<String>>) -> Self {
+        self.stop_words = words.map(|words| words.iter().map(|word| word.to_lowercase()).collect());
+        self
+    }
+
+    /// Rewrite each article's content with `transform` before it is written.
+    pub fn content_transform(mut self, transform: Option<ContentTransform>) -> Self {
+        self.content_transform = transform;
+        self
+    }
+
+    /// Check `token` before writing each article and category file, failing
+    /// with `WikipediaError::Cancelled` once it is cancelled. Files already
+    /// written are left in place.
+    pub fn cancellation(mut self, token: Option<CancellationToken>) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// Report progress to `on_progress` every `interval` written articles instead of every 1000.
+    pub fn progress_interval(mut self, interval: usize) -> Self {
+        self.progress_interval = interval.max(1);
+        self
+    }
+
+    /// Send progress reports to `callback` instead of printing them.
+    pub fn on_progress(mut self, callback: ProgressCallback) -> Self {
+        self.on_progress = callback;
+        self
+    }
+
+    /// Order of the names in `list_categories.json`.
+    pub fn category_order(mut self, order: CategoryOrder) -> Self {
+        self.category_order = order;
+        self
+    }
+
+    /// Split article bodies longer than `max_chars` characters into parts of
+    /// at most that size, written to `tools/get_article/<file>/<n>.json`,
+    /// and leave an index of the parts in the article's own file. Parts break
+    /// before `##` (or `== ==`) section headings where possible, then between
+    /// lines, and concatenated give back the body.
+    pub fn chunk_articles(mut self, max_chars: usize) -> Self {
+        self.chunk_articles = Some(max_chars.max(1));
+        self
+    }
+
+    /// Write a `get_article` file for every redirect title. A redirect whose
+    /// chain ends at a parsed article gets that article's text; one whose
+    /// target isn't in the corpus, or is more than `max_redirect_depth` hops
+    /// away, gets a pointer to the last title reached. Chains that loop are
+    /// skipped. Titles that already have a file keep it.
+    pub fn follow_redirects(mut self, enabled: bool) -> Self {
+        self.follow_redirects = enabled;
+        self
+    }
+
+    /// Longest redirect chain, in hops, that is followed to its end. At least
+    /// one hop is always taken.
+    pub fn max_redirect_depth(mut self, depth: usize) -> Self {
+        self.max_redirect_depth = depth.max(1);
+        self
+    }
+
+    /// Use `categories` (category → titles), e.g. from an offline classifier,
+    /// as the category map and for front matter; the categorizer's
+    /// `categorize` is no longer called. `new` has already categorized the
+    /// parsed articles unless `parser.categories` was set, which
+    /// `StaticMcpGeneratorBuilder::categories` takes care of.
+    pub fn categories(
+        mut self,
+        categories: std::collections::HashMap<String, Vec<String>>,
+    ) -> Self {
+        let mut by_title: std::collections::HashMap<String, Vec<String>> =
+            std::collections::HashMap::new();
+        for (category, titles) in &categories {
+            for title in titles {
+                by_title
+                    .entry(title.clone())
+                    .or_default()
+                    .push(category.clone());
+            }
+        }
+        self.prebuilt_categories = Some(by_title);
+        self.categories = categories;
+        self
+    }
+
+    /// Keep streaming mode's temporary title list in `dir` rather than the
+    /// system temp directory.
+    pub fn spill_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.spill_dir = dir;
+        self
+    }
+
+    /// The `get_article` file named `filename`, in its shard directory.
+    fn article_file_path(&self, filename: &str) -> String {
+        layout::sharded_article_file_path(filename, self.shard_depth)
+    }
+
+    /// Digits `list_articles` page numbers are padded to in filenames: those of
+    /// `total_pages` with `zero_pad_pages`, none otherwise.
+    fn page_digits(&self, total_pages: usize) -> usize {
+        if self.zero_pad_pages {
+            total_pages.to_string().len()
+        } else {
+            0
+        }
+    }
+
+    /// Replace the `# Title` heading written above each article body.
+    pub fn article_header(mut self, header: ArticleHeader) -> Self {
+        self.article_header = header;
+        self
+    }
+
+    /// Replace the tokenizer used to build the search index.
+    pub fn tokenizer(mut self, tokenizer: impl SearchTokenizer + 'static) -> Self {
+        self.tokenizer = Box::new(tokenizer);
+        self
+    }
+
+    /// Write every file through `sink` instead of to the output directory.
+    pub fn sink(mut self, sink: impl OutputSink + 'static) -> Self {
+        self.sink = RefCell::new(Box::new(sink));
+        self
+    }
+
+    fn write_output(
+        &self,
+        path: impl AsRef<str>,
+        contents: impl AsRef<[u8]>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        self.check_output_path(path)?;
+        self.sink
+            .borrow_mut()
+            .write(path, &lf_only_bytes(contents.as_ref()))
+            .map_err(|error| self.output_error(path, error))?;
+        self.files_written.set(self.files_written.get() + 1);
+        Ok(())
+    }
+
+    fn create_output_dir(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.check_output_path(path)?;
+        self.sink
+            .borrow_mut()
+            .create_dir(path)
+            .map_err(|error| self.output_error(path, error))
+    }
+
+    /// Wraps a failed sink write as `WikipediaError::Io`, noting how far the run got.
+    fn output_error(
+        &self,
+        path: &str,
+        error: Box<dyn std::error::Error>,
+    ) -> Box<dyn std::error::Error> {
+        let source = match error.downcast::<std::io::Error>() {
+            Ok(error) => *error,
+            Err(error) => std::io::Error::other(error.to_string()),
+        };
+        WikipediaError::Io {
+            path: path.to_string(),
+            written_so_far: self.files_written.get(),
+            source,
+        }
+        .into()
+    }
+
+    fn read_output(
+        &self,
+        path: impl AsRef<str>,
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        match self.sink.borrow().read(path.as_ref())? {
+            Some(bytes) => Ok(Some(String::from_utf8(bytes)?)),
+            None => Ok(None),
         }
     }
 
+    /// The `get_article` response at `path`, or `None` when there is none. A file
+    /// that doesn't parse, e.g. one left half-written by an interrupted run, is
+    /// reported and treated as missing so the caller overwrites it.
+    fn read_article_response(
+        &self,
+        path: &str,
+    ) -> Result<Option<ToolResponse>, Box<dyn std::error::Error>> {
+        let Some(bytes) = self.sink.borrow().read(path)? else {
+            return Ok(None);
+        };
+        match serde_json::from_slice::<ToolResponse>(&bytes) {
+            Ok(response) if !response.content.is_empty() => Ok(Some(response)),
+            Ok(_) => {
+                eprintln!("Warning: {path} has no content; overwriting it");
+                Ok(None)
+            }
+            Err(error) => {
+                eprintln!("Warning: {path} is not a valid tool response ({error}); overwriting it");
+                Ok(None)
+            }
+        }
+    }
+
+    fn output_exists(&self, path: impl AsRef<str>) -> Result<bool, Box<dyn std::error::Error>> {
+        self.sink.borrow().exists(path.as_ref())
+    }
+
+    fn remove_output(&self, path: impl AsRef<str>) -> Result<(), Box<dyn std::error::Error>> {
+        self.check_output_path(path.as_ref())?;
+        self.sink.borrow_mut().remove(path.as_ref())
+    }
+
+    /// Fails with `WikipediaError::UnsafePath` unless `path` stays inside the
+    /// output root. Category names reach paths unencoded, so a categorizer
+    /// returning `../evil` or `/etc/foo` is caught here. The check is lexical,
+    /// with no filesystem access: the path may only hold plain names, which
+    /// keeps it under the root whatever the sink.
+    fn check_output_path(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            Ok(())
+        } else {
+            Err(WikipediaError::UnsafePath {
+                path: path.to_string(),
+            }
+            .into())
+        }
+    }
+
+    /// Fails with `WikipediaError::Cancelled`, carrying the stats so far, once
+    /// the run's cancellation token is cancelled.
+    fn check_cancelled(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            return Err(WikipediaError::Cancelled {
+                stats: self.stats.clone(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+
     pub fn generate(
         &mut self,
         exact_matches: bool,
         topic_filter: Option<TopicFilter>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(max_ratio) = self.max_dangling_ratio {
+            let (total, dangling) = self.link_stats()?;
+            let ratio = dangling_ratio(total, dangling);
+            if ratio > max_ratio {
+                return Err(format!(
+                    "{dangling} of {total} internal links are dangling (ratio {ratio:.3} exceeds {max_ratio})"
+                )
+                .into());
+            }
+        }
+
+        let result = self.write_tree(exact_matches, topic_filter);
+        if let Err(error) = &result
+            && let Some(WikipediaError::Io { written_so_far, .. }) = error.downcast_ref()
+        {
+            self.mark_stats_partial(*written_so_far);
+        }
+        result
+    }
+
+    /// Writes every output file; `mcp.json` and the resources come first so a
+    /// run that fails partway still leaves them describing the tree.
+    fn write_tree(
+        &mut self,
+        exact_matches: bool,
+        topic_filter: Option<TopicFilter>,
     ) -> Result<(), Box<dyn std::error::Error>> {
         self.create_directories()?;
         self.generate_manifest(&topic_filter)?;
         self.generate_resources(&topic_filter)?;
         self.generate_tools(exact_matches, &topic_filter)?;
+        self.generate_collisions_report()?;
+        self.generate_disambiguation_index()?;
+        if self.content_addressed {
+            self.generate_content_addressed_files()?;
+        }
+        if self.emit_schemas {
+            self.generate_schemas()?;
+        }
+        if self.emit_hosting_meta {
+            self.generate_hosting_meta()?;
+        }
+        if self.emit_checksums {
+            self.generate_checksums()?;
+        }
 
         println!("Generated StaticMCP files in: {:?}", self.output_dir);
         Ok(())
     }
 
     fn create_directories(&self) -> Result<(), Box<dyn std::error::Error>> {
-        fs::create_dir_all(&self.output_dir)?;
-        fs::create_dir_all(self.output_dir.join("resources"))?;
-        fs::create_dir_all(self.output_dir.join("tools/get_article"))?;
-        fs::create_dir_all(self.output_dir.join("tools/list_articles"))?;
-        fs::create_dir_all(self.output_dir.join("tools/categories"))?;
+        self.create_output_dir("resources")?;
+        for tool in [
+            ToolKind::GetArticle,
+            ToolKind::GetArticleById,
+            ToolKind::ListArticles,
+            ToolKind::Categories,
+            ToolKind::ArticleCategories,
+        ] {
+            if self.tool_enabled(tool) {
+                self.create_output_dir(&format!("tools/{}", tool.name()))?;
+            }
+        }
+        if self.search_index {
+            self.create_output_dir("tools/search")?;
+        }
+        if self.emit_summaries {
+            self.create_output_dir("tools/get_summary")?;
+        }
+        if self.chunking.is_some() {
+            self.create_output_dir("tools/chunks")?;
+        }
         Ok(())
     }
 
-    fn create_streaming_directories(&self) -> Result<(), Box<dyn std::error::Error>> {
-        fs::create_dir_all(&self.output_dir)?;
-        fs::create_dir_all(self.output_dir.join("resources"))?;
-        fs::create_dir_all(self.output_dir.join("tools/get_article"))?;
-        fs::create_dir_all(self.output_dir.join("tools/list_articles"))?;
-        fs::create_dir_all(self.output_dir.join("tools/categories"))?;
-        Ok(())
+    /// After a failed write, rewrites `stats.json` (when it was written) to
+    /// count only the articles that made it to disk and flag the tree as
+    /// partial. Best effort: the sink may well refuse this write too.
+    fn mark_stats_partial(&self, written_so_far: usize) {
+        let stats_path = "resources/stats.json";
+        let result = (|| -> Result<(), Box<dyn std::error::Error>> {
+            let Some(stats_json) = self.read_output(stats_path)? else {
+                return Ok(());
+            };
+            let mut stats_response: ResourceResponse = serde_json::from_str(&stats_json)?;
+            let mut stats: serde_json::Value = serde_json::from_str(&stats_response.text)?;
+            stats["total_articles"] = self.stats.articles_written.into();
+            stats["partial"] = true.into();
+            stats["files_written"] = written_so_far.into();
+            stats_response.text = serde_json::to_string_pretty(&stats)?;
+            self.sink.borrow_mut().write(
+                stats_path,
+                serde_json::to_string_pretty(&stats_response)?.as_bytes(),
+            )
+        })();
+        if let Err(error) = result {
+            eprintln!("Warning: could not mark {stats_path} as partial: {error}");
+        }
     }
 
     fn generate_manifest(
         &self,
         topic_filter: &Option<TopicFilter>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let server_name = if let Some(filter) = topic_filter {
-            filter.server_name(&self.language)
-        } else {
-            format!("Wikipedia {} StaticMCP", self.language.to_uppercase())
-        };
+        let server_name = self.resolve_server_name(topic_filter);
+
+        let mut resources = vec![
+            Resource {
+                uri: "wikipedia://stats".to_string(),
+                name: "Wikipedia Statistics".to_string(),
+                description: "Statistics about the Wikipedia dump".to_string(),
+                mime_type: "application/json".to_string(),
+            },
+            Resource {
+                uri: "wikipedia://articles".to_string(),
+                name: "Article List".to_string(),
+                description: "List of all available Wikipedia articles".to_string(),
+                mime_type: "application/json".to_string(),
+            },
+            Resource {
+                uri: "wikipedia://collisions".to_string(),
+                name: "Title Collisions".to_string(),
+                description: "Titles sharing an article filename and how they were resolved"
+                    .to_string(),
+                mime_type: "application/json".to_string(),
+            },
+            Resource {
+                uri: "wikipedia://disambiguation".to_string(),
+                name: "Disambiguation Index".to_string(),
+                description: "Every collision group with the file each member title is served from"
+                    .to_string(),
+                mime_type: "application/json".to_string(),
+            },
+            Resource {
+                uri: "wikipedia://article_ids".to_string(),
+                name: "Article IDs".to_string(),
+                description: "Maps article titles to page ids and page ids back to titles"
+                    .to_string(),
+                mime_type: "application/json".to_string(),
+            },
+            Resource {
+                uri: "wikipedia://geo".to_string(),
+                name: "Geographic Index".to_string(),
+                description: "Latitude and longitude of every article with {{Coord}} coordinates"
+                    .to_string(),
+                mime_type: "application/json".to_string(),
+            },
+        ];
+
+        if self.content_addressed {
+            resources.push(article_index_resource());
+        }
+
+        if self.article_metadata {
+            resources.push(Resource {
+                uri: "wikipedia://article_metadata".to_string(),
+                name: "Article Metadata".to_string(),
+                description: "Filename, length, short description and categories of every article"
+                    .to_string(),
+                mime_type: "application/json".to_string(),
+            });
+        }
+
+        if self.excluded.is_some() {
+            resources.push(Resource {
+                uri: "wikipedia://excluded".to_string(),
+                name: "Excluded Titles".to_string(),
+                description: "Pages the filters skipped, grouped by the reason".to_string(),
+                mime_type: "application/json".to_string(),
+            });
+        }
+
+        if !self.files.is_empty() {
+            resources.push(Resource {
+                uri: "wikipedia://files".to_string(),
+                name: "File Metadata".to_string(),
+                description: "Descriptions, authors and licenses of media files".to_string(),
+                mime_type: "application/json".to_string(),
+            });
+        }
+
+        let tools = self.builtin_tools();
 
         let manifest = Manifest {
             protocol_version: "2024-11-05".to_string(),
@@ -102,98 +1051,92 @@ impl<C: ArticleCategorizer> StaticMcpGenerator<C> {
                 name: server_name,
                 version: "1.0.0".to_string(),
             },
-            capabilities: Capabilities {
-                resources: vec![
-                    Resource {
-                        uri: "wikipedia://stats".to_string(),
-                        name: "Wikipedia Statistics".to_string(),
-                        description: "Statistics about the Wikipedia dump".to_string(),
-                        mime_type: "application/json".to_string(),
-                    },
-                    Resource {
-                        uri: "wikipedia://articles".to_string(),
-                        name: "Article List".to_string(),
-                        description: "List of all available Wikipedia articles".to_string(),
-                        mime_type: "application/json".to_string(),
-                    },
-                ],
-                tools: vec![
-                    Tool {
-                        name: "get_article".to_string(),
-                        description: "Get the full content of a specific Wikipedia article"
-                            .to_string(),
-                        input_schema: serde_json::json!({
-                            "type": "object",
-                            "properties": {
-                                "title": {
-                                    "type": "string",
-                                    "description": "Article title"
-                                }
-                            },
-                            "required": ["title"]
-                        }),
-                    },
-                    Tool {
-                        name: "list_articles".to_string(),
-                        description: "List available Wikipedia articles with pagination"
-                            .to_string(),
-                        input_schema: serde_json::json!({
-                            "type": "object",
-                            "properties": {
-                                "page": {
-                                    "type": "integer",
-                                    "description": "Page number (1-based, default: 1)",
-                                    "minimum": 1
-                                }
-                            },
-                            "required": []
-                        }),
-                    },
-                    Tool {
-                        name: "list_categories".to_string(),
-                        description: "List available article categories".to_string(),
-                        input_schema: serde_json::json!({
-                            "type": "object",
-                            "properties": {},
-                            "required": []
-                        }),
-                    },
-                    Tool {
-                        name: "categories".to_string(),
-                        description: "Get articles from a specific category".to_string(),
-                        input_schema: serde_json::json!({
-                            "type": "object",
-                            "properties": {
-                                "category": {
-                                    "type": "string",
-                                    "description": "Category name"
-                                }
-                            },
-                            "required": ["category"]
-                        }),
-                    },
-                ],
-            },
+            capabilities: Capabilities { resources, tools },
         };
 
-        let manifest_json = serde_json::to_string_pretty(&manifest)?;
-        let mut file = File::create(self.output_dir.join("mcp.json"))?;
-        file.write_all(manifest_json.as_bytes())?;
+        self.write_output("mcp.json", manifest.to_json(self.compat)?)?;
+        self.write_well_known(&manifest)?;
         Ok(())
     }
 
+    /// With `emit_well_known`, writes the discovery document for `manifest`:
+    /// where the manifest is, the host path of each resource, and for each
+    /// tool the file it answers from or the directory of its per-argument files.
+    fn write_well_known(&self, manifest: &Manifest) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.emit_well_known {
+            return Ok(());
+        }
+        let resources: Vec<serde_json::Value> = manifest
+            .capabilities
+            .resources
+            .iter()
+            .map(|resource| {
+                let name = resource.uri.trim_start_matches("wikipedia://");
+                serde_json::json!({
+                    "uri": resource.uri,
+                    "url": format!("/resources/{name}.json")
+                })
+            })
+            .collect();
+        let tools: Vec<serde_json::Value> = manifest
+            .capabilities
+            .tools
+            .iter()
+            .map(|tool| {
+                // A call without arguments is one file; the others are looked up inside a directory
+                let takes_arguments = tool.input_schema["required"]
+                    .as_array()
+                    .is_some_and(|required| !required.is_empty());
+                let url = if takes_arguments {
+                    format!("/tools/{}/", tool.name)
+                } else {
+                    format!("/tools/{}.json", tool.name)
+                };
+                serde_json::json!({ "name": tool.name, "url": url })
+            })
+            .collect();
+        let document = serde_json::json!({
+            "name": manifest.server_info.name,
+            "protocolVersion": manifest.protocol_version,
+            "transport": "static",
+            "manifest": "/mcp.json",
+            "resources": resources,
+            "tools": tools
+        });
+        self.write_output(
+            layout::WELL_KNOWN_PATH,
+            serde_json::to_string_pretty(&document)?,
+        )
+    }
+
     fn generate_resources(
         &self,
         topic_filter: &Option<TopicFilter>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let stats = serde_json::json!({
+        let mut lengths = Vec::with_capacity(self.articles.len());
+        self.articles.for_each(|article| {
+            lengths.push(article.content.chars().count());
+            Ok(())
+        })?;
+
+        let mut stats = serde_json::json!({
             "total_articles": self.articles.len(),
             "total_redirects": self.redirects.len(),
             "language": self.language,
             "topic_filter": topic_filter.as_ref().map(|f| f.description()),
-            "generated_at": chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string()
+            "generated_at": self.generation_timestamp(),
+            "content_lengths": content_length_stats(&lengths)
         });
 
+        if self.report_dangling_links {
+            let (total, dangling) = self.link_stats()?;
+            stats["links"] = serde_json::json!({
+                "total": total,
+                "dangling": dangling,
+                "dangling_ratio": dangling_ratio(total, dangling)
+            });
+        }
+
         let stats_response = ResourceResponse {
             uri: "wikipedia://stats".to_string(),
             mime_type: "application/json".to_string(),
@@ -201,8 +1144,7 @@ impl<C: ArticleCategorizer> StaticMcpGenerator<C> {
         };
 
         let stats_json = serde_json::to_string_pretty(&stats_response)?;
-        let mut file = File::create(self.output_dir.join("resources/stats.json"))?;
-        file.write_all(stats_json.as_bytes())?;
+        self.write_output("resources/stats.json", stats_json)?;
 
         let article_titles: Vec<&String> = self.articles.keys().collect();
         let articles_response = ResourceResponse {
@@ -212,9 +1154,59 @@ impl<C: ArticleCategorizer> StaticMcpGenerator<C> {
         };
 
         let articles_json = serde_json::to_string_pretty(&articles_response)?;
-        let mut file = File::create(self.output_dir.join("resources/articles.json"))?;
-        file.write_all(articles_json.as_bytes())?;
+        self.write_output("resources/articles.json", articles_json)?;
+        self.generate_article_ids(BTreeMap::new())?;
+        self.generate_geo_index(BTreeMap::new())?;
+
+        if self.article_metadata {
+            self.generate_article_metadata()?;
+        }
+
+        if self.excluded.is_some() {
+            self.generate_excluded()?;
+        }
+
+        if !self.files.is_empty() {
+            let mut files: Vec<&FileInfo> = self.files.values().collect();
+            files.sort_by(|a, b| a.title.cmp(&b.title));
+            let files_response = ResourceResponse {
+                uri: "wikipedia://files".to_string(),
+                mime_type: "application/json".to_string(),
+                text: serde_json::to_string(&files)?,
+            };
+
+            let files_json = serde_json::to_string_pretty(&files_response)?;
+            self.write_output("resources/files.json", files_json)?;
+        }
+
+        Ok(())
+    }
 
+    /// Writes `resources/excluded.json`: the recorded titles grouped by
+    /// reason, with `total` counting every skipped page and `truncated` set
+    /// when some went unrecorded.
+    fn generate_excluded(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some((excluded, total)) = &self.excluded else {
+            return Ok(());
+        };
+        let mut by_reason: BTreeMap<ExclusionReason, Vec<&str>> = BTreeMap::new();
+        for page in excluded {
+            by_reason.entry(page.reason).or_default().push(&page.title);
+        }
+
+        let excluded_response = ResourceResponse {
+            uri: "wikipedia://excluded".to_string(),
+            mime_type: "application/json".to_string(),
+            text: serde_json::to_string(&serde_json::json!({
+                "total": total,
+                "truncated": excluded.len() < *total,
+                "reasons": by_reason
+            }))?,
+        };
+        self.write_output(
+            "resources/excluded.json",
+            serde_json::to_string_pretty(&excluded_response)?,
+        )?;
         Ok(())
     }
 
@@ -230,7 +1222,52 @@ impl<C: ArticleCategorizer> StaticMcpGenerator<C> {
         };
 
         self.generate_article_responses(article_limit)?;
+        if self.follow_redirects && self.tool_enabled(ToolKind::GetArticle) {
+            self.generate_redirect_responses()?;
+        }
         self.generate_list_tools()?;
+        if self.search_index {
+            self.generate_search_index(self.articles.keys())?;
+        }
+        if let Some(count) = self.emit_recent {
+            self.generate_recent(count)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `tools/recent.json` with the `count` articles whose revision
+    /// timestamps are latest, ties broken by title. Articles without a
+    /// timestamp are left out.
+    fn generate_recent(&self, count: usize) -> Result<(), Box<dyn std::error::Error>> {
+        let mut edits: Vec<(String, String)> = Vec::new();
+        self.articles.for_each(|article| {
+            if let Some(timestamp) = &article.timestamp {
+                edits.push((timestamp.clone(), article.title.clone()));
+            }
+            Ok(())
+        })?;
+        edits.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        edits.truncate(count);
+
+        let articles: Vec<serde_json::Value> = edits
+            .into_iter()
+            .map(|(timestamp, title)| serde_json::json!({ "title": title, "timestamp": timestamp }))
+            .collect();
+        let recent = serde_json::json!({
+            "count": articles.len(),
+            "articles": articles
+        });
+        let response = ToolResponse {
+            content: vec![ToolContent {
+                content_type: "text".to_string(),
+                text: serde_json::to_string_pretty(&recent)?,
+                mime_type: None,
+            }],
+        };
+        self.write_output(
+            "tools/recent.json",
+            serde_json::to_string_pretty(&response)?,
+        )?;
         Ok(())
     }
 
@@ -238,272 +1275,1477 @@ impl<C: ArticleCategorizer> StaticMcpGenerator<C> {
         &mut self,
         limit: usize,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let articles_to_process: Vec<(String, Article)> = self
-            .articles
-            .iter()
-            .take(limit)
-            .map(|(title, article)| (title.clone(), article.clone()))
-            .collect();
+        let titles_to_process: Vec<String> = self.articles.keys().take(limit).cloned().collect();
         println!(
             "Generating {} article responses...",
-            articles_to_process.len()
+            titles_to_process.len()
+        );
+
+        for (i, title) in titles_to_process.iter().enumerate() {
+            self.check_cancelled()?;
+            let Some(article) = self
+                .articles
+                .get(title)?
+                .map(|article| article.into_owned())
+            else {
+                continue;
+            };
+            self.write_article_with_collision_handling(title, &article)?;
+
+            if (i + 1) % self.progress_interval == 0 {
+                self.on_progress.report(Progress::Generated(i + 1));
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the `get_article` files of redirect titles for `follow_redirects`.
+    fn generate_redirect_responses(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut aliases: Vec<&String> = self.redirects.keys().collect();
+        aliases.sort();
+
+        for alias in aliases {
+            let path =
+                self.article_file_path(&crate::filename_encoding::encode_staticmcp_filename(alias));
+            if self.output_exists(&path)? {
+                continue;
+            }
+            let target = match self.resolve_redirect(alias) {
+                Some(RedirectChain::Resolved(target)) => {
+                    if let Some(article) = self.articles.get(&target.title)? {
+                        let (_, text) =
+                            self.render_article(&target.title, &self.transform_content(&article));
+                        self.write_output(
+                            &path,
+                            serde_json::to_string_pretty(&self.article_response(text))?,
+                        )?;
+                        continue;
+                    }
+                    target
+                }
+                Some(RedirectChain::TooDeep(target)) => target,
+                Some(RedirectChain::Cycle) | None => {
+                    eprintln!("Warning: redirect '{alias}' loops back on itself; skipping it");
+                    continue;
+                }
+            };
+            let pointer = Article {
+                title: alias.clone(),
+                redirect: Some(target.title.clone()),
+                redirect_section: target.section.clone(),
+                ..Default::default()
+            };
+            let (_, text) = self.render_article(alias, &pointer);
+            self.write_output(
+                &path,
+                serde_json::to_string_pretty(&self.article_response(text))?,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Follows the redirect at `title` hop by hop, for at most
+    /// `max_redirect_depth` hops. `None` when `title` isn't a redirect.
+    fn resolve_redirect(&self, title: &str) -> Option<RedirectChain<'_>> {
+        let mut seen = std::collections::HashSet::from([title]);
+        let mut target = self.redirects.get(title)?;
+        let mut hops = 1;
+        loop {
+            if !seen.insert(target.title.as_str()) {
+                return Some(RedirectChain::Cycle);
+            }
+            let Some(next) = self.redirects.get(&target.title) else {
+                return Some(RedirectChain::Resolved(target));
+            };
+            if hops >= self.max_redirect_depth {
+                return Some(RedirectChain::TooDeep(target));
+            }
+            hops += 1;
+            target = next;
+        }
+    }
+
+    /// Appends `title` to the on-disk title list, creating it on first use.
+    fn log_title(&mut self, title: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if self.title_log.is_none() {
+            let dir = self.spill_dir.clone().unwrap_or_else(std::env::temp_dir);
+            self.title_log = Some(RefCell::new(TitleLog::create(&dir)?));
+        }
+        if let Some(log) = &self.title_log {
+            log.borrow_mut().push(title)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_article_with_collision_handling(
+        &mut self,
+        title: &str,
+        article: &Article,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.categorizer.should_include(title, &article.content) {
+            return Ok(());
+        }
+        self.stats.articles_written += 1;
+        // `new` has already categorized parsed articles; only streamed ones are new here
+        if self.article_titles.insert(title.to_string()) {
+            if self.streaming {
+                self.log_title(title)?;
+            }
+            if self.prebuilt_categories.is_none() {
+                let category_names = self.categorizer.categorize(title, &article.content);
+                for category in category_names {
+                    self.categories
+                        .entry(category)
+                        .or_default()
+                        .push(title.to_string());
+                }
+            }
+        }
+
+        let article = &*self.transform_content(article);
+        if self.tool_enabled(ToolKind::GetArticleById) {
+            self.write_article_by_id(title, article)?;
+        }
+        if !self.tool_enabled(ToolKind::GetArticle) {
+            return Ok(());
+        }
+
+        let base_filename = crate::filename_encoding::encode_staticmcp_filename(title);
+        let base_file_path = self.article_file_path(&base_filename);
+
+        if let Some(redirect_target) = &article.redirect {
+            let redirect_filename =
+                crate::filename_encoding::encode_staticmcp_filename(redirect_target);
+            if redirect_filename == base_filename {
+                return Ok(());
+            }
+        }
+
+        if let Some(redirect_target) = self.extract_redirect_target_from_content(&article.content) {
+            let redirect_filename =
+                crate::filename_encoding::encode_staticmcp_filename(&redirect_target.title);
+            if redirect_filename == base_filename {
+                return Ok(());
+            }
+        }
+
+        if let Some(mut existing_response) = self.read_article_response(&base_file_path)? {
+            // Collisions merge and move whole articles, so a split one is put back together first
+            let existing_text = std::mem::take(&mut existing_response.content[0].text);
+            existing_response.content[0].text =
+                self.unchunked_text(&base_filename, existing_text)?;
+
+            if let Some(&existing_namespace) = self.written_namespaces.get(&base_filename)
+                && existing_namespace != article.namespace
+            {
+                self.handle_namespace_collision(
+                    title,
+                    article,
+                    existing_namespace,
+                    &base_filename,
+                )?;
+            } else if let Some(policy) = self.case_variants
+                && let Some((existing_title, _)) = self.written_headers.get(&base_filename)
+                && existing_title != title
+                && existing_title.to_lowercase() == title.to_lowercase()
+            {
+                let existing_title = existing_title.clone();
+                self.handle_case_variant(
+                    policy,
+                    title,
+                    article,
+                    (&existing_title, existing_response),
+                    &base_filename,
+                )?;
+            } else {
+                self.handle_collision(title, article, existing_response, &base_filename)?;
+            }
+        } else {
+            let (header, content) = self.render_article(title, article);
+            self.write_article_file(&base_filename, title, article, &header, content)?;
+            self.written_headers
+                .insert(base_filename.clone(), (title.to_string(), header));
+            self.written_namespaces
+                .insert(base_filename.clone(), article.namespace);
+        }
+
+        Ok(())
+    }
+
+    /// `article` with `content_transform` applied to its content.
+    fn transform_content<'a>(&self, article: &'a Article) -> std::borrow::Cow<'a, Article> {
+        match &self.content_transform {
+            Some(transform) => std::borrow::Cow::Owned(Article {
+                content: transform.apply(&article.content, article),
+                ..article.clone()
+            }),
+            None => std::borrow::Cow::Borrowed(article),
+        }
+    }
+
+    /// Resolves `title`, which differs from the already-written `existing_title`
+    /// only by case, according to `policy`.
+    fn handle_case_variant(
+        &mut self,
+        policy: CaseVariantPolicy,
+        title: &str,
+        article: &Article,
+        (existing_title, existing_response): (&str, ToolResponse),
+        base_filename: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let resolution = match policy {
+            CaseVariantPolicy::Merge => {
+                self.write_merged_article(base_filename, &existing_response, title, article)?;
+                CollisionResolution::Merged
+            }
+            CaseVariantPolicy::KeepFirst => CollisionResolution::KeptFirst,
+            CaseVariantPolicy::HashSuffix => {
+                let digest = Sha256::digest(title.as_bytes());
+                let hash: String = digest[..4].iter().map(|b| format!("{b:02x}")).collect();
+                let filename = crate::filename_encoding::encode_variant_filename(
+                    base_filename,
+                    &format!("_{hash}"),
+                );
+                self.write_single_article(&filename, title, article)?;
+                self.record_collision(
+                    base_filename,
+                    (existing_title, base_filename),
+                    (title, &filename),
+                    CollisionResolution::HashSuffixed,
+                );
+                return Ok(());
+            }
+        };
+        self.record_collision(
+            base_filename,
+            (existing_title, base_filename),
+            (title, base_filename),
+            resolution,
+        );
+        Ok(())
+    }
+
+    /// Keeps pages from different namespaces that share `base_filename` apart.
+    /// A main-namespace page keeps the plain filename; the other page moves to
+    /// `<name>__ns<id>`, listed in `resources/disambiguation.json`.
+    fn handle_namespace_collision(
+        &mut self,
+        title: &str,
+        article: &Article,
+        existing_namespace: i64,
+        base_filename: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let (existing_title, _) = self
+            .written_headers
+            .get(base_filename)
+            .cloned()
+            .unwrap_or_default();
+
+        if article.namespace == 0 {
+            let existing_filename = crate::filename_encoding::encode_variant_filename(
+                base_filename,
+                &format!("__ns{existing_namespace}"),
+            );
+            self.move_article_file(base_filename, &existing_filename)?;
+
+            let (header, content) = self.render_article(title, article);
+            self.write_article_file(base_filename, title, article, &header, content)?;
+            self.written_headers
+                .insert(base_filename.to_string(), (title.to_string(), header));
+            self.written_namespaces
+                .insert(base_filename.to_string(), article.namespace);
+            self.record_collision(
+                base_filename,
+                (&existing_title, &existing_filename),
+                (title, base_filename),
+                CollisionResolution::NamespaceSuffixed,
+            );
+        } else {
+            let filename = crate::filename_encoding::encode_variant_filename(
+                base_filename,
+                &format!("__ns{}", article.namespace),
+            );
+            self.write_single_article(&filename, title, article)?;
+            self.record_collision(
+                base_filename,
+                (&existing_title, base_filename),
+                (title, &filename),
+                CollisionResolution::NamespaceSuffixed,
+            );
+        }
+        Ok(())
+    }
+
+    fn handle_collision(
+        &mut self,
+        new_title: &str,
+        new_article: &Article,
+        existing_response: ToolResponse,
+        base_filename: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let existing_text = &existing_response.content[0].text;
+
+        let (existing_title, existing_header) = self
+            .written_headers
+            .get(base_filename)
+            .cloned()
+            .unwrap_or_else(|| {
+                let title = self.extract_title_from_content(existing_text);
+                let header = format!("# {title}");
+                (title, header)
+            });
+        let existing_body = existing_text
+            .strip_prefix(&format!("{existing_header}\n\n"))
+            .unwrap_or(existing_text);
+
+        if !existing_text.starts_with("Multiple articles found") {
+            let existing_article = Article {
+                id: 0,
+                title: existing_title.clone(),
+                content: existing_body.to_string(),
+                redirect: self.extract_redirect_from_content(existing_text),
+                ..Default::default()
+            };
+
+            if let Some(redirect_target) = &existing_article.redirect {
+                let redirect_filename =
+                    crate::filename_encoding::encode_staticmcp_filename(redirect_target);
+                if redirect_filename == base_filename {
+                    let (header, content) = self.render_article(new_title, new_article);
+                    self.write_article_file(
+                        base_filename,
+                        new_title,
+                        new_article,
+                        &header,
+                        content,
+                    )?;
+                    self.written_headers
+                        .insert(base_filename.to_string(), (new_title.to_string(), header));
+                    self.record_collision(
+                        base_filename,
+                        (&existing_title, base_filename),
+                        (new_title, base_filename),
+                        CollisionResolution::ReplacedRedirect,
+                    );
+                    return Ok(());
+                }
+            }
+
+            if let Some(redirect_target) = self.extract_redirect_target_from_content(existing_text)
+            {
+                let redirect_filename =
+                    crate::filename_encoding::encode_staticmcp_filename(&redirect_target.title);
+                if redirect_filename == base_filename {
+                    let (header, content) = self.render_article(new_title, new_article);
+                    self.write_article_file(
+                        base_filename,
+                        new_title,
+                        new_article,
+                        &header,
+                        content,
+                    )?;
+                    self.written_headers
+                        .insert(base_filename.to_string(), (new_title.to_string(), header));
+                    self.record_collision(
+                        base_filename,
+                        (&existing_title, base_filename),
+                        (new_title, base_filename),
+                        CollisionResolution::ReplacedRedirect,
+                    );
+                    return Ok(());
+                }
+            }
+        }
+
+        if existing_text.starts_with("Multiple articles found") {
+            let mut variant_number = 1;
+            while self.output_exists(
+                self.article_file_path(&disambig_filename(base_filename, variant_number)),
+            )? {
+                variant_number += 1;
+            }
+
+            let variant_filename = disambig_filename(base_filename, variant_number);
+            let variant_title = format!("{new_title}__disambig_{variant_number}");
+            self.write_single_article(&variant_filename, &variant_title, new_article)?;
+            self.record_collision(
+                base_filename,
+                (&existing_title, base_filename),
+                (new_title, &variant_filename),
+                CollisionResolution::Disambiguated,
+            );
+
+            let updated_disambiguation = format!(
+                "{existing_text}• **{variant_title}** - Use get_article tool with title '{variant_title}'\n"
+            );
+
+            self.write_disambiguation_list(base_filename, updated_disambiguation)?;
+        } else if existing_text.len() <= 1000 && new_article.content.len() <= 1000 {
+            self.record_collision(
+                base_filename,
+                (&existing_title, base_filename),
+                (new_title, base_filename),
+                CollisionResolution::Merged,
+            );
+            self.write_merged_article(base_filename, &existing_response, new_title, new_article)?;
+        } else {
+            let existing_variant_title = format!("{existing_title}__disambig_1");
+            let existing_filename = disambig_filename(base_filename, 1);
+            self.write_single_article(
+                &existing_filename,
+                &existing_variant_title,
+                &Article {
+                    id: 0,
+                    title: existing_title.clone(),
+                    content: existing_body.to_string(),
+                    redirect: None,
+                    raw: raw_wikitext(&existing_response).map(str::to_string),
+                    ..Default::default()
+                },
+            )?;
+
+            let new_variant_title = format!("{new_title}__disambig_2");
+            let new_filename = disambig_filename(base_filename, 2);
+            self.write_single_article(&new_filename, &new_variant_title, new_article)?;
+            self.record_collision(
+                base_filename,
+                (&existing_title, &existing_filename),
+                (new_title, &new_filename),
+                CollisionResolution::Disambiguated,
+            );
+
+            let disambiguation = format!(
+                "Multiple articles found. Choose the one you need:\n\n• **{existing_variant_title}** - Use get_article tool with title '{existing_variant_title}'\n• **{new_variant_title}** - Use get_article tool with title '{new_variant_title}'\n"
+            );
+
+            self.write_disambiguation_list(base_filename, disambiguation)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the disambiguation list `text` as the `get_article` file named
+    /// `filename`. A list is no article, so the parts and derived files left
+    /// by the article it displaced are removed.
+    fn write_disambiguation_list(
+        &self,
+        filename: &str,
+        text: String,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.remove_output(layout::article_chunk_dir(filename, self.shard_depth))?;
+        self.remove_output(layout::summary_path(filename))?;
+        self.remove_output(layout::chunks_path(filename))?;
+        self.remove_output(format!("tools/chunks/{filename}"))?;
+        self.write_output(
+            self.article_file_path(filename),
+            serde_json::to_string_pretty(&self.article_response(text))?,
+        )
+    }
+
+    /// Appends `article` as a `## title` section to the `get_article` file
+    /// named `filename`, whose current contents are `existing`, so the file
+    /// serves both. The files derived from it cover the whole merged body,
+    /// under the title of the article first written there.
+    fn write_merged_article(
+        &mut self,
+        filename: &str,
+        existing: &ToolResponse,
+        title: &str,
+        article: &Article,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let existing_text = &existing.content[0].text;
+        let (existing_title, header) =
+            self.written_headers
+                .get(filename)
+                .cloned()
+                .unwrap_or_else(|| {
+                    (
+                        self.extract_title_from_content(existing_text),
+                        String::new(),
+                    )
+                });
+        let text = format!(
+            "{existing_text}{MERGED_SECTION}{title}\n\n{}",
+            article.content
+        );
+        // The wikitext is joined like the rendered text, so both split on the same separator
+        let raw = article.raw.as_ref().map(|raw| {
+            format!(
+                "{}{MERGED_SECTION}{title}\n\n{raw}",
+                raw_wikitext(existing).unwrap_or_default()
+            )
+        });
+        let merged = Article {
+            title: existing_title.clone(),
+            content: text
+                .strip_prefix(&format!("{header}\n\n"))
+                .unwrap_or(&text)
+                .to_string(),
+            namespace: article.namespace,
+            raw,
+            ..Default::default()
+        };
+        self.write_article_file(filename, &existing_title, &merged, &header, text)
+    }
+
+    /// Adds `new` to the collision group at `base_filename`, each side given as
+    /// a (title, serving filename) pair. When `existing` moved off the base
+    /// file, every title still served from it moves with it.
+    fn record_collision(
+        &mut self,
+        base_filename: &str,
+        (existing_title, existing_filename): (&str, &str),
+        (new_title, new_filename): (&str, &str),
+        resolution: CollisionResolution,
+    ) {
+        let record = self
+            .collisions
+            .entry(base_filename.to_string())
+            .or_insert_with(|| CollisionRecord {
+                base_filename: base_filename.to_string(),
+                titles: vec![existing_title.to_string()],
+                filenames: vec![existing_filename.to_string()],
+                resolution,
+            });
+        for filename in &mut record.filenames {
+            if filename == base_filename {
+                *filename = existing_filename.to_string();
+            }
+        }
+        record.titles.push(new_title.to_string());
+        record.filenames.push(new_filename.to_string());
+        record.resolution = resolution;
+        self.stats.collisions = self.collisions.len();
+    }
+
+    /// Writes `resources/collisions.json` listing every collision group and how it was resolved.
+    fn generate_collisions_report(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let records: Vec<&CollisionRecord> = self.collisions.values().collect();
+        let collisions_response = ResourceResponse {
+            uri: "wikipedia://collisions".to_string(),
+            mime_type: "application/json".to_string(),
+            text: serde_json::to_string(&records)?,
+        };
+
+        let collisions_json = serde_json::to_string_pretty(&collisions_response)?;
+        self.write_output("resources/collisions.json", collisions_json)?;
+        Ok(())
+    }
+
+    /// Writes `resources/disambiguation.json`: for every collision group, its
+    /// base filename and the file each member title is served from.
+    fn generate_disambiguation_index(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let groups: Vec<serde_json::Value> = self
+            .collisions
+            .values()
+            .map(|record| {
+                let members: Vec<serde_json::Value> = record
+                    .titles
+                    .iter()
+                    .zip(&record.filenames)
+                    .map(|(title, filename)| {
+                        serde_json::json!({ "title": title, "filename": filename })
+                    })
+                    .collect();
+                serde_json::json!({ "base_filename": record.base_filename, "members": members })
+            })
+            .collect();
+
+        let disambiguation_response = ResourceResponse {
+            uri: "wikipedia://disambiguation".to_string(),
+            mime_type: "application/json".to_string(),
+            text: serde_json::to_string(&groups)?,
+        };
+        self.write_output(
+            "resources/disambiguation.json",
+            serde_json::to_string_pretty(&disambiguation_response)?,
+        )?;
+        Ok(())
+    }
+
+    /// Writes `resources/article_ids.json`: `by_title` maps each parsed
+    /// article's title to its page id and `by_id` maps ids back. Starts from
+    /// the `by_title` passed in, the map of an earlier run when updating.
+    /// Articles without an id (0) are left out.
+    fn generate_article_ids(
+        &self,
+        mut by_title: BTreeMap<String, u64>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.articles.for_each(|article| {
+            if article.id != 0 {
+                by_title.insert(article.title.clone(), article.id);
+            }
+            Ok(())
+        })?;
+        let by_id: BTreeMap<u64, &String> =
+            by_title.iter().map(|(title, &id)| (id, title)).collect();
+
+        let ids_response = ResourceResponse {
+            uri: "wikipedia://article_ids".to_string(),
+            mime_type: "application/json".to_string(),
+            text: serde_json::to_string(&serde_json::json!({
+                "by_title": by_title,
+                "by_id": by_id
+            }))?,
+        };
+        self.write_output(
+            "resources/article_ids.json",
+            serde_json::to_string_pretty(&ids_response)?,
+        )?;
+        Ok(())
+    }
+
+    /// Writes `resources/geo.json`: the title, `lat` and `lon` of each article
+    /// with coordinates, sorted by title. Starts from `by_title`, the index of
+    /// an earlier run when updating; a parsed article replaces its old entry.
+    fn generate_geo_index(
+        &self,
+        mut by_title: BTreeMap<String, (f64, f64)>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.articles.for_each(|article| {
+            match article.coordinates {
+                Some(coordinates) => by_title.insert(article.title.clone(), coordinates),
+                None => by_title.remove(&article.title),
+            };
+            Ok(())
+        })?;
+        let entries: Vec<serde_json::Value> = by_title
+            .iter()
+            .map(
+                |(title, (lat, lon))| serde_json::json!({ "title": title, "lat": lat, "lon": lon }),
+            )
+            .collect();
+
+        let geo_response = ResourceResponse {
+            uri: "wikipedia://geo".to_string(),
+            mime_type: "application/json".to_string(),
+            text: serde_json::to_string(&entries)?,
+        };
+        self.write_output(
+            "resources/geo.json",
+            serde_json::to_string_pretty(&geo_response)?,
+        )?;
+        Ok(())
+    }
+
+    /// Renames every article file written this run to `<name>.<sha8>.json`,
+    /// where `sha8` is the first 8 hex digits of the SHA-256 of its contents,
+    /// and writes `resources/article_index.json` mapping titles to those names,
+    /// relative to `tools/get_article/` so shard directories are included.
+    fn generate_content_addressed_files(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut hashed_names = std::collections::HashMap::new();
+        for filename in &self.written_files {
+            let path = self.article_file_path(filename);
+            let contents = self.read_output(&path)?.unwrap_or_default();
+            let digest = Sha256::digest(&contents);
+            let sha8: String = digest[..4].iter().map(|b| format!("{b:02x}")).collect();
+            self.write_output(
+                self.article_file_path(&format!("{filename}.{sha8}")),
+                contents,
+            )?;
+            self.remove_output(&path)?;
+            hashed_names.insert(
+                filename.as_str(),
+                format!(
+                    "{}{filename}.{sha8}.json",
+                    layout::article_shard(filename, self.shard_depth)
+                ),
+            );
+        }
+
+        let index: BTreeMap<&String, &String> = self
+            .article_titles
+            .iter()
+            .filter_map(|title| {
+                let filename = crate::filename_encoding::encode_staticmcp_filename(title);
+                hashed_names
+                    .get(filename.as_str())
+                    .map(|hashed| (title, hashed))
+            })
+            .collect();
+
+        let index_response = ResourceResponse {
+            uri: "wikipedia://article_index".to_string(),
+            mime_type: "application/json".to_string(),
+            text: serde_json::to_string(&index)?,
+        };
+        self.write_output(
+            "resources/article_index.json",
+            serde_json::to_string_pretty(&index_response)?,
+        )?;
+        Ok(())
+    }
+
+    fn generate_schemas(&self) -> Result<(), Box<dyn std::error::Error>> {
+        for (filename, schema) in crate::schemas::all_schemas() {
+            self.write_output(
+                format!("schemas/{filename}"),
+                serde_json::to_string_pretty(&schema)?,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes `robots.txt`, letting crawlers reach the manifest and tools, and a
+    /// `_headers` file (the format Netlify and Cloudflare Pages read) with cache
+    /// lifetimes. Article files are only cached as immutable when their names
+    /// are content-addressed; otherwise a regeneration may change them in place.
+    fn generate_hosting_meta(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let robots = "User-agent: *\nAllow: /mcp.json\nAllow: /resources/\nAllow: /tools/\n";
+        self.write_output("robots.txt", robots)?;
+
+        let article_cache = if self.content_addressed {
+            "public, max-age=31536000, immutable"
+        } else {
+            "public, max-age=86400"
+        };
+        let headers = format!(
+            "/mcp.json\n  Cache-Control: public, max-age=300\n  Content-Type: application/json\n\n\
+             /resources/*\n  Cache-Control: public, max-age=3600\n  Content-Type: application/json\n\n\
+             /tools/*\n  Cache-Control: public, max-age=3600\n  Content-Type: application/json\n\n\
+             /tools/get_article/*\n  Cache-Control: {article_cache}\n"
+        );
+        self.write_output("_headers", headers)?;
+        Ok(())
+    }
+
+    /// Writes `checksums.json`, mapping the path of every file in the output to
+    /// the hex SHA-256 of its bytes.
+    fn generate_checksums(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut checksums = BTreeMap::new();
+        let sink = self.sink.borrow();
+        for path in sink.paths()? {
+            if path == CHECKSUMS_PATH {
+                continue;
+            }
+            let digest = Sha256::digest(sink.read(&path)?.unwrap_or_default());
+            let hex: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+            checksums.insert(path, hex);
+        }
+        drop(sink);
+        self.write_output(CHECKSUMS_PATH, serde_json::to_string_pretty(&checksums)?)?;
+        Ok(())
+    }
+
+    fn write_single_article(
+        &mut self,
+        filename: &str,
+        title: &str,
+        article: &Article,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let header = self.render_header(title, article);
+        let content = join_header(&header, &self.render_body_with_see_also(article));
+        self.write_article_file(filename, title, article, &header, content)
+    }
+
+    /// Writes `text`, `article` rendered under `header`, as the `get_article`
+    /// file named `filename`: split with `chunk_articles`, with the raw
+    /// wikitext of `include_raw`, and followed by the files other tools
+    /// derive from it. Every write of an article's file goes through here.
+    fn write_article_file(
+        &mut self,
+        filename: &str,
+        title: &str,
+        article: &Article,
+        header: &str,
+        text: String,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let text = self.chunk_long_article(filename, header, text)?;
+        let response = self.with_raw(self.article_response(text), article);
+        self.write_output(
+            self.article_file_path(filename),
+            serde_json::to_string_pretty(&response)?,
+        )?;
+        self.write_derived_files(filename, title, article)?;
+        self.written_files.insert(filename.to_string());
+        Ok(())
+    }
+
+    /// Moves the `get_article` file named `from` to `to` along with its
+    /// `chunk_articles` parts and derived files, for a page that gives its
+    /// filename up to another. The file at `from` is left for the caller to
+    /// overwrite.
+    fn move_article_file(
+        &mut self,
+        from: &str,
+        to: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(mut response) = self.read_article_response(&self.article_file_path(from))? else {
+            return Ok(());
+        };
+        let header = self
+            .written_headers
+            .get(from)
+            .map(|(_, header)| header.clone())
+            .unwrap_or_default();
+        let text = self.unchunked_text(from, std::mem::take(&mut response.content[0].text))?;
+        response.content[0].text = lf_only(self.chunk_long_article(to, &header, text)?);
+        self.remove_output(layout::article_chunk_dir(from, self.shard_depth))?;
+        self.write_output(
+            self.article_file_path(to),
+            serde_json::to_string_pretty(&response)?,
+        )?;
+        self.written_files.insert(to.to_string());
+
+        self.move_output(layout::summary_path(from), layout::summary_path(to))?;
+        self.move_output(layout::chunks_path(from), layout::chunks_path(to))?;
+        self.remove_output(format!("tools/chunks/{to}"))?;
+        for index in 1.. {
+            if !self.move_output(
+                layout::chunk_path(from, index),
+                layout::chunk_path(to, index),
+            )? {
+                break;
+            }
+        }
+        self.remove_output(format!("tools/chunks/{from}"))
+    }
+
+    /// Moves the file at `from` to `to`, returning whether there was one.
+    fn move_output(&self, from: String, to: String) -> Result<bool, Box<dyn std::error::Error>> {
+        let Some(contents) = self.read_output(&from)? else {
+            return Ok(false);
+        };
+        self.write_output(to, contents)?;
+        self.remove_output(from)?;
+        Ok(true)
+    }
+
+    /// `text` of the `get_article` file named `filename`, with the index
+    /// `chunk_articles` left in place of a long body replaced by the body
+    /// put back together from its parts. Other texts are returned unchanged.
+    fn unchunked_text(
+        &self,
+        filename: &str,
+        text: String,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let header = match text.find(CHUNKED_ARTICLE_PREFIX) {
+            Some(0) => "",
+            Some(start) if text[..start].ends_with("\n\n") => &text[..start - 2],
+            _ => return Ok(text),
+        };
+        let first_part = layout::article_chunk_path(filename, self.shard_depth, 1);
+        if !text[header.len()..].contains(&first_part) {
+            return Ok(text);
+        }
+
+        let mut body = String::new();
+        for part in 1.. {
+            let path = layout::article_chunk_path(filename, self.shard_depth, part);
+            let Some(response) = self.read_article_response(&path)? else {
+                break;
+            };
+            body.push_str(&response.content[0].text);
+        }
+        Ok(join_header(header, &body))
+    }
+
+    /// Writes the files other tools derive from `article`, served from the
+    /// `get_article` file named `filename`: its summary and its chunks.
+    fn write_derived_files(
+        &self,
+        filename: &str,
+        title: &str,
+        article: &Article,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.write_summary(filename, title, article)?;
+        self.write_chunks(filename, title, article)
+    }
+
+    /// With `chunking`, writes `article`'s token windows and their overview,
+    /// replacing any left by an earlier write of the same file.
+    fn write_chunks(
+        &self,
+        filename: &str,
+        title: &str,
+        article: &Article,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(chunking) = self.chunking else {
+            return Ok(());
+        };
+        if article.redirect.is_some() {
+            return Ok(());
+        }
+        self.remove_output(format!("tools/chunks/{filename}"))?;
+
+        let windows = chunk_windows(&article.content, chunking.size, chunking.overlap);
+        for (i, window) in windows.iter().enumerate() {
+            let chunk = serde_json::json!({
+                "title": title,
+                "index": i + 1,
+                "total": windows.len(),
+                "start": window.chars.start,
+                "end": window.chars.end,
+                "text": &article.content[window.bytes.clone()]
+            });
+            let response = ToolResponse {
+                content: vec![ToolContent {
+                    content_type: "text".to_string(),
+                    text: serde_json::to_string_pretty(&chunk)?,
+                    mime_type: None,
+                }],
+            };
+            self.write_output(
+                layout::chunk_path(filename, i + 1),
+                serde_json::to_string_pretty(&response)?,
+            )?;
+        }
+
+        let overview = serde_json::json!({
+            "title": title,
+            "chunks": windows.len(),
+            "size": chunking.size,
+            "overlap": chunking.overlap
+        });
+        let response = ToolResponse {
+            content: vec![ToolContent {
+                content_type: "text".to_string(),
+                text: serde_json::to_string_pretty(&overview)?,
+                mime_type: None,
+            }],
+        };
+        self.write_output(
+            layout::chunks_path(filename),
+            serde_json::to_string_pretty(&response)?,
+        )
+    }
+
+    /// With `emit_summaries`, writes the `get_summary` file of `article`,
+    /// served from the `get_article` file named `filename`.
+    fn write_summary(
+        &self,
+        filename: &str,
+        title: &str,
+        article: &Article,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.emit_summaries || article.redirect.is_some() {
+            return Ok(());
+        }
+        let lead = lead_section(&article.content);
+        let summary = match self.summary_sentences {
+            Some(count) => leading_sentences(lead, count),
+            None => lead,
+        };
+        let response = self.article_response(join_header(
+            &self.render_header(title, article),
+            summary.trim(),
+        ));
+        self.write_output(
+            layout::summary_path(filename),
+            serde_json::to_string_pretty(&response)?,
+        )
+    }
+
+    /// With `chunk_articles`, writes the parts of an over-long body to
+    /// `tools/get_article/<filename>/<n>.json` and returns `text` (the header
+    /// followed by the body) with the body replaced by an index of the
+    /// parts. Shorter texts are returned unchanged, and parts left by an
+    /// earlier write of the same file are removed.
+    fn chunk_long_article(
+        &self,
+        filename: &str,
+        header: &str,
+        text: String,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let Some(max_chars) = self.chunk_articles else {
+            return Ok(text);
+        };
+        self.remove_output(layout::article_chunk_dir(filename, self.shard_depth))?;
+
+        let body = if header.is_empty() {
+            text.as_str()
+        } else {
+            text.strip_prefix(header)
+                .and_then(|rest| rest.strip_prefix("\n\n"))
+                .unwrap_or(&text)
+        };
+        if body.chars().count() <= max_chars {
+            return Ok(text);
+        }
+
+        let chunks = chunk_body(body, max_chars);
+        let mut index = format!(
+            "{CHUNKED_ARTICLE_PREFIX}{} parts. Read them in order, from {} to {}:\n",
+            chunks.len(),
+            layout::article_chunk_path(filename, self.shard_depth, 1),
+            layout::article_chunk_path(filename, self.shard_depth, chunks.len())
+        );
+        for (i, chunk) in chunks.iter().enumerate() {
+            let path = layout::article_chunk_path(filename, self.shard_depth, i + 1);
+            let first_line = chunk.lines().next().unwrap_or_default().trim();
+            let label = section_heading(first_line).unwrap_or(if i == 0 {
+                "Introduction"
+            } else {
+                "(continued)"
+            });
+            index.push_str(&format!("\n{}. {label}: {path}", i + 1));
+            let response = self.article_response(chunk.to_string());
+            self.write_output(&path, serde_json::to_string_pretty(&response)?)?;
+        }
+        Ok(join_header(header, &index))
+    }
+
+    /// Writes the `get_article` text for `article` to `tools/get_article_by_id/<id>.json`.
+    /// Redirects and pages without an id have no entry.
+    fn write_article_by_id(
+        &self,
+        title: &str,
+        article: &Article,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if article.id == 0 || article.redirect.is_some() {
+            return Ok(());
+        }
+        let (_, content) = self.render_article(title, article);
+        let response = self.article_response(content);
+        self.write_output(
+            layout::article_by_id_path(article.id),
+            serde_json::to_string_pretty(&response)?,
+        )?;
+        Ok(())
+    }
+
+    /// Wraps `get_article` text in a tool response tagged with the configured content MIME type.
+    fn article_response(&self, text: String) -> ToolResponse {
+        ToolResponse {
+            content: vec![ToolContent {
+                content_type: "text".to_string(),
+                text: lf_only(text),
+                mime_type: Some(self.article_mime().to_string()),
+            }],
+        }
+    }
+
+    /// Adds `article`'s original wikitext, when the parser kept it with
+    /// `include_raw`, as a second `text/x-wiki` content item. A file holding
+    /// merged articles carries their wikitext joined by the same `## title`
+    /// sections as its text; a disambiguation list carries none.
+    fn with_raw(&self, mut response: ToolResponse, article: &Article) -> ToolResponse {
+        if let Some(raw) = &article.raw {
+            response.content.push(ToolContent {
+                content_type: "text".to_string(),
+                text: lf_only(raw.clone()),
+                mime_type: Some(RAW_WIKITEXT_MIME.to_string()),
+            });
+        }
+        response
+    }
+
+    /// MIME type advertised for article text: the one set with `content_mime`,
+    /// or the content style's own.
+    fn article_mime(&self) -> &str {
+        match (&self.content_mime, self.content_style) {
+            (Some(mime), _) => mime,
+            (None, ContentStyle::Markdown) => "text/markdown",
+            (None, ContentStyle::PlainText) => "text/plain",
+        }
+    }
+
+    /// Formats the configured header for `article` shown under `title`, which
+    /// differs from the article's own title for disambiguation variants.
+    fn render_header(&self, title: &str, article: &Article) -> String {
+        let header = if self.content_style == ContentStyle::PlainText {
+            String::new()
+        } else if title == article.title {
+            self.article_header.format(article)
+        } else {
+            self.article_header.format(&Article {
+                title: title.to_string(),
+                ..article.clone()
+            })
+        };
+        if self.frontmatter {
+            join_header(&self.render_frontmatter(title, article), &header)
+        } else {
+            header
+        }
+    }
+
+    /// The article body in the configured content style.
+    fn render_body<'a>(&self, content: &'a str) -> std::borrow::Cow<'a, str> {
+        let body: std::borrow::Cow<'a, str> = match self.content_style {
+            ContentStyle::Markdown => content.into(),
+            ContentStyle::PlainText => content
+                .lines()
+                .map(|line| {
+                    let mut line = line;
+                    while let Some(rest) = line.strip_prefix("> ") {
+                        line = rest;
+                    }
+                    line
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+                .into(),
+        };
+        match &self.stop_words {
+            Some(stop_words) => strip_stop_words(&body, stop_words).into(),
+            None => body,
+        }
+    }
+
+    /// The article body followed, with `emit_see_also`, by its "See also" section.
+    fn render_body_with_see_also(&self, article: &Article) -> String {
+        let body = self.render_body(&article.content);
+        match self.see_also(article) {
+            Some(see_also) => format!("{body}\n\n{see_also}"),
+            None => body.into_owned(),
+        }
+    }
+
+    /// "See also" section for `article`: its links that resolve, through
+    /// redirects, to another article in the corpus, in link order. Paths are
+    /// relative to the article's own file. `None` when disabled or empty.
+    fn see_also(&self, article: &Article) -> Option<String> {
+        if !self.emit_see_also {
+            return None;
+        }
+        let mut seen = std::collections::HashSet::new();
+        let entries: Vec<String> = article
+            .links
+            .iter()
+            .map(|link| match self.resolve_redirect(link) {
+                Some(RedirectChain::Resolved(target)) => &target.title,
+                _ => link,
+            })
+            .filter(|&target| {
+                *target != article.title
+                    && self.article_titles.contains(target)
+                    && seen.insert(target)
+            })
+            .map(|target| {
+                let filename = crate::filename_encoding::encode_staticmcp_filename(target);
+                // Up out of this article's shard directories, then into the target's
+                let path = format!(
+                    "{}../get_article/{}{filename}.json",
+                    "../".repeat(self.shard_depth),
+                    layout::article_shard(&filename, self.shard_depth)
+                );
+                match self.content_style {
+                    ContentStyle::Markdown => format!("- [{target}]({path})"),
+                    ContentStyle::PlainText => format!("- {target}: {path}"),
+                }
+            })
+            .collect();
+        if entries.is_empty() {
+            return None;
+        }
+        let heading = match self.content_style {
+            ContentStyle::Markdown => "## See also",
+            ContentStyle::PlainText => "See also",
+        };
+        Some(format!("{heading}\n\n{}", entries.join("\n")))
+    }
+
+    /// YAML front matter for `article` shown under `title`. Scalars and lists
+    /// are written in JSON-compatible flow style, which every YAML parser reads.
+    fn render_frontmatter(&self, title: &str, article: &Article) -> String {
+        let quote = |value: &str| serde_json::Value::from(value).to_string();
+        let list = |values: &[String]| {
+            let quoted: Vec<String> = values.iter().map(|value| quote(value)).collect();
+            format!("[{}]", quoted.join(", "))
+        };
+
+        let mut categories = match &self.prebuilt_categories {
+            Some(by_title) => by_title.get(title).cloned().unwrap_or_default(),
+            None => self.categorizer.categorize(title, &article.content),
+        };
+        categories.sort();
+        categories.dedup();
+        let aliases = self
+            .aliases
+            .get_or_init(|| {
+                let mut by_target: std::collections::HashMap<String, Vec<String>> =
+                    std::collections::HashMap::new();
+                for (alias, target) in &self.redirects {
+                    by_target
+                        .entry(target.title.clone())
+                        .or_default()
+                        .push(alias.clone());
+                }
+                for aliases in by_target.values_mut() {
+                    aliases.sort();
+                }
+                by_target
+            })
+            .get(title)
+            .map(Vec::as_slice)
+            .unwrap_or(&[]);
+        let url = format!(
+            "https://{}.wikipedia.org/wiki/{}",
+            self.language,
+            title.replace(' ', "_")
         );
 
-        for (i, (title, article)) in articles_to_process.iter().enumerate() {
-            self.write_article_with_collision_handling(title, article)?;
+        format!(
+            "---\ntitle: {}\nid: {}\nurl: {}\ncategories: {}\naliases: {}\nlanglinks: {}\ncoordinates: {}\nlength: {}\n---",
+            quote(title),
+            article.id,
+            quote(&url),
+            list(&categories),
+            list(aliases),
+            langlinks_map(&article.langlinks),
+            serde_json::json!(article.coordinates),
+            article.content.chars().count()
+        )
+    }
 
-            if (i + 1) % 1000 == 0 {
-                println!("Generated {} article responses...", i + 1);
+    /// Renders the `get_article` text for an article: the header followed by
+    /// the body, or by a pointer to the target when the article is a redirect.
+    /// Returns the header alongside the full text.
+    fn render_article(&self, title: &str, article: &Article) -> (String, String) {
+        let header = self.render_header(title, article);
+        let redirect_target = match &article.redirect {
+            Some(target) => Some(RedirectTarget {
+                title: target.clone(),
+                section: article.redirect_section.clone(),
+            }),
+            None => self.extract_redirect_target_from_content(&article.content),
+        };
+        let text = if let Some(redirect_target) = redirect_target {
+            let mut pointer = format!(
+                "This article redirects to another article. Use the get_article tool with title '{}' to access the target content.",
+                redirect_target.title
+            );
+            if let Some(section) = &redirect_target.section {
+                pointer.push_str(&format!(" The redirect points to its '{section}' section."));
             }
-        }
-        Ok(())
+            join_header(&header, &pointer)
+        } else {
+            join_header(&header, &self.render_body_with_see_also(article))
+        };
+        (header, text)
     }
 
-    pub fn write_article_with_collision_handling(
+    /// Applies this generator's articles as a delta on top of an output tree
+    /// previously written by `generate`. New articles are added (going through
+    /// the usual collision handling), changed ones are rewritten in place, and
+    /// the manifest, stats, article list, pagination and category files are
+    /// refreshed. Article files outside the delta are left untouched.
+    ///
+    /// Stats fields that need the whole corpus, such as content lengths and
+    /// link counts, keep the values from the last full generation.
+    pub fn update(
         &mut self,
-        title: &str,
-        article: &Article,
+        topic_filter: Option<TopicFilter>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        self.article_titles.insert(title.to_string());
-        let category_names = self.categorizer.categorize(title, &article.content);
-        for category in category_names {
-            self.categories
-                .entry(category)
-                .or_default()
-                .push(title.to_string());
+        if self.content_addressed {
+            return Err(
+                "Incremental updates are not supported for content-addressed output".into(),
+            );
         }
 
-        let base_filename = crate::filename_encoding::encode_staticmcp_filename(title);
-        let base_file_path = self
-            .output_dir
-            .join(format!("tools/get_article/{base_filename}.json"));
+        let articles_path = "resources/articles.json";
+        let Some(articles_json) = self.read_output(articles_path)? else {
+            return Err(format!(
+                "No generated output to update in {}",
+                self.output_dir.display()
+            )
+            .into());
+        };
+        let articles_resource: ResourceResponse = serde_json::from_str(&articles_json)?;
+        let existing_titles: BTreeSet<String> = serde_json::from_str(&articles_resource.text)?;
+
+        if let Some(collisions_json) = self.read_output("resources/collisions.json")? {
+            let collisions_resource: ResourceResponse = serde_json::from_str(&collisions_json)?;
+            let collisions: Vec<CollisionRecord> = serde_json::from_str(&collisions_resource.text)?;
+            self.collisions = collisions
+                .into_iter()
+                .map(|mut record| {
+                    // Reports written before filenames were tracked
+                    if record.filenames.len() != record.titles.len() {
+                        record.filenames = vec![record.base_filename.clone(); record.titles.len()];
+                    }
+                    (record.base_filename.clone(), record)
+                })
+                .collect();
+            self.stats.collisions = self.collisions.len();
+        }
 
-        if let Some(redirect_target) = &article.redirect {
-            let redirect_filename =
-                crate::filename_encoding::encode_staticmcp_filename(redirect_target);
-            if redirect_filename == base_filename {
-                return Ok(());
+        // The delta's own categories replace whatever its titles had before
+        let delta_categories = std::mem::take(&mut self.categories);
+        let previous_categories = self.load_existing_categories()?;
+        for (category, titles) in &previous_categories {
+            let kept: Vec<String> = titles
+                .iter()
+                .filter(|title| !self.articles.contains_key(title))
+                .cloned()
+                .collect();
+            if !kept.is_empty() {
+                self.categories.insert(category.clone(), kept);
             }
         }
-
-        if let Some(redirect_target) = self.extract_redirect_target_from_content(&article.content) {
-            let redirect_filename =
-                crate::filename_encoding::encode_staticmcp_filename(&redirect_target);
-            if redirect_filename == base_filename {
-                return Ok(());
+        for (category, titles) in delta_categories {
+            self.categories.entry(category).or_default().extend(titles);
+        }
+        for category in previous_categories.keys() {
+            if !self.categories.contains_key(category) {
+                self.remove_output(layout::category_path(category))?;
+                self.remove_output(format!("tools/categories/{category}"))?;
             }
         }
 
-        if base_file_path.exists() {
-            self.handle_collision(title, article, &base_filename)?;
-        } else {
-            let content = if let Some(redirect_target) =
-                self.extract_redirect_target_from_content(&article.content)
-            {
-                format!(
-                    "# {title}\n\nThis article redirects to another article. Use the get_article tool with title '{redirect_target}' to access the target content."
-                )
-            } else {
-                format!("# {}\n\n{}", title, article.content)
-            };
+        self.article_titles.extend(existing_titles.iter().cloned());
+        self.create_directories()?;
 
-            let response = ToolResponse {
-                content: vec![ToolContent {
-                    content_type: "text".to_string(),
-                    text: content,
-                }],
+        let mut delta: Vec<String> = self.articles.keys().cloned().collect();
+        delta.sort();
+
+        for title in &delta {
+            self.check_cancelled()?;
+            let Some(article) = self
+                .articles
+                .get(title)?
+                .map(|article| article.into_owned())
+            else {
+                continue;
             };
+            let filename = crate::filename_encoding::encode_staticmcp_filename(title);
+            let path = self.article_file_path(&filename);
 
-            let response_json = serde_json::to_string_pretty(&response)?;
-            std::fs::write(&base_file_path, response_json)?;
+            if existing_titles.contains(title)
+                && !self.collisions.contains_key(&filename)
+                && let Some(existing_json) = self.read_output(&path)?
+            {
+                self.stats.articles_written += 1;
+                let transformed = self.transform_content(&article).into_owned();
+                let (header, text) = self.render_article(title, &transformed);
+                let text = self.chunk_long_article(&filename, &header, text)?;
+                let response = self.with_raw(self.article_response(text), &article);
+                let response_json = serde_json::to_string_pretty(&response)?;
+                if existing_json != response_json {
+                    self.write_output(&path, response_json)?;
+                    self.write_derived_files(&filename, title, &transformed)?;
+                    if self.tool_enabled(ToolKind::GetArticleById) {
+                        self.write_article_by_id(title, &transformed)?;
+                    }
+                }
+                self.written_headers
+                    .insert(filename, (title.clone(), header));
+            } else {
+                self.write_article_with_collision_handling(title, &article)?;
+            }
         }
 
-        Ok(())
-    }
-
-    fn handle_collision(
-        &mut self,
-        new_title: &str,
-        new_article: &Article,
-        base_filename: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let base_file_path = self
-            .output_dir
-            .join(format!("tools/get_article/{base_filename}.json"));
-
-        let existing_content = std::fs::read_to_string(&base_file_path)?;
-        let existing_response: ToolResponse = serde_json::from_str(&existing_content)?;
-        let existing_text = &existing_response.content[0].text;
-
-        if !existing_text.starts_with("Multiple articles found") {
-            let existing_title = self.extract_title_from_content(existing_text);
+        self.generate_manifest(&topic_filter)?;
+        self.update_stats_resource()?;
 
-            let existing_article = Article {
-                id: 0,
-                title: existing_title.clone(),
-                content: existing_text
-                    .strip_prefix(&format!("# {existing_title}\n\n"))
-                    .unwrap_or(existing_text)
-                    .to_string(),
-                redirect: self.extract_redirect_from_content(existing_text),
-            };
+        let article_titles: BTreeSet<&String> = self.article_titles.iter().collect();
+        let articles_response = ResourceResponse {
+            uri: "wikipedia://articles".to_string(),
+            mime_type: "application/json".to_string(),
+            text: serde_json::to_string(&article_titles)?,
+        };
+        self.write_output(
+            articles_path,
+            serde_json::to_string_pretty(&articles_response)?,
+        )?;
 
-            if let Some(redirect_target) = &existing_article.redirect {
-                let redirect_filename =
-                    crate::filename_encoding::encode_staticmcp_filename(redirect_target);
-                if redirect_filename == base_filename {
-                    let content = if let Some(new_redirect_target) =
-                        self.extract_redirect_target_from_content(&new_article.content)
-                    {
-                        format!(
-                            "# {new_title}\n\nThis article redirects to another article. Use the get_article tool with title '{new_redirect_target}' to access the target content."
-                        )
-                    } else {
-                        format!("# {}\n\n{}", new_title, new_article.content)
-                    };
-                    let response = ToolResponse {
-                        content: vec![ToolContent {
-                            content_type: "text".to_string(),
-                            text: content,
-                        }],
-                    };
-                    let response_json = serde_json::to_string_pretty(&response)?;
-                    std::fs::write(&base_file_path, response_json)?;
-                    return Ok(());
-                }
+        let existing_ids = match self.read_output("resources/article_ids.json")? {
+            Some(ids_json) => {
+                let ids_resource: ResourceResponse = serde_json::from_str(&ids_json)?;
+                let mut ids: serde_json::Value = serde_json::from_str(&ids_resource.text)?;
+                serde_json::from_value(ids["by_title"].take())?
             }
-
-            if let Some(redirect_target) = self.extract_redirect_target_from_content(existing_text)
-            {
-                let redirect_filename =
-                    crate::filename_encoding::encode_staticmcp_filename(&redirect_target);
-                if redirect_filename == base_filename {
-                    let content = if let Some(new_redirect_target) =
-                        self.extract_redirect_target_from_content(&new_article.content)
-                    {
-                        format!(
-                            "# {new_title}\n\nThis article redirects to another article. Use the get_article tool with title '{new_redirect_target}' to access the target content."
-                        )
-                    } else {
-                        format!("# {}\n\n{}", new_title, new_article.content)
-                    };
-                    let response = ToolResponse {
-                        content: vec![ToolContent {
-                            content_type: "text".to_string(),
-                            text: content,
-                        }],
-                    };
-                    let response_json = serde_json::to_string_pretty(&response)?;
-                    std::fs::write(&base_file_path, response_json)?;
-                    return Ok(());
+            None => BTreeMap::new(),
+        };
+        self.generate_article_ids(existing_ids)?;
+
+        let mut existing_geo = BTreeMap::new();
+        if let Some(geo_json) = self.read_output("resources/geo.json")? {
+            let geo_resource: ResourceResponse = serde_json::from_str(&geo_json)?;
+            let entries: Vec<serde_json::Value> = serde_json::from_str(&geo_resource.text)?;
+            for entry in entries {
+                if let (Some(title), Some(lat), Some(lon)) = (
+                    entry["title"].as_str(),
+                    entry["lat"].as_f64(),
+                    entry["lon"].as_f64(),
+                ) {
+                    existing_geo.insert(title.to_string(), (lat, lon));
                 }
             }
         }
+        self.generate_geo_index(existing_geo)?;
 
-        if existing_text.starts_with("Multiple articles found") {
-            let mut variant_number = 1;
-            while self
-                .output_dir
-                .join(format!(
-                    "tools/get_article/{base_filename}__disambig_{variant_number}.json"
-                ))
-                .exists()
-            {
-                variant_number += 1;
-            }
-
-            let variant_filename = format!("{base_filename}__disambig_{variant_number}");
-            let variant_title = format!("{new_title}__disambig_{variant_number}");
-            self.write_single_article(&variant_filename, &variant_title, new_article)?;
-
-            let updated_disambiguation = format!(
-                "{existing_text}• **{variant_title}** - Use get_article tool with title '{variant_title}'\n"
-            );
-
-            let response = ToolResponse {
-                content: vec![ToolContent {
-                    content_type: "text".to_string(),
-                    text: updated_disambiguation,
-                }],
-            };
-
-            let response_json = serde_json::to_string_pretty(&response)?;
-            std::fs::write(&base_file_path, response_json)?;
-        } else if existing_text.len() <= 1000 && new_article.content.len() <= 1000 {
-            let merged_content = format!(
-                "{}\n\n---\n\n## {}\n\n{}",
-                existing_text, new_title, new_article.content
-            );
-
-            let response = ToolResponse {
-                content: vec![ToolContent {
-                    content_type: "text".to_string(),
-                    text: merged_content,
-                }],
-            };
-
-            let response_json = serde_json::to_string_pretty(&response)?;
-            std::fs::write(&base_file_path, response_json)?;
-        } else {
-            let existing_title = self.extract_title_from_content(existing_text);
+        if self.tool_enabled(ToolKind::ListArticles) {
+            self.generate_streaming_pagination()?;
+        }
+        self.generate_category_tools()?;
+        if self.search_index {
+            self.generate_search_index(self.article_titles.iter())?;
+        }
+        self.generate_collisions_report()?;
+        self.generate_disambiguation_index()?;
+        if self.emit_checksums {
+            self.generate_checksums()?;
+        }
 
-            let existing_variant_title = format!("{existing_title}__disambig_1");
-            let existing_filename = format!("{base_filename}__disambig_1");
-            self.write_single_article(
-                &existing_filename,
-                &existing_variant_title,
-                &Article {
-                    id: 0,
-                    title: existing_title.clone(),
-                    content: existing_text
-                        .strip_prefix(&format!("# {existing_title}\n\n"))
-                        .unwrap_or(existing_text)
-                        .to_string(),
-                    redirect: None,
-                },
-            )?;
+        println!("Updated {} articles in {:?}", delta.len(), self.output_dir);
+        Ok(())
+    }
 
-            let new_variant_title = format!("{new_title}__disambig_2");
-            let new_filename = format!("{base_filename}__disambig_2");
-            self.write_single_article(&new_filename, &new_variant_title, new_article)?;
+    /// Reads category → titles back from the `tools/categories/` files of a
+    /// previous run, following overflow pages.
+    fn load_existing_categories(
+        &self,
+    ) -> Result<std::collections::HashMap<String, Vec<String>>, Box<dyn std::error::Error>> {
+        read_categories(&**self.sink.borrow())
+    }
 
-            let disambiguation = format!(
-                "Multiple articles found. Choose the one you need:\n\n• **{existing_variant_title}** - Use get_article tool with title '{existing_variant_title}'\n• **{new_variant_title}** - Use get_article tool with title '{new_variant_title}'\n"
-            );
+    /// Writes `resources/article_metadata.json`: one entry per article, sorted by title.
+    fn generate_article_metadata(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut categories_by_title: std::collections::HashMap<&str, BTreeSet<&str>> =
+            std::collections::HashMap::new();
+        for (category, titles) in &self.categories {
+            for title in titles {
+                categories_by_title
+                    .entry(title.as_str())
+                    .or_default()
+                    .insert(category.as_str());
+            }
+        }
 
-            let response = ToolResponse {
-                content: vec![ToolContent {
-                    content_type: "text".to_string(),
-                    text: disambiguation,
-                }],
+        let sorted: BTreeSet<&String> = self.articles.keys().collect();
+        let mut entries: Vec<serde_json::Value> = Vec::with_capacity(sorted.len());
+        for title in sorted {
+            let Some(article) = self.articles.get(title)? else {
+                continue;
             };
-
-            let response_json = serde_json::to_string_pretty(&response)?;
-            std::fs::write(&base_file_path, response_json)?;
+            entries.push(serde_json::json!({
+                "title": title,
+                "filename": format!(
+                    "{}.json",
+                    crate::filename_encoding::encode_staticmcp_filename(title)
+                ),
+                "length": article.content.chars().count(),
+                "short_description": article.short_description,
+                "langlinks": langlinks_map(&article.langlinks),
+                "coordinates": article.coordinates,
+                "categories": categories_by_title.remove(title.as_str()).unwrap_or_default()
+            }));
         }
 
+        let metadata_response = ResourceResponse {
+            uri: "wikipedia://article_metadata".to_string(),
+            mime_type: "application/json".to_string(),
+            text: serde_json::to_string(&entries)?,
+        };
+        self.write_output(
+            "resources/article_metadata.json",
+            serde_json::to_string_pretty(&metadata_response)?,
+        )?;
         Ok(())
     }
 
-    fn write_single_article(
-        &self,
-        filename: &str,
-        title: &str,
-        article: &Article,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let content = format!("# {}\n\n{}", title, article.content);
-        let response = ToolResponse {
-            content: vec![ToolContent {
-                content_type: "text".to_string(),
-                text: content,
-            }],
-        };
-
-        let response_json = serde_json::to_string_pretty(&response)?;
-        let file_path = self
-            .output_dir
-            .join(format!("tools/get_article/{filename}.json"));
-        std::fs::write(&file_path, response_json)?;
+    /// Refreshes the article count and timestamp of an existing `stats.json`.
+    fn update_stats_resource(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let stats_path = "resources/stats.json";
+        let mut stats_response: ResourceResponse =
+            serde_json::from_str(&self.read_output(stats_path)?.unwrap_or_default())?;
+        let mut stats: serde_json::Value = serde_json::from_str(&stats_response.text)?;
+        stats["total_articles"] = self.article_titles.len().into();
+        stats["generated_at"] = self.generation_timestamp().into();
+        stats_response.text = serde_json::to_string_pretty(&stats)?;
+        self.write_output(stats_path, serde_json::to_string_pretty(&stats_response)?)?;
         Ok(())
     }
 
@@ -513,13 +2755,14 @@ impl<C: ArticleCategorizer> StaticMcpGenerator<C> {
     ) -> Result<(), Box<dyn std::error::Error>> {
         println!("🏛️  Generating metadata files...");
 
-        self.create_streaming_directories()?;
+        self.create_directories()?;
 
         let topic_filter = Some(crate::filters::TopicFilter::History);
-        let server_name = topic_filter
-            .as_ref()
-            .map(|f| f.server_name(&self.language))
-            .unwrap_or_else(|| format!("Wikipedia {} StaticMCP", self.language.to_uppercase()));
+        let server_name = self.resolve_server_name(&topic_filter);
+
+        let mut tools = self.builtin_tools();
+        // `tools/recent.json` needs revision timestamps from a full parse
+        tools.retain(|tool| tool.name != "recent");
 
         let manifest = crate::types::Manifest {
             protocol_version: "2024-11-05".to_string(),
@@ -528,79 +2771,45 @@ impl<C: ArticleCategorizer> StaticMcpGenerator<C> {
                 version: "1.0.0".to_string(),
             },
             capabilities: crate::types::Capabilities {
-                resources: vec![crate::types::Resource {
-                    uri: "wikipedia://stats".to_string(),
-                    name: "Wikipedia Statistics".to_string(),
-                    description: "Statistics about the Wikipedia dump".to_string(),
-                    mime_type: "application/json".to_string(),
-                }],
-                tools: vec![
-                    crate::types::Tool {
-                        name: "get_article".to_string(),
-                        description: "Get the full content of a specific Wikipedia article"
-                            .to_string(),
-                        input_schema: serde_json::json!({
-                            "type": "object",
-                            "properties": {
-                                "title": {
-                                    "type": "string",
-                                    "description": "Article title"
-                                }
-                            },
-                            "required": ["title"]
-                        }),
-                    },
-                    crate::types::Tool {
-                        name: "list_articles".to_string(),
-                        description: "List available Wikipedia articles with pagination"
-                            .to_string(),
-                        input_schema: serde_json::json!({
-                            "type": "object",
-                            "properties": {
-                                "page": {
-                                    "type": "integer",
-                                    "description": "Page number (1-based, default: 1)",
-                                    "minimum": 1
-                                }
-                            },
-                            "required": []
-                        }),
+                resources: vec![
+                    crate::types::Resource {
+                        uri: "wikipedia://stats".to_string(),
+                        name: "Wikipedia Statistics".to_string(),
+                        description: "Statistics about the Wikipedia dump".to_string(),
+                        mime_type: "application/json".to_string(),
                     },
-                    crate::types::Tool {
-                        name: "list_categories".to_string(),
-                        description: "List all available article categories".to_string(),
-                        input_schema: serde_json::json!({
-                            "type": "object",
-                            "properties": {},
-                            "required": []
-                        }),
+                    crate::types::Resource {
+                        uri: "wikipedia://collisions".to_string(),
+                        name: "Title Collisions".to_string(),
+                        description:
+                            "Titles sharing an article filename and how they were resolved"
+                                .to_string(),
+                        mime_type: "application/json".to_string(),
                     },
-                    crate::types::Tool {
-                        name: "categories".to_string(),
-                        description: "Get articles from a specific category".to_string(),
-                        input_schema: serde_json::json!({
-                            "type": "object",
-                            "properties": {
-                                "category": {
-                                    "type": "string",
-                                    "description": "Category name"
-                                }
-                            },
-                            "required": ["category"]
-                        }),
+                    crate::types::Resource {
+                        uri: "wikipedia://disambiguation".to_string(),
+                        name: "Disambiguation Index".to_string(),
+                        description:
+                            "Every collision group with the file each member title is served from"
+                                .to_string(),
+                        mime_type: "application/json".to_string(),
                     },
-                ],
+                ]
+                .into_iter()
+                .chain(self.content_addressed.then(article_index_resource))
+                .collect(),
+                tools,
             },
         };
 
-        let manifest_json = serde_json::to_string_pretty(&manifest)?;
-        std::fs::write(self.output_dir.join("mcp.json"), manifest_json)?;
+        self.write_output("mcp.json", manifest.to_json(self.compat)?)?;
+        self.write_well_known(&manifest)?;
 
         let stats = serde_json::json!({
             "total_articles": self.article_titles.len(),
             "language": self.language,
             "topic_filter": "History",
-            "generated_at": chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            "generated_at": self.generation_timestamp(),
             "streaming_mode": true
         });
 
@@ -611,7 +2820,7 @@ impl<C: ArticleCategorizer> StaticMcpGenerator<C> {
         };
 
         let stats_json = serde_json::to_string_pretty(&stats_response)?;
-        std::fs::write(self.output_dir.join("resources/stats.json"), stats_json)?;
+        self.write_output("resources/stats.json", stats_json)?;
 
         let article_titles: Vec<&String> = self.article_titles.iter().collect();
         let articles_response = crate::types::ResourceResponse {
@@ -621,125 +2830,49 @@ impl<C: ArticleCategorizer> StaticMcpGenerator<C> {
         };
 
         let articles_json = serde_json::to_string_pretty(&articles_response)?;
-        std::fs::write(
-            self.output_dir.join("resources/articles.json"),
-            articles_json,
-        )?;
-
-        self.generate_streaming_pagination()?;
-        self.generate_streaming_categories()?;
-
-        println!("✅ Metadata, pagination, and categories generated");
-        Ok(())
-    }
-
-    fn generate_streaming_pagination(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let articles_per_page = 50;
-        let all_articles: Vec<&String> = self.article_titles.iter().collect();
-        let total_pages = all_articles.len().div_ceil(articles_per_page);
+        self.write_output("resources/articles.json", articles_json)?;
 
-        for page in 1..=total_pages {
-            let start_idx = (page - 1) * articles_per_page;
-            let end_idx = (start_idx + articles_per_page).min(all_articles.len());
-            let page_articles = &all_articles[start_idx..end_idx];
-
-            let page_response = serde_json::json!({
-                "pagination": {
-                    "current_page": page,
-                    "total_pages": total_pages,
-                    "per_page": articles_per_page,
-                    "total_articles": self.article_titles.len()
-                },
-                "articles": page_articles
-            });
-
-            let response = crate::types::ToolResponse {
-                content: vec![crate::types::ToolContent {
-                    content_type: "text".to_string(),
-                    text: serde_json::to_string_pretty(&page_response)?,
-                }],
-            };
-
-            let response_json = serde_json::to_string_pretty(&response)?;
-            std::fs::write(
-                self.output_dir
-                    .join(format!("tools/list_articles/{page}.json")),
-                response_json,
-            )?;
+        if self.tool_enabled(ToolKind::ListArticles) {
+            self.generate_streaming_pagination()?;
+        }
+        self.generate_category_tools()?;
+        self.generate_collisions_report()?;
+        self.generate_disambiguation_index()?;
+        if self.search_index {
+            self.generate_search_index(self.article_titles.iter())?;
+        }
+        if self.emit_schemas {
+            self.generate_schemas()?;
+        }
+        if self.content_addressed {
+            self.generate_content_addressed_files()?;
+        }
+        if self.emit_hosting_meta {
+            self.generate_hosting_meta()?;
+        }
+        if self.emit_checksums {
+            self.generate_checksums()?;
         }
 
-        let metadata_response = serde_json::json!({
-            "pagination": {
-                "current_page": null,
-                "total_pages": total_pages,
-                "per_page": articles_per_page,
-                "total_articles": self.article_titles.len()
-            },
-            "message": format!("Use /list_articles/{{page}}.json to get specific pages (1-{})", total_pages)
-        });
-
-        let response = crate::types::ToolResponse {
-            content: vec![crate::types::ToolContent {
-                content_type: "text".to_string(),
-                text: serde_json::to_string_pretty(&metadata_response)?,
-            }],
-        };
-
-        let response_json = serde_json::to_string_pretty(&response)?;
-        std::fs::write(
-            self.output_dir.join("tools/list_articles.json"),
-            response_json,
-        )?;
-
+        println!("✅ Metadata, pagination, and categories generated");
         Ok(())
     }
-
-    fn generate_streaming_categories(&self) -> Result<(), Box<dyn std::error::Error>> {
-        // Generate list_categories.json
-        let category_names: Vec<&String> = self.categories.keys().collect();
-        let categories_response = serde_json::json!({
-            "categories": category_names
-        });
-
-        let response = crate::types::ToolResponse {
-            content: vec![crate::types::ToolContent {
-                content_type: "text".to_string(),
-                text: serde_json::to_string_pretty(&categories_response)?,
-            }],
-        };
-
-        let response_json = serde_json::to_string_pretty(&response)?;
-        std::fs::write(
-            self.output_dir.join("tools/list_categories.json"),
-            response_json,
-        )?;
-
-        // Generate individual category files
-        for (category, articles) in &self.categories {
-            if !articles.is_empty() {
-                let category_response = serde_json::json!({
-                    "category": category,
-                    "articles": articles,
-                    "count": articles.len()
-                });
-
-                let response = crate::types::ToolResponse {
-                    content: vec![crate::types::ToolContent {
-                        content_type: "text".to_string(),
-                        text: serde_json::to_string_pretty(&category_response)?,
-                    }],
-                };
-
-                let response_json = serde_json::to_string_pretty(&response)?;
-                std::fs::write(
-                    self.output_dir
-                        .join(format!("tools/categories/{category}.json")),
-                    response_json,
-                )?;
+
+    /// `list_articles` for streaming mode and `update`. Titles come from the
+    /// sorted on-disk log when one was kept, so the pages never need every
+    /// title in memory at once.
+    fn generate_streaming_pagination(&self) -> Result<(), Box<dyn std::error::Error>> {
+        match &self.title_log {
+            Some(log) => {
+                let sorted = log.borrow_mut().sorted()?;
+                self.write_list_articles(sorted.len(), sorted.iter()?)
+            }
+            None => {
+                let mut titles: Vec<&String> = self.article_titles.iter().collect();
+                titles.sort();
+                self.write_list_articles(titles.len(), titles.into_iter().map(|t| Ok(t.clone())))
             }
         }
-
-        Ok(())
     }
 
     fn extract_title_from_content(&self, content: &str) -> String {
@@ -760,37 +2893,58 @@ impl<C: ArticleCategorizer> StaticMcpGenerator<C> {
         None
     }
 
-    fn extract_redirect_target_from_content(&self, content: &str) -> Option<String> {
+    fn extract_redirect_target_from_content(&self, content: &str) -> Option<RedirectTarget> {
         let content = content.trim();
         if (content.starts_with("#REDIRECT") || content.starts_with("#redirect"))
             && let Some(start) = content.find("[[")
             && let Some(end) = content[start..].find("]]")
         {
             let target = &content[start + 2..start + end];
-            if let Some(pipe_pos) = target.find('|') {
-                return Some(target[..pipe_pos].trim().to_string());
-            } else {
-                return Some(target.trim().to_string());
-            }
+            let target = target.split('|').next().unwrap_or(target);
+            return Some(RedirectTarget::parse(target));
         }
         None
     }
 
     fn generate_list_tools(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let articles_per_page = 50;
-        let total_pages = self.articles.len().div_ceil(articles_per_page);
-        let all_articles: Vec<&String> = self.articles.keys().collect();
+        if self.tool_enabled(ToolKind::ListArticles) {
+            self.generate_list_articles()?;
+        }
+        self.generate_category_tools()
+    }
+
+    fn generate_list_articles(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut titles: Vec<&String> = self.articles.keys().collect();
+        titles.sort();
+        self.write_list_articles(titles.len(), titles.into_iter().map(|t| Ok(t.clone())))
+    }
+
+    /// Writes the `list_articles` pages and overview for `total` titles,
+    /// taking each page's titles from `titles` in order as it is written.
+    fn write_list_articles(
+        &self,
+        total: usize,
+        mut titles: impl Iterator<Item = std::io::Result<String>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let articles_per_page = ARTICLES_PER_PAGE;
+        let total_pages = total.div_ceil(articles_per_page);
+        let digits = self.page_digits(total_pages);
         for page in 1..=total_pages {
             let start_idx = (page - 1) * articles_per_page;
-            let end_idx = (start_idx + articles_per_page).min(all_articles.len());
-            let page_articles = &all_articles[start_idx..end_idx];
+            let page_articles = titles
+                .by_ref()
+                .take(articles_per_page)
+                .collect::<std::io::Result<Vec<String>>>()?;
+            let end_idx = start_idx + page_articles.len();
 
             let page_response = serde_json::json!({
                 "pagination": {
                     "current_page": page,
                     "total_pages": total_pages,
                     "per_page": articles_per_page,
-                    "total_articles": self.articles.len()
+                    "total_articles": total,
+                    "start_index": start_idx,
+                    "end_index": end_idx - 1
                 },
                 "articles": page_articles
             });
@@ -799,15 +2953,15 @@ impl<C: ArticleCategorizer> StaticMcpGenerator<C> {
                 content: vec![ToolContent {
                     content_type: "text".to_string(),
                     text: serde_json::to_string_pretty(&page_response)?,
+                    mime_type: None,
                 }],
             };
 
             let response_json = serde_json::to_string_pretty(&response)?;
-            let mut file = File::create(
-                self.output_dir
-                    .join(format!("tools/list_articles/{page}.json")),
+            self.write_output(
+                layout::padded_list_articles_page_path(page, digits),
+                response_json,
             )?;
-            file.write_all(response_json.as_bytes())?;
         }
 
         let metadata_response = serde_json::json!({
@@ -815,64 +2969,987 @@ impl<C: ArticleCategorizer> StaticMcpGenerator<C> {
                 "current_page": null,
                 "total_pages": total_pages,
                 "per_page": articles_per_page,
-                "total_articles": self.articles.len()
+                "total_articles": total
             },
-            "message": format!("Use /list_articles/{{page}}.json to get specific pages (1-{})", total_pages)
+            "message": format!(
+                "Use /list_articles/{{page}}.json to get specific pages ({:0digits$}-{total_pages:0digits$})",
+                1
+            )
         });
 
         let response = ToolResponse {
             content: vec![ToolContent {
                 content_type: "text".to_string(),
                 text: serde_json::to_string_pretty(&metadata_response)?,
+                mime_type: None,
             }],
         };
 
         let response_json = serde_json::to_string_pretty(&response)?;
-        let mut file = File::create(self.output_dir.join("tools/list_articles.json"))?;
-        file.write_all(response_json.as_bytes())?;
+        self.write_output(layout::LIST_ARTICLES_PATH, response_json)?;
+
+        Ok(())
+    }
+
+    /// Writes whichever of `list_categories`, `categories` and
+    /// `article_categories` are enabled, in both full and streaming mode.
+    fn generate_category_tools(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.tool_enabled(ToolKind::ListCategories) {
+            let category_names: Vec<&String> = self.categories.keys().collect();
+            self.write_category_list(&category_names)?;
+        }
+        if self.tool_enabled(ToolKind::Categories) {
+            self.generate_category_files()?;
+        }
+        if self.tool_enabled(ToolKind::ArticleCategories) {
+            self.generate_article_categories()?;
+        }
+
+        Ok(())
+    }
+
+    /// Rewrites the files of category `name` so it lists `articles`, then
+    /// `list_categories.json` to match, for curating categories after a run.
+    /// Article bodies, other categories and the `article_categories` lookups
+    /// are left as they are. Empty `articles` removes the category.
+    pub fn regenerate_category(
+        &mut self,
+        name: &str,
+        articles: Vec<String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut names: BTreeSet<String> = self.categories.keys().cloned().collect();
+        if let Some(list_json) = self.read_output(layout::LIST_CATEGORIES_PATH)? {
+            names.extend(serde_json::from_value::<Vec<String>>(
+                parse_tool_payload(&list_json)?["categories"].take(),
+            )?);
+        }
+
+        self.remove_output(layout::category_path(name))?;
+        self.remove_output(format!("tools/categories/{name}"))?;
+
+        if articles.is_empty() {
+            names.remove(name);
+            self.categories.remove(name);
+        } else {
+            names.insert(name.to_string());
+            self.write_category(name, &articles)?;
+            self.categories.insert(name.to_string(), articles);
+        }
+
+        let names: Vec<&String> = names.iter().collect();
+        self.write_category_list(&names)
+    }
+
+    /// Writes `tools/list_categories.json` in `category_order`, adding a
+    /// `descriptions` map for the categories the categorizer describes.
+    fn write_category_list(&self, names: &[&String]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut names = names.to_vec();
+        match self.category_order {
+            CategoryOrder::Alphabetical => names.sort(),
+            CategoryOrder::BySize => {
+                let mut sized = Vec::with_capacity(names.len());
+                for name in names {
+                    sized.push((std::cmp::Reverse(self.category_size(name)?), name));
+                }
+                sized.sort();
+                names = sized.into_iter().map(|(_, name)| name).collect();
+            }
+        }
 
-        // Generate categories using the same logic as streaming mode
-        let category_names: Vec<&String> = self.categories.keys().collect();
-        let categories_response = serde_json::json!({
-            "categories": category_names
+        let mut categories_response = serde_json::json!({
+            "categories": names
         });
+        let descriptions: BTreeMap<&String, String> = names
+            .iter()
+            .filter_map(|&name| Some((name, self.categorizer.describe(name)?)))
+            .collect();
+        if !descriptions.is_empty() {
+            categories_response["descriptions"] = serde_json::to_value(descriptions)?;
+        }
 
         let response = ToolResponse {
             content: vec![ToolContent {
                 content_type: "text".to_string(),
                 text: serde_json::to_string_pretty(&categories_response)?,
+                mime_type: None,
             }],
         };
 
         let response_json = serde_json::to_string_pretty(&response)?;
-        let mut file = File::create(self.output_dir.join("tools/list_categories.json"))?;
-        file.write_all(response_json.as_bytes())?;
+        self.write_output(layout::LIST_CATEGORIES_PATH, response_json)?;
+        Ok(())
+    }
+
+    /// Number of titles in category `name`, read back from its file when it
+    /// was written by an earlier run.
+    fn category_size(&self, name: &str) -> Result<usize, Box<dyn std::error::Error>> {
+        if let Some(titles) = self.categories.get(name) {
+            return Ok(titles.len());
+        }
+        let Some(category_json) = self.read_output(layout::category_path(name))? else {
+            return Ok(0);
+        };
+        Ok(parse_tool_payload(&category_json)?["count"]
+            .as_u64()
+            .unwrap_or(0) as usize)
+    }
 
-        // Generate individual category files
+    /// Writes `tools/categories/<name>.json` for every non-empty category.
+    fn generate_category_files(&self) -> Result<(), Box<dyn std::error::Error>> {
         for (category, articles) in &self.categories {
+            self.check_cancelled()?;
             if !articles.is_empty() {
-                let category_response = serde_json::json!({
-                    "category": category,
-                    "articles": articles,
-                    "count": articles.len()
+                self.write_category(category, articles)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes `tools/categories/<category>.json`. When `max_category_entries`
+    /// is set and exceeded, the primary file keeps the first N titles and the
+    /// rest go to `tools/categories/<category>/<page>.json`, starting at page 2.
+    /// With `article_metadata`, each page also lists its members' short
+    /// descriptions and lengths under `entries`.
+    fn write_category(
+        &self,
+        category: &str,
+        articles: &[String],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let per_page = self.max_category_entries.unwrap_or(articles.len()).max(1);
+        let total_pages = articles.len().div_ceil(per_page);
+
+        for (index, chunk) in articles.chunks(per_page).enumerate() {
+            let page = index + 1;
+            let mut category_response = serde_json::json!({
+                "category": category,
+                "articles": chunk,
+                "count": articles.len()
+            });
+            if self.article_metadata {
+                category_response["entries"] = self.category_entries(chunk)?.into();
+            }
+            if total_pages > 1 {
+                category_response["page"] = page.into();
+                category_response["total_pages"] = total_pages.into();
+                if page < total_pages {
+                    category_response["truncated"] = true.into();
+                    category_response["note"] = format!(
+                        "Truncated to {} of {} articles, see page {}: tools/categories/{}/{}.json",
+                        chunk.len(),
+                        articles.len(),
+                        page + 1,
+                        category,
+                        page + 1
+                    )
+                    .into();
+                }
+            }
+
+            let response = ToolResponse {
+                content: vec![ToolContent {
+                    content_type: "text".to_string(),
+                    text: serde_json::to_string_pretty(&category_response)?,
+                    mime_type: None,
+                }],
+            };
+
+            let path = if page == 1 {
+                layout::category_path(category)
+            } else {
+                layout::category_page_path(category, page)
+            };
+            self.write_output(path, serde_json::to_string_pretty(&response)?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Title, short description and length of each of `titles` that is in the
+    /// parsed corpus, for the `entries` of a category page.
+    fn category_entries(
+        &self,
+        titles: &[String],
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let mut entries = Vec::with_capacity(titles.len());
+        for title in titles {
+            if let Some(article) = self.articles.get(title)? {
+                entries.push(serde_json::json!({
+                    "title": title,
+                    "short_description": article.short_description,
+                    "length": article.content.chars().count()
+                }));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// The configured server name, or one derived from the topic filter and
+    /// the language's display name, e.g. "Wikipedia (Español) StaticMCP".
+    fn resolve_server_name(&self, topic_filter: &Option<TopicFilter>) -> String {
+        if let Some(name) = &self.server_name {
+            return name.clone();
+        }
+        match topic_filter {
+            Some(filter) => filter.server_name(&self.language),
+            None => match crate::filters::language_display_name(&self.language) {
+                Some(label) => format!("Wikipedia ({label}) StaticMCP"),
+                None => format!("Wikipedia {} StaticMCP", self.language.to_uppercase()),
+            },
+        }
+    }
+
+    fn tool_enabled(&self, tool: ToolKind) -> bool {
+        self.enabled_tools.contains(&tool)
+    }
+
+    /// Drops the manifest entries of built-in tools that aren't enabled.
+    fn retain_enabled_tools(&self, tools: &mut Vec<Tool>) {
+        tools.retain(|tool| {
+            ToolKind::ALL
+                .iter()
+                .find(|kind| kind.name() == tool.name)
+                .is_none_or(|kind| self.tool_enabled(*kind))
+        });
+    }
+
+    #[cfg(feature = "chrono")]
+    fn generation_timestamp(&self) -> String {
+        self.generated_at
+            .clone()
+            .unwrap_or_else(|| chrono::Utc::now().into())
+            .as_str()
+            .to_string()
+    }
+
+    /// Without a clock, the timestamp is whatever was injected through `generated_at`.
+    #[cfg(not(feature = "chrono"))]
+    fn generation_timestamp(&self) -> String {
+        self.generated_at
+            .as_ref()
+            .map_or("unknown", Timestamp::as_str)
+            .to_string()
+    }
+
+    /// Counts internal links across all articles and how many of them point to
+    /// titles that are neither an article nor a redirect in this corpus.
+    fn link_stats(&self) -> Result<(usize, usize), Box<dyn std::error::Error>> {
+        let mut total = 0;
+        let mut dangling = 0;
+        self.articles.for_each(|article| {
+            for link in &article.links {
+                total += 1;
+                if !self.articles.contains_key(link) && !self.redirects.contains_key(link) {
+                    dangling += 1;
+                }
+            }
+            Ok(())
+        })?;
+        Ok((total, dangling))
+    }
+
+    /// Definitions of the tools this generator advertises in `mcp.json`: the
+    /// enabled built-in tools plus `search` and `recent` when they are on.
+    pub fn builtin_tools(&self) -> Vec<Tool> {
+        let mut get_article_description = format!(
+            "Get the full content of a specific Wikipedia article ({})",
+            self.article_mime()
+        );
+        if self.shard_depth > 0 {
+            get_article_description.push_str(&format!(
+                ". Files are sharded {depth} level(s) deep, each directory named after the next {depth} character(s) of the filename",
+                depth = self.shard_depth
+            ));
+        }
+        let mut tools = vec![
+            Tool {
+                name: "get_article".to_string(),
+                description: get_article_description,
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "title": {
+                            "type": "string",
+                            "description": "Article title"
+                        }
+                    },
+                    "required": ["title"]
+                }),
+            },
+            self.get_article_by_id_tool(),
+            Tool {
+                name: "list_articles".to_string(),
+                description: "List available Wikipedia articles with pagination".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "page": {
+                            "type": "integer",
+                            "description": "Page number (1-based, default: 1)",
+                            "minimum": 1
+                        }
+                    },
+                    "required": []
+                }),
+            },
+            Tool {
+                name: "list_categories".to_string(),
+                description: "List available article categories".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {},
+                    "required": []
+                }),
+            },
+            Tool {
+                name: "categories".to_string(),
+                description: "Get articles from a specific category".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "category": {
+                            "type": "string",
+                            "description": "Category name"
+                        }
+                    },
+                    "required": ["category"]
+                }),
+            },
+            Tool {
+                name: "article_categories".to_string(),
+                description: "Get the categories a specific article belongs to".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "title": {
+                            "type": "string",
+                            "description": "Article title"
+                        }
+                    },
+                    "required": ["title"]
+                }),
+            },
+        ];
+
+        if self.search_index {
+            tools.push(self.search_tool());
+        }
+        if self.emit_summaries {
+            let length = match self.summary_sentences {
+                Some(count) => format!("its first {count} sentence(s)"),
+                None => "its lead section".to_string(),
+            };
+            tools.push(Tool {
+                name: "get_summary".to_string(),
+                description: format!(
+                    "Get a short summary of a specific Wikipedia article: {length} ({})",
+                    self.article_mime()
+                ),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "title": {
+                            "type": "string",
+                            "description": "Article title"
+                        }
+                    },
+                    "required": ["title"]
+                }),
+            });
+        }
+        if let Some(chunking) = self.chunking {
+            tools.push(Tool {
+                name: "chunks".to_string(),
+                description: format!(
+                    "Get a Wikipedia article as overlapping chunks of up to {} words, each repeating the last {} of the one before; without a chunk number, how many chunks there are",
+                    chunking.size, chunking.overlap
+                ),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "title": {
+                            "type": "string",
+                            "description": "Article title"
+                        },
+                        "chunk": {
+                            "type": "integer",
+                            "description": "Chunk number (1-based)",
+                            "minimum": 1
+                        }
+                    },
+                    "required": ["title"]
+                }),
+            });
+        }
+        if self.emit_recent.is_some() {
+            tools.push(Tool {
+                name: "recent".to_string(),
+                description: "List the most recently edited articles, newest first".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {},
+                    "required": []
+                }),
+            });
+        }
+        self.retain_enabled_tools(&mut tools);
+        tools
+    }
+
+    fn get_article_by_id_tool(&self) -> Tool {
+        Tool {
+            name: "get_article_by_id".to_string(),
+            description: format!(
+                "Get the full content of a Wikipedia article by its page id ({})",
+                self.article_mime()
+            ),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "integer",
+                        "description": "Wikipedia page id",
+                        "minimum": 1
+                    }
+                },
+                "required": ["id"]
+            }),
+        }
+    }
+
+    fn search_tool(&self) -> Tool {
+        Tool {
+            name: "search".to_string(),
+            description: "Find articles whose title contains a search term".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Single search term"
+                    },
+                    "page": {
+                        "type": "integer",
+                        "description": "Results page number (1-based, default: 1)",
+                        "minimum": 1
+                    }
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    /// Writes the results for each title token produced by the configured
+    /// tokenizer, paginated like `list_articles`: every page goes to
+    /// `tools/search/<token>/<page>.json`, and `tools/search/<token>.json`
+    /// repeats the first page.
+    fn generate_search_index<'a>(
+        &self,
+        titles: impl Iterator<Item = &'a String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut index: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+        for title in titles {
+            for token in self.tokenizer.tokenize(title) {
+                index.entry(token).or_default().insert(title.as_str());
+            }
+        }
+
+        let mut by_filename: BTreeMap<String, (String, BTreeSet<&str>)> = BTreeMap::new();
+        for (token, titles) in index {
+            let filename = crate::filename_encoding::encode_staticmcp_filename(&token);
+            by_filename
+                .entry(filename)
+                .or_insert_with(|| (token, BTreeSet::new()))
+                .1
+                .extend(titles);
+        }
+
+        for (filename, (query, results)) in by_filename {
+            let results: Vec<&str> = results.into_iter().collect();
+            let total_pages = results.len().div_ceil(ARTICLES_PER_PAGE);
+
+            for (index, chunk) in results.chunks(ARTICLES_PER_PAGE).enumerate() {
+                let page = index + 1;
+                let search_response = serde_json::json!({
+                    "query": query,
+                    "results": chunk,
+                    "count": results.len(),
+                    "pagination": {
+                        "current_page": page,
+                        "total_pages": total_pages,
+                        "per_page": ARTICLES_PER_PAGE,
+                        "total_results": results.len()
+                    }
                 });
 
                 let response = ToolResponse {
                     content: vec![ToolContent {
                         content_type: "text".to_string(),
-                        text: serde_json::to_string_pretty(&category_response)?,
+                        text: serde_json::to_string_pretty(&search_response)?,
+                        mime_type: None,
                     }],
                 };
 
                 let response_json = serde_json::to_string_pretty(&response)?;
-                let mut file = File::create(
-                    self.output_dir
-                        .join(format!("tools/categories/{category}.json")),
+                if page == 1 {
+                    self.write_output(format!("tools/search/{filename}.json"), &response_json)?;
+                }
+                self.write_output(
+                    format!("tools/search/{filename}/{page}.json"),
+                    response_json,
                 )?;
-                file.write_all(response_json.as_bytes())?;
             }
         }
 
         Ok(())
     }
+
+    /// Writes the article → categories lookup, the inverse of `self.categories`.
+    /// Titles that encode to the same filename share one file.
+    fn generate_article_categories(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut by_title: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for (category, titles) in &self.categories {
+            for title in titles {
+                by_title
+                    .entry(title.as_str())
+                    .or_default()
+                    .insert(category.as_str());
+            }
+        }
+
+        let mut by_filename: BTreeMap<String, Vec<serde_json::Value>> = BTreeMap::new();
+        for (title, categories) in by_title {
+            by_filename
+                .entry(crate::filename_encoding::encode_staticmcp_filename(title))
+                .or_default()
+                .push(serde_json::json!({
+                    "title": title,
+                    "categories": categories
+                }));
+        }
+
+        for (filename, articles) in by_filename {
+            let response = ToolResponse {
+                content: vec![ToolContent {
+                    content_type: "text".to_string(),
+                    text: serde_json::to_string_pretty(&serde_json::json!({
+                        "articles": articles
+                    }))?,
+                    mime_type: None,
+                }],
+            };
+
+            let response_json = serde_json::to_string_pretty(&response)?;
+            self.write_output(
+                format!("tools/article_categories/{filename}.json"),
+                response_json,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs `categorizer` over every article, returning category name → titles.
+pub(crate) fn categorize_articles<C: ArticleCategorizer>(
+    articles: &std::collections::HashMap<String, Article>,
+    categorizer: &C,
+) -> std::collections::HashMap<String, Vec<String>> {
+    let mut categories: std::collections::HashMap<String, Vec<String>> =
+        std::collections::HashMap::new();
+    for (title, article) in articles {
+        let category_names = categorizer.categorize(title, &article.content);
+        for category in category_names {
+            categories.entry(category).or_default().push(title.clone());
+        }
+    }
+    categories
+}
+
+/// Summarizes article content lengths (in characters): min/max/mean and a
+/// histogram over `<1k`, `1k-5k`, `5k-20k` and `>20k` buckets.
+fn content_length_stats(lengths: &[usize]) -> serde_json::Value {
+    let mut histogram = [0usize; 4];
+    for &length in lengths {
+        let bucket = match length {
+            0..1_000 => 0,
+            1_000..5_000 => 1,
+            5_000..=20_000 => 2,
+            _ => 3,
+        };
+        histogram[bucket] += 1;
+    }
+
+    let mean = if lengths.is_empty() {
+        0.0
+    } else {
+        lengths.iter().sum::<usize>() as f64 / lengths.len() as f64
+    };
+
+    serde_json::json!({
+        "min": lengths.iter().min().copied().unwrap_or(0),
+        "max": lengths.iter().max().copied().unwrap_or(0),
+        "mean": mean,
+        "histogram": {
+            "<1k": histogram[0],
+            "1k-5k": histogram[1],
+            "5k-20k": histogram[2],
+            ">20k": histogram[3]
+        }
+    })
+}
+
+/// Filename of the `n`th `__disambig_` variant of `base_filename`, shortened to
+/// fit the filename length limit.
+fn disambig_filename(base_filename: &str, n: usize) -> String {
+    crate::filename_encoding::encode_variant_filename(base_filename, &format!("__disambig_{n}"))
+}
+
+/// An article's interlanguage links as a JSON object from language code to title.
+fn langlinks_map(langlinks: &[(String, String)]) -> serde_json::Value {
+    langlinks
+        .iter()
+        .map(|(language, title)| (language.clone(), title.as_str().into()))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+/// `text` without a leading byte order mark and with `\r\n` and lone `\r`
+/// line endings turned into `\n`.
+fn lf_only(text: String) -> String {
+    let stripped = text.strip_prefix('\u{feff}').unwrap_or(&text);
+    if stripped.len() == text.len() && !text.contains('\r') {
+        return text;
+    }
+    stripped.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// `lf_only` for the raw bytes of an output file, so every file the generator
+/// writes is BOM-free and LF-only whatever produced it.
+fn lf_only_bytes(bytes: &[u8]) -> std::borrow::Cow<'_, [u8]> {
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    if !bytes.contains(&b'\r') {
+        return std::borrow::Cow::Borrowed(bytes);
+    }
+    let mut normalized = Vec::with_capacity(bytes.len());
+    let mut iter = bytes.iter().peekable();
+    while let Some(&byte) = iter.next() {
+        if byte == b'\r' {
+            iter.next_if_eq(&&b'\n');
+            normalized.push(b'\n');
+        } else {
+            normalized.push(byte);
+        }
+    }
+    std::borrow::Cow::Owned(normalized)
+}
+
+/// The title of a `## Heading` or `== Heading ==` line, the section starts
+/// `chunk_articles` prefers to break before.
+fn section_heading(line: &str) -> Option<&str> {
+    if let Some(title) = line.strip_prefix("## ") {
+        return Some(title.trim());
+    }
+    let title = line.strip_prefix("==")?.strip_suffix("==")?;
+    (!title.starts_with('=') && !title.trim().is_empty()).then(|| title.trim())
+}
+
+/// Abbreviations whose trailing `.` doesn't end a sentence, lowercased
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "mt", "vs", "etc", "e.g", "i.e", "approx",
+    "ca", "c", "no", "vol", "fig", "inc", "ltd", "co", "gen", "col", "lt", "sgt", "capt", "rev",
+    "jan", "feb", "mar", "apr", "jun", "jul", "aug", "sep", "sept", "oct", "nov", "dec", "u.s",
+];
+
+/// The text of `content` before its first `##` or `== ==` heading, or
+/// before a `---` rule such as the one ending the first of merged articles.
+fn lead_section(content: &str) -> &str {
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if section_heading(line.trim()).is_some() || line.trim() == "---" {
+            break;
+        }
+        offset += line.len();
+    }
+    &content[..offset]
+}
+
+/// The first `count` sentences of `text`, or all of it when it has fewer.
+fn leading_sentences(text: &str, count: usize) -> &str {
+    match sentence_ends(text).get(count.max(1) - 1) {
+        Some(&end) => &text[..end],
+        None => text,
+    }
+}
+
+/// Byte offsets just past the end of each sentence of `text`. A sentence
+/// ends at `.`, `!` or `?` (and any closing quotes or brackets) followed by
+/// whitespace or the end of the text, unless the `.` follows an abbreviation
+/// or a single-letter initial.
+fn sentence_ends(text: &str) -> Vec<usize> {
+    let mut ends = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if !matches!(c, '.' | '!' | '?') {
+            continue;
+        }
+        // Closing quotes and brackets belong to the sentence they end
+        let mut end = i + c.len_utf8();
+        while let Some(&(j, next)) = chars.peek()
+            && matches!(next, '"' | '\'' | ')' | ']' | '”' | '’')
+        {
+            end = j + next.len_utf8();
+            chars.next();
+        }
+        if !chars.peek().is_none_or(|&(_, next)| next.is_whitespace()) {
+            continue;
+        }
+        if c == '.' {
+            let word = text[..i]
+                .rsplit(|c: char| c.is_whitespace() || c == '(')
+                .next()
+                .unwrap_or_default();
+            let is_initial = word.chars().count() == 1 && word.chars().all(char::is_uppercase);
+            if is_initial || ABBREVIATIONS.contains(&word.to_lowercase().as_str()) {
+                continue;
+            }
+        }
+        ends.push(end);
+    }
+    ends
+}
+
+/// A window of `chunk_windows`, as byte and character ranges of the content
+struct ChunkWindow {
+    bytes: std::ops::Range<usize>,
+    chars: std::ops::Range<usize>,
+}
+
+/// Splits `content` into windows of at most `size` whitespace-separated
+/// tokens, each starting `overlap` tokens before the previous one ended
+/// (`overlap < size`). A window is cut back to end on a sentence when that
+/// still moves it past the overlap.
+fn chunk_windows(content: &str, size: usize, overlap: usize) -> Vec<ChunkWindow> {
+    // (byte range, char range) of each token
+    let mut tokens: Vec<(std::ops::Range<usize>, std::ops::Range<usize>)> = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+    for (char_index, (byte_index, c)) in content.char_indices().enumerate() {
+        match (c.is_whitespace(), current) {
+            (false, None) => current = Some((byte_index, char_index)),
+            (true, Some((byte_start, char_start))) => {
+                tokens.push((byte_start..byte_index, char_start..char_index));
+                current = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((byte_start, char_start)) = current {
+        tokens.push((
+            byte_start..content.len(),
+            char_start..content.chars().count(),
+        ));
+    }
+
+    let sentence_ends = sentence_ends(content);
+    let ends_sentence: Vec<bool> = tokens
+        .iter()
+        .map(|(bytes, _)| sentence_ends.binary_search(&bytes.end).is_ok())
+        .collect();
+
+    let mut windows = Vec::new();
+    let mut start = 0;
+    while start < tokens.len() {
+        let mut end = (start + size).min(tokens.len());
+        if end < tokens.len()
+            && let Some(sentence_end) = (start + overlap + 1..=end)
+                .rev()
+                .find(|&i| ends_sentence[i - 1])
+        {
+            end = sentence_end;
+        }
+        windows.push(ChunkWindow {
+            bytes: tokens[start].0.start..tokens[end - 1].0.end,
+            chars: tokens[start].1.start..tokens[end - 1].1.end,
+        });
+        if end == tokens.len() {
+            break;
+        }
+        start = end - overlap;
+    }
+    windows
+}
+
+/// Splits `body` into consecutive parts of at most `max_chars` characters
+/// that concatenate back to it: whole sections where they fit, else whole
+/// lines, else runs of characters.
+fn chunk_body(body: &str, max_chars: usize) -> Vec<&str> {
+    // Level 0 splits before section headings, 1 after each line, 2 between
+    // characters; a piece too long for one part is split at the next level
+    fn split<'a>(text: &'a str, level: usize, max_chars: usize, pieces: &mut Vec<&'a str>) {
+        let parts: Vec<&str> = match level {
+            0 => {
+                let mut parts = Vec::new();
+                let (mut start, mut offset) = (0, 0);
+                for line in text.split_inclusive('\n') {
+                    if offset > start && section_heading(line.trim()).is_some() {
+                        parts.push(&text[start..offset]);
+                        start = offset;
+                    }
+                    offset += line.len();
+                }
+                parts.push(&text[start..]);
+                parts
+            }
+            1 => text.split_inclusive('\n').collect(),
+            _ => text
+                .char_indices()
+                .map(|(i, c)| &text[i..i + c.len_utf8()])
+                .collect(),
+        };
+        for part in parts {
+            if level < 2 && part.chars().count() > max_chars {
+                split(part, level + 1, max_chars, pieces);
+            } else {
+                pieces.push(part);
+            }
+        }
+    }
+
+    let mut pieces = Vec::new();
+    split(body, 0, max_chars, &mut pieces);
+
+    // Pieces are consecutive slices of `body`, so a part is the span from its first to its last
+    let mut chunks = Vec::new();
+    let (mut start, mut end, mut chars) = (0, 0, 0);
+    for piece in pieces {
+        let piece_chars = piece.chars().count();
+        if chars + piece_chars > max_chars && end > start {
+            chunks.push(&body[start..end]);
+            start = end;
+            chars = 0;
+        }
+        end += piece.len();
+        chars += piece_chars;
+    }
+    if end > start {
+        chunks.push(&body[start..end]);
+    }
+    chunks
+}
+
+/// The `text/x-wiki` item `include_raw` added to a `get_article` response, if any
+fn raw_wikitext(response: &ToolResponse) -> Option<&str> {
+    response
+        .content
+        .iter()
+        .find(|content| content.mime_type.as_deref() == Some(RAW_WIKITEXT_MIME))
+        .map(|content| content.text.as_str())
+}
+
+/// Joins a rendered header and body with a blank line, or returns the body
+/// alone when there is no header.
+fn join_header(header: &str, body: &str) -> String {
+    if header.is_empty() {
+        body.to_string()
+    } else {
+        format!("{header}\n\n{body}")
+    }
+}
+
+/// Reads category → titles back from the `tools/categories/` files in
+/// `sink`, following overflow pages. Empty when there is no category list.
+pub(crate) fn read_categories(
+    sink: &dyn OutputSink,
+) -> Result<std::collections::HashMap<String, Vec<String>>, Box<dyn std::error::Error>> {
+    let read = |path: &str| -> Result<Option<String>, Box<dyn std::error::Error>> {
+        match sink.read(path)? {
+            Some(bytes) => Ok(Some(String::from_utf8(bytes)?)),
+            None => Ok(None),
+        }
+    };
+
+    let mut categories = std::collections::HashMap::new();
+    let Some(list_json) = read(layout::LIST_CATEGORIES_PATH)? else {
+        return Ok(categories);
+    };
+
+    let names: Vec<String> =
+        serde_json::from_value(parse_tool_payload(&list_json)?["categories"].take())?;
+    for name in names {
+        let Some(primary_json) = read(&layout::category_path(&name))? else {
+            continue;
+        };
+        let mut payload = parse_tool_payload(&primary_json)?;
+        let total_pages = payload["total_pages"].as_u64().unwrap_or(1);
+        let mut titles: Vec<String> = serde_json::from_value(payload["articles"].take())?;
+        for page in 2..=total_pages {
+            let overflow_json = read(&layout::category_page_path(&name, page as usize))?
+                .ok_or_else(|| format!("Missing page {page} of category {name}"))?;
+            let mut overflow = parse_tool_payload(&overflow_json)?;
+            titles.extend(serde_json::from_value::<Vec<String>>(
+                overflow["articles"].take(),
+            )?);
+        }
+        categories.insert(name, titles);
+    }
+    Ok(categories)
+}
+
+/// Parses a `ToolResponse` file and returns the JSON carried in its text.
+fn parse_tool_payload(json: &str) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let response: ToolResponse = serde_json::from_str(json)?;
+    let text = response
+        .content
+        .first()
+        .map(|content| content.text.as_str())
+        .unwrap_or("null");
+    Ok(serde_json::from_str(text)?)
+}
+
+fn article_index_resource() -> Resource {
+    Resource {
+        uri: "wikipedia://article_index".to_string(),
+        name: "Article Index".to_string(),
+        description: "Maps article titles to their content-addressed get_article filenames"
+            .to_string(),
+        mime_type: "application/json".to_string(),
+    }
+}
+
+/// Drops every word (run of alphanumerics) of `text` whose lowercase form is
+/// in `stop_words`, along with one space next to it so no gaps are left.
+/// Punctuation and line breaks stay where they were.
+fn strip_stop_words(text: &str, stop_words: &std::collections::HashSet<String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if !c.is_alphanumeric() {
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        let end = rest
+            .find(|c: char| !c.is_alphanumeric())
+            .unwrap_or(rest.len());
+        let (word, after) = rest.split_at(end);
+        rest = after;
+        if !stop_words.contains(&word.to_lowercase()) {
+            out.push_str(word);
+        } else if let Some(after_space) = rest.strip_prefix(' ')
+            && (out.is_empty() || out.ends_with([' ', '\n']))
+        {
+            rest = after_space;
+        } else if !rest.starts_with(' ') && out.ends_with(' ') {
+            out.pop();
+        }
+    }
+    out
+}
+
+fn dangling_ratio(total: usize, dangling: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        dangling as f64 / total as f64
+    }
 }
@@ -0,0 +1,108 @@
+use crate::filename_encoding::encode_staticmcp_filename;
+
+/// The paginated `list_articles` overview, relative to the output directory
+pub const LIST_ARTICLES_PATH: &str = "tools/list_articles.json";
+
+/// The `list_categories` response, relative to the output directory
+pub const LIST_CATEGORIES_PATH: &str = "tools/list_categories.json";
+
+/// The `get_article` file for `title`, e.g. `tools/get_article/roman_empire.json`.
+/// Titles that collided with another are served from the file listed for them
+/// in `resources/disambiguation.json` instead.
+pub fn article_path(title: &str) -> String {
+    sharded_article_path(title, 0)
+}
+
+/// The `get_article` file for `title` as written with `shard_depth(depth)`
+pub fn sharded_article_path(title: &str, depth: usize) -> String {
+    sharded_article_file_path(&encode_staticmcp_filename(title), depth)
+}
+
+/// The `get_article` file named `filename` (already encoded, without `.json`)
+pub fn article_file_path(filename: &str) -> String {
+    sharded_article_file_path(filename, 0)
+}
+
+/// The `get_article` file named `filename`, nested `depth` directories deep,
+/// e.g. `tools/get_article/ro/ma/roman_empire.json` at depth 2
+pub fn sharded_article_file_path(filename: &str, depth: usize) -> String {
+    format!(
+        "tools/get_article/{}{filename}.json",
+        article_shard(filename, depth)
+    )
+}
+
+/// The shard directories of `filename` at `depth`, each followed by `/`:
+/// `depth` levels of `depth` characters taken in turn from the start of the
+/// name, padded with `_` when it runs out. Empty at depth 0.
+pub fn article_shard(filename: &str, depth: usize) -> String {
+    let mut chars = filename.chars();
+    let mut shard = String::new();
+    for _ in 0..depth {
+        for _ in 0..depth {
+            shard.push(chars.next().unwrap_or('_'));
+        }
+        shard.push('/');
+    }
+    shard
+}
+
+/// Part `part` (1-based) of the `get_article` file named `filename` when the
+/// article was split with `chunk_articles`, nested like the file at `depth`:
+/// `tools/get_article/roman_empire/2.json` at depth 0
+pub fn article_chunk_path(filename: &str, depth: usize, part: usize) -> String {
+    format!("{}/{part}.json", article_chunk_dir(filename, depth))
+}
+
+/// The directory holding the `chunk_articles` parts of the `get_article` file
+/// named `filename`, next to the file: `tools/get_article/roman_empire` at depth 0
+pub fn article_chunk_dir(filename: &str, depth: usize) -> String {
+    format!(
+        "tools/get_article/{}{filename}",
+        article_shard(filename, depth)
+    )
+}
+
+/// The `chunks` overview of the article whose `get_article` file is named `filename`
+pub fn chunks_path(filename: &str) -> String {
+    format!("tools/chunks/{filename}.json")
+}
+
+/// Chunk `index` (1-based) of the article whose `get_article` file is named `filename`
+pub fn chunk_path(filename: &str, index: usize) -> String {
+    format!("tools/chunks/{filename}/{index}.json")
+}
+
+/// The discovery document written by `emit_well_known`, at the host root
+pub const WELL_KNOWN_PATH: &str = ".well-known/mcp.json";
+
+/// The `get_summary` file of the article whose `get_article` file is named `filename`
+pub fn summary_path(filename: &str) -> String {
+    format!("tools/get_summary/{filename}.json")
+}
+
+/// The `get_article_by_id` file for page `id`
+pub fn article_by_id_path(id: u64) -> String {
+    format!("tools/get_article_by_id/{id}.json")
+}
+
+/// Page `page` (1-based) of `list_articles`
+pub fn list_articles_page_path(page: usize) -> String {
+    padded_list_articles_page_path(page, 0)
+}
+
+/// Page `page` of `list_articles` with its number zero-padded to `digits`,
+/// as written with `zero_pad_pages`
+pub fn padded_list_articles_page_path(page: usize, digits: usize) -> String {
+    format!("tools/list_articles/{page:0digits$}.json")
+}
+
+/// The `categories` file for category `name`, holding its first page of titles
+pub fn category_path(name: &str) -> String {
+    format!("tools/categories/{name}.json")
+}
+
+/// Overflow page `page` (2 and up) of category `name`
+pub fn category_page_path(name: &str, page: usize) -> String {
+    format!("tools/categories/{name}/{page}.json")
+}
@@ -1,14 +1,26 @@
+use std::collections::HashSet;
 use std::path::PathBuf;
 
+pub mod article_store;
 pub mod filename_encoding;
 pub mod filters;
 pub mod generator;
+pub mod layout;
+pub mod merge;
 pub mod parser;
+pub mod schemas;
+#[cfg(feature = "serve")]
+pub mod serve;
+pub mod sink;
 pub mod types;
 
-pub use filters::TopicFilter;
-pub use generator::StaticMcpGenerator;
+pub use filters::{KeywordCategorizer, TopicFilter};
+pub use generator::{StaticMcpGenerator, StaticMcpGeneratorBuilder};
+pub use merge::merge_outputs;
 pub use parser::WikipediaParser;
+#[cfg(feature = "serve")]
+pub use serve::serve;
+pub use sink::{FilesystemSink, OutputSink};
 pub use types::*;
 
 #[derive(Debug, Clone)]
@@ -16,9 +28,63 @@ pub struct Config {
     pub input_path: PathBuf,
     pub output_path: PathBuf,
     pub language: String,
+    pub language_configured: bool,
     pub max_articles: Option<usize>,
     pub topic_filter: Option<TopicFilter>,
     pub exact_matches: bool,
+    pub include_files: bool,
+    pub search_index: bool,
+    pub report_dangling_links: bool,
+    pub max_dangling_ratio: Option<f64>,
+    pub article_header: ArticleHeader,
+    pub generated_at: Option<Timestamp>,
+    pub content_mime: Option<String>,
+    pub include_namespaces: Vec<i64>,
+    pub emit_schemas: bool,
+    pub max_category_entries: Option<usize>,
+    pub content_addressed: bool,
+    pub exclude_titles: HashSet<String>,
+    pub only_titles: Option<HashSet<String>>,
+    pub categorize_during_parse: bool,
+    pub server_name: Option<String>,
+    pub case_variants: Option<CaseVariantPolicy>,
+    pub article_metadata: bool,
+    pub enabled_tools: Vec<ToolKind>,
+    pub frontmatter: bool,
+    pub content_style: ContentStyle,
+    pub spill_dir: Option<PathBuf>,
+    pub emit_recent: Option<usize>,
+    pub emit_summaries: bool,
+    pub summary_sentences: Option<usize>,
+    pub emit_hosting_meta: bool,
+    pub emit_well_known: bool,
+    pub raw_wikitext: bool,
+    pub include_raw: bool,
+    pub emit_checksums: bool,
+    pub keep_empty_articles: bool,
+    pub zero_pad_pages: bool,
+    pub sample_every: usize,
+    pub emit_see_also: bool,
+    pub require_nonempty: bool,
+    pub compat: McpCompat,
+    pub shard_depth: usize,
+    pub stop_words: Option<HashSet<String>>,
+    pub body_redirects: bool,
+    pub content_transform: Option<ContentTransform>,
+    pub category_order: CategoryOrder,
+    pub cancellation: Option<CancellationToken>,
+    pub progress_interval: usize,
+    pub on_progress: ProgressCallback,
+    pub follow_redirects: bool,
+    pub max_redirect_depth: usize,
+    pub record_excluded: bool,
+    pub max_excluded: usize,
+    pub relevance: Option<RelevanceFilter>,
+    pub id_range: Option<(u64, u64)>,
+    pub case_insensitive_titles: bool,
+    pub output_format: OutputFormat,
+    pub chunk_articles: Option<usize>,
+    pub chunking: Option<ChunkConfig>,
 }
 
 impl Config {
@@ -27,14 +93,71 @@ impl Config {
             input_path,
             output_path,
             language: "en".to_string(),
+            language_configured: false,
             max_articles: None,
             topic_filter: None,
             exact_matches: false,
+            include_files: false,
+            search_index: false,
+            report_dangling_links: false,
+            max_dangling_ratio: None,
+            article_header: ArticleHeader::default(),
+            generated_at: None,
+            content_mime: None,
+            include_namespaces: vec![0],
+            emit_schemas: false,
+            max_category_entries: None,
+            content_addressed: false,
+            exclude_titles: HashSet::new(),
+            only_titles: None,
+            categorize_during_parse: false,
+            server_name: None,
+            case_variants: None,
+            article_metadata: false,
+            enabled_tools: ToolKind::ALL.to_vec(),
+            frontmatter: false,
+            content_style: ContentStyle::default(),
+            spill_dir: None,
+            emit_recent: None,
+            emit_summaries: false,
+            summary_sentences: None,
+            emit_hosting_meta: false,
+            emit_well_known: false,
+            raw_wikitext: false,
+            include_raw: false,
+            emit_checksums: false,
+            keep_empty_articles: false,
+            zero_pad_pages: false,
+            sample_every: 1,
+            emit_see_also: false,
+            require_nonempty: false,
+            compat: McpCompat::default(),
+            shard_depth: 0,
+            stop_words: None,
+            body_redirects: true,
+            content_transform: None,
+            category_order: CategoryOrder::default(),
+            cancellation: None,
+            progress_interval: 1000,
+            on_progress: ProgressCallback::default(),
+            follow_redirects: false,
+            max_redirect_depth: 5,
+            record_excluded: false,
+            max_excluded: 10_000,
+            relevance: None,
+            id_range: None,
+            case_insensitive_titles: false,
+            output_format: OutputFormat::default(),
+            chunk_articles: None,
+            chunking: None,
         }
     }
 
+    /// Wikipedia language code. When left unset, the language stated by the
+    /// dump's `<siteinfo>` is used; when set, a differing dump language is reported.
     pub fn language(mut self, language: impl Into<String>) -> Self {
         self.language = language.into();
+        self.language_configured = true;
         self
     }
 
@@ -52,13 +175,511 @@ impl Config {
         self.exact_matches = enabled;
         self
     }
+
+    /// Keep `File:` pages as metadata in `resources/files.json` instead of dropping them.
+    pub fn include_files(mut self, enabled: bool) -> Self {
+        self.include_files = enabled;
+        self
+    }
+
+    /// Emit a title search index under `tools/search/` using the default tokenizer.
+    pub fn search_index(mut self, enabled: bool) -> Self {
+        self.search_index = enabled;
+        self
+    }
+
+    /// Report internal link totals and dangling-link counts in `stats.json`.
+    pub fn report_dangling_links(mut self, enabled: bool) -> Self {
+        self.report_dangling_links = enabled;
+        self
+    }
+
+    /// Fail generation when the dangling-link ratio exceeds `ratio` (0.0–1.0).
+    pub fn max_dangling_ratio(mut self, ratio: f64) -> Self {
+        self.max_dangling_ratio = Some(ratio);
+        self
+    }
+
+    /// Format the header written above each article body, replacing the default `# Title`.
+    pub fn article_header(
+        mut self,
+        format: impl Fn(&Article) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.article_header = ArticleHeader::new(format);
+        self
+    }
+
+    /// Pin the generation timestamp for reproducible output (default: the current time).
+    pub fn generated_at(mut self, at: impl Into<Timestamp>) -> Self {
+        self.generated_at = Some(at.into());
+        self
+    }
+
+    /// MIME type advertised for article content (default: `text/markdown`, or `text/plain` for plain text).
+    pub fn content_mime(mut self, mime: impl Into<String>) -> Self {
+        self.content_mime = Some(mime.into());
+        self
+    }
+
+    /// Namespace ids to include (default: `[0]`, the article namespace).
+    pub fn include_namespaces(mut self, namespaces: Vec<i64>) -> Self {
+        self.include_namespaces = namespaces;
+        self
+    }
+
+    /// Write JSON Schema documents for the generated files into `schemas/`.
+    pub fn emit_schemas(mut self, enabled: bool) -> Self {
+        self.emit_schemas = enabled;
+        self
+    }
+
+    /// Cap the titles in each category file, writing the remainder to overflow pages.
+    pub fn max_category_entries(mut self, max: usize) -> Self {
+        self.max_category_entries = Some(max);
+        self
+    }
+
+    /// Name article files after a hash of their contents, with a title → filename index.
+    pub fn content_addressed(mut self, enabled: bool) -> Self {
+        self.content_addressed = enabled;
+        self
+    }
+
+    /// Drop pages with these exact titles regardless of any filter.
+    pub fn exclude_titles(mut self, titles: HashSet<String>) -> Self {
+        self.exclude_titles = titles;
+        self
+    }
+
+    /// Keep only pages with these exact titles, ignoring the topic filter (default: no allow-list).
+    pub fn only_titles(mut self, titles: Option<HashSet<String>>) -> Self {
+        self.only_titles = titles;
+        self
+    }
+
+    /// Categorize articles while parsing instead of in a second pass over the corpus.
+    pub fn categorize_during_parse(mut self, enabled: bool) -> Self {
+        self.categorize_during_parse = enabled;
+        self
+    }
+
+    /// Override the manifest's server name (default: derived from the language and topic filter).
+    pub fn server_name(mut self, name: impl Into<String>) -> Self {
+        self.server_name = Some(name.into());
+        self
+    }
+
+    /// How to resolve titles that differ only by case (default: the general collision handling).
+    pub fn case_variants(mut self, policy: CaseVariantPolicy) -> Self {
+        self.case_variants = Some(policy);
+        self
+    }
+
+    /// Also write `resources/article_metadata.json` with per-article filename, length,
+    /// short description and categories, and list the same details on category pages.
+    pub fn article_metadata(mut self, enabled: bool) -> Self {
+        self.article_metadata = enabled;
+        self
+    }
+
+    /// Built-in tools to generate and advertise (default: all).
+    pub fn enabled_tools(mut self, tools: Vec<ToolKind>) -> Self {
+        self.enabled_tools = tools;
+        self
+    }
+
+    /// Prepend YAML front matter (title, id, url, categories, aliases, length) to each article.
+    pub fn frontmatter(mut self, enabled: bool) -> Self {
+        self.frontmatter = enabled;
+        self
+    }
+
+    /// Article text format: Markdown with a `# Title` heading (default) or plain text.
+    pub fn content_style(mut self, style: ContentStyle) -> Self {
+        self.content_style = style;
+        self
+    }
+
+    /// Keep parsed articles in a temporary log under `dir` instead of in memory,
+    /// reading them back during generation. Implies `categorize_during_parse`.
+    pub fn spill_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.spill_dir = Some(dir.into());
+        self
+    }
+
+    /// List this many of the most recently edited articles in `tools/recent.json`.
+    pub fn emit_recent(mut self, count: Option<usize>) -> Self {
+        self.emit_recent = count;
+        self
+    }
+
+    /// Add a `get_summary` tool serving each article's lead section (default: false).
+    pub fn emit_summaries(mut self, enabled: bool) -> Self {
+        self.emit_summaries = enabled;
+        self
+    }
+
+    /// Cut `get_summary` responses to this many leading sentences (default: the whole lead section).
+    pub fn summary_sentences(mut self, count: usize) -> Self {
+        self.summary_sentences = Some(count);
+        self
+    }
+
+    /// Write a `.well-known/mcp.json` discovery document pointing at the manifest (default: false).
+    pub fn emit_well_known(mut self, enabled: bool) -> Self {
+        self.emit_well_known = enabled;
+        self
+    }
+
+    /// Write `robots.txt` and a `_headers` caching hint file for static hosting.
+    pub fn emit_hosting_meta(mut self, enabled: bool) -> Self {
+        self.emit_hosting_meta = enabled;
+        self
+    }
+
+    /// Store and serve each article's original wikitext, skipping `clean_wikitext`.
+    pub fn raw_wikitext(mut self, enabled: bool) -> Self {
+        self.raw_wikitext = enabled;
+        self
+    }
+
+    /// Serve each article's original wikitext as a second `get_article` content item (default: false).
+    pub fn include_raw(mut self, enabled: bool) -> Self {
+        self.include_raw = enabled;
+        self
+    }
+
+    /// Write `checksums.json` mapping every generated file to the SHA-256 of its bytes.
+    pub fn emit_checksums(mut self, enabled: bool) -> Self {
+        self.emit_checksums = enabled;
+        self
+    }
+
+    /// Keep articles whose content is empty after cleaning (default: they are dropped).
+    pub fn keep_empty_articles(mut self, enabled: bool) -> Self {
+        self.keep_empty_articles = enabled;
+        self
+    }
+
+    /// Zero-pad `list_articles` page filenames (`01.json`) so they sort in order.
+    pub fn zero_pad_pages(mut self, enabled: bool) -> Self {
+        self.zero_pad_pages = enabled;
+        self
+    }
+
+    /// Keep every Nth article that passes the filters (default: 1, all of them).
+    pub fn sample_every(mut self, n: usize) -> Self {
+        self.sample_every = n;
+        self
+    }
+
+    /// Append a "See also" section linking each article to the in-corpus articles it links to.
+    pub fn emit_see_also(mut self, enabled: bool) -> Self {
+        self.emit_see_also = enabled;
+        self
+    }
+
+    /// Fail with `WikipediaError::NoArticles` instead of writing an empty tree when nothing matches.
+    pub fn require_nonempty(mut self, enabled: bool) -> Self {
+        self.require_nonempty = enabled;
+        self
+    }
+
+    /// Shape of `mcp.json`: `McpCompat::Current` (default) or a legacy client profile.
+    pub fn compat(mut self, compat: McpCompat) -> Self {
+        self.compat = compat;
+        self
+    }
+
+    /// Nest article files `depth` directories deep by filename prefix (default: 0, flat).
+    pub fn shard_depth(mut self, depth: usize) -> Self {
+        self.shard_depth = depth;
+        self
+    }
+
+    /// Strip these words, whole and case-insensitively, from article content (default: None).
+    pub fn stop_words(mut self, words: Option<HashSet<String>>) -> Self {
+        self.stop_words = words;
+        self
+    }
+
+    /// Treat pages whose text starts with `#REDIRECT [[Target]]` as redirects (default: true).
+    pub fn body_redirects(mut self, enabled: bool) -> Self {
+        self.body_redirects = enabled;
+        self
+    }
+
+    /// Rewrite each article's content, given it and the article, just before it is written (default: unchanged).
+    pub fn content_transform(
+        mut self,
+        transform: impl Fn(&str, &Article) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.content_transform = Some(ContentTransform::new(transform));
+        self
+    }
+
+    /// Order of `list_categories.json`: alphabetical (default) or largest category first.
+    pub fn category_order(mut self, order: CategoryOrder) -> Self {
+        self.category_order = order;
+        self
+    }
+
+    /// Write a `get_article` file for each redirect title, holding its target's content (default: false).
+    pub fn follow_redirects(mut self, enabled: bool) -> Self {
+        self.follow_redirects = enabled;
+        self
+    }
+
+    /// Stop parsing and generation once `token` is cancelled, with `WikipediaError::Cancelled` (default: None).
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Report progress every this many articles while parsing and generating (default: 1000).
+    pub fn progress_interval(mut self, interval: usize) -> Self {
+        self.progress_interval = interval;
+        self
+    }
+
+    /// Receive progress reports in `report` instead of having them printed (default: println).
+    pub fn on_progress(mut self, report: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        self.on_progress = ProgressCallback::new(report);
+        self
+    }
+
+    /// Follow redirect chains for at most this many hops (default: 5).
+    pub fn max_redirect_depth(mut self, depth: usize) -> Self {
+        self.max_redirect_depth = depth;
+        self
+    }
+
+    /// List the titles the filters skipped, with the reason, in `resources/excluded.json` (default: false).
+    pub fn record_excluded(mut self, enabled: bool) -> Self {
+        self.record_excluded = enabled;
+        self
+    }
+
+    /// Most titles `record_excluded` lists; the rest are only counted (default: 10,000).
+    pub fn max_excluded(mut self, max: usize) -> Self {
+        self.max_excluded = max;
+        self
+    }
+
+    /// Keep only articles `scorer` rates at least `threshold`, alongside or instead of `topic_filter`.
+    pub fn relevance_scorer(
+        mut self,
+        scorer: impl RelevanceScorer + Send + Sync + 'static,
+        threshold: f32,
+    ) -> Self {
+        self.relevance = Some(RelevanceFilter::new(scorer, threshold));
+        self
+    }
+
+    /// Treat titles differing only in case as one article, shown as first seen (default: false).
+    pub fn case_insensitive_titles(mut self, enabled: bool) -> Self {
+        self.case_insensitive_titles = enabled;
+        self
+    }
+
+    /// Write the StaticMCP tree, or stream the articles to one `articles.jsonl` (default: StaticMcp).
+    pub fn output_format(mut self, format: OutputFormat) -> Self {
+        self.output_format = format;
+        self
+    }
+
+    /// Write overlapping token windows of each article under `tools/chunks/`, for embedding (default: None).
+    pub fn chunking(mut self, chunking: Option<ChunkConfig>) -> Self {
+        self.chunking = chunking;
+        self
+    }
+
+    /// Keep only pages whose id is in `first..=last`, inclusive, to split a dump between machines (default: None).
+    pub fn id_range(mut self, range: Option<(u64, u64)>) -> Self {
+        self.id_range = range;
+        self
+    }
+
+    /// Split `get_article` bodies longer than `max_chars` into section-sized parts (default: None).
+    pub fn chunk_articles(mut self, max_chars: usize) -> Self {
+        self.chunk_articles = Some(max_chars);
+        self
+    }
 }
 
 pub fn generate<C: ArticleCategorizer>(
     config: Config,
     categorizer: C,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let mut parser = WikipediaParser::new(config.language.clone());
+) -> Result<GenerationStats, Box<dyn std::error::Error>> {
+    if config.output_format == OutputFormat::JsonLines {
+        return export_json_lines(&config, &categorizer);
+    }
+    let parser = parse_input(&config, &categorizer)?;
+    if config.require_nonempty && parser.article_count() == 0 {
+        return Err(WikipediaError::NoArticles.into());
+    }
+    generate_from_parser(config, parser, categorizer)
+}
+
+/// Generates StaticMCP files from an already-populated parser, skipping the
+/// parse step. `config.input_path` and the parse options are ignored.
+pub fn generate_from_parser<C: ArticleCategorizer>(
+    config: Config,
+    parser: WikipediaParser,
+    categorizer: C,
+) -> Result<GenerationStats, Box<dyn std::error::Error>> {
+    let exact_matches = config.exact_matches;
+    let topic_filter = config.topic_filter.clone();
+    let mut generator = StaticMcpGeneratorBuilder::new(config).build(parser, categorizer);
+    generator.generate(exact_matches, topic_filter)?;
+
+    Ok(generator.stats().clone())
+}
+
+/// Applies the articles in `parser` as a delta on top of the output tree
+/// previously generated at `config.output_path`: new articles are added,
+/// changed ones rewritten, and the manifest, stats, pagination and category
+/// files refreshed. Article files outside the delta are left untouched.
+pub fn update<C: ArticleCategorizer>(
+    config: Config,
+    parser: WikipediaParser,
+    categorizer: C,
+) -> Result<GenerationStats, Box<dyn std::error::Error>> {
+    let topic_filter = config.topic_filter.clone();
+    let mut generator = StaticMcpGeneratorBuilder::new(config).build(parser, categorizer);
+    generator.update(topic_filter)?;
+
+    Ok(generator.stats().clone())
+}
+
+/// Parses and filters the input like `generate`, then returns the in-memory
+/// corpus and its categories without writing any StaticMCP files.
+pub fn parse_only<C: ArticleCategorizer>(
+    config: Config,
+    categorizer: C,
+) -> Result<ParsedCorpus, Box<dyn std::error::Error>> {
+    let mut parser = parse_input(&config, &categorizer)?;
+    parser
+        .articles
+        .retain(|title, article| categorizer.should_include(title, &article.content));
+    if let Some(spilled) = parser.spilled.take() {
+        spilled.for_each(|article| {
+            if categorizer.should_include(&article.title, &article.content) {
+                parser
+                    .articles
+                    .insert(article.title.clone(), article.clone());
+            }
+            Ok(())
+        })?;
+    }
+    let categories = parser
+        .categories
+        .take()
+        .unwrap_or_else(|| generator::categorize_articles(&parser.articles, &categorizer));
+
+    Ok(ParsedCorpus {
+        articles: parser.articles,
+        redirects: parser.redirects,
+        files: parser.files,
+        categories,
+    })
+}
+
+/// Streams each article that passes the filters and `categorizer` to
+/// `articles.jsonl` in the output directory, one `{"title", "content", "id"}`
+/// object per line, as it is parsed. Memory use doesn't grow with the dump.
+fn export_json_lines(
+    config: &Config,
+    categorizer: &dyn ArticleCategorizer,
+) -> Result<GenerationStats, Box<dyn std::error::Error>> {
+    use std::io::Write;
+
+    let parser = configured_parser(config)?;
+    std::fs::create_dir_all(&config.output_path)?;
+    let path = config.output_path.join(JSON_LINES_FILE);
+    let mut out = std::io::BufWriter::new(std::fs::File::create(&path)?);
+
+    let mut written = 0;
+    let metrics = parser.for_each_article(
+        &config.input_path,
+        config.max_articles,
+        &config.topic_filter,
+        |article| {
+            if !categorizer.should_include(&article.title, &article.content) {
+                return Ok(());
+            }
+            let line = serde_json::json!({
+                "title": article.title,
+                "content": article.content,
+                "id": article.id
+            });
+            serde_json::to_writer(&mut out, &line)?;
+            out.write_all(b"\n")?;
+            written += 1;
+            Ok(())
+        },
+    )?;
+    out.flush()?;
+
+    if config.require_nonempty && written == 0 {
+        return Err(WikipediaError::NoArticles.into());
+    }
+    println!("Exported {written} articles to {}", path.display());
+    Ok(GenerationStats {
+        articles_written: written,
+        parse: metrics,
+        ..Default::default()
+    })
+}
+
+/// The file `OutputFormat::JsonLines` writes in the output directory
+const JSON_LINES_FILE: &str = "articles.jsonl";
+
+fn parse_input(
+    config: &Config,
+    categorizer: &dyn ArticleCategorizer,
+) -> Result<WikipediaParser, Box<dyn std::error::Error>> {
+    let mut parser = configured_parser(config)?;
+
+    if config.categorize_during_parse || config.spill_dir.is_some() {
+        parser.parse_categorized(
+            &config.input_path,
+            config.max_articles,
+            &config.topic_filter,
+            categorizer,
+        )?;
+    } else {
+        parser.parse(
+            &config.input_path,
+            config.max_articles,
+            &config.topic_filter,
+        )?;
+    }
+
+    Ok(parser)
+}
+
+/// A parser with the parse options of `config`, after checking that the
+/// input is a format it reads.
+fn configured_parser(config: &Config) -> Result<WikipediaParser, Box<dyn std::error::Error>> {
+    let parser = WikipediaParser::new(config.language.clone())
+        .include_files(config.include_files)
+        .include_namespaces(config.include_namespaces.clone())
+        .exclude_titles(config.exclude_titles.clone())
+        .only_titles(config.only_titles.clone())
+        .spill_dir(config.spill_dir.clone())
+        .raw_wikitext(config.raw_wikitext)
+        .include_raw(config.include_raw)
+        .keep_empty_articles(config.keep_empty_articles)
+        .sample_every(config.sample_every)
+        .body_redirects(config.body_redirects)
+        .relevance(config.relevance.clone())
+        .id_range(config.id_range)
+        .case_insensitive_titles(config.case_insensitive_titles)
+        .cancellation(config.cancellation.clone())
+        .progress_interval(config.progress_interval)
+        .on_progress(config.on_progress.clone())
+        .record_excluded(config.record_excluded.then_some(config.max_excluded));
 
     let extension = config
         .input_path
@@ -67,20 +688,14 @@ pub fn generate<C: ArticleCategorizer>(
         .unwrap_or("")
         .to_lowercase();
 
-    match extension.as_str() {
-        "xml" | "bz2" => {
-            parser.parse(
-                &config.input_path,
-                config.max_articles,
-                &config.topic_filter,
-            )?;
-        }
-        _ => return Err("Unsupported file format. Use .xml or .bz2 files.".into()),
+    if !matches!(extension.as_str(), "xml" | "bz2")
+        && parser::tar_compression(&config.input_path).is_none()
+    {
+        return Err(parser::UnsupportedFormat(
+            "Unsupported file format. Use .xml, .bz2, .tar or .tar.gz files.".to_string(),
+        )
+        .into());
     }
 
-    let mut generator =
-        StaticMcpGenerator::new(config.output_path, config.language, parser, categorizer);
-    generator.generate(config.exact_matches, config.topic_filter)?;
-
-    Ok(())
+    Ok(parser)
 }
@@ -0,0 +1,268 @@
+use crate::filename_encoding::encode_staticmcp_filename;
+use crate::generator::{CHUNKED_ARTICLE_PREFIX, MERGED_SECTION, read_categories};
+use crate::layout;
+use crate::parser::WikipediaParser;
+use crate::sink::{FilesystemSink, OutputSink};
+use crate::types::*;
+use crate::{Config, StaticMcpGeneratorBuilder};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Combines the trees written by earlier `generate` runs into one tree at
+/// `out`. Articles, categories, page ids and coordinates are read back from
+/// each tree in `dirs` and generated again as a single corpus, so
+/// `list_articles` is paginated over all of them, `stats.json` counts them
+/// all and there is one manifest. Titles from different trees that share a
+/// filename go through the usual collision handling. When a title appears in
+/// more than one tree, the first tree's copy is kept.
+///
+/// Article text is taken as written, so the merged tree is generated with the
+/// default options; the language is the first tree's.
+pub fn merge_outputs(
+    dirs: &[PathBuf],
+    out: PathBuf,
+) -> Result<GenerationStats, Box<dyn std::error::Error>> {
+    let mut language = None;
+    let mut articles: HashMap<String, Article> = HashMap::new();
+    let mut categories: HashMap<String, Vec<String>> = HashMap::new();
+
+    for dir in dirs {
+        let tree = Tree::open(dir)?;
+        language.get_or_insert(tree.language()?);
+
+        for (title, article) in tree.articles()? {
+            articles.entry(title).or_insert(article);
+        }
+        for (category, titles) in read_categories(&tree.sink)? {
+            categories.entry(category).or_default().extend(titles);
+        }
+    }
+    for titles in categories.values_mut() {
+        titles.sort();
+        titles.dedup();
+    }
+
+    let language = language.unwrap_or_else(|| "en".to_string());
+    let mut parser = WikipediaParser::new(language.clone());
+    parser.articles = articles;
+
+    let mut generator =
+        StaticMcpGeneratorBuilder::new(Config::new(PathBuf::new(), out).language(language))
+            .categories(categories)
+            .build(parser, NoCategorizer);
+    generator.generate(true, None)?;
+    Ok(generator.stats().clone())
+}
+
+/// One input tree of `merge_outputs`
+struct Tree {
+    dir: PathBuf,
+    sink: FilesystemSink,
+}
+
+impl Tree {
+    fn open(dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let sink = FilesystemSink::new(dir);
+        if !sink.exists("resources/articles.json")? {
+            return Err(format!("No generated output to merge in {}", dir.display()).into());
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            sink,
+        })
+    }
+
+    /// The text of the resource at `path`, or `None` if the tree doesn't have it.
+    fn resource<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+    ) -> Result<Option<T>, Box<dyn std::error::Error>> {
+        let Some(bytes) = self.sink.read(path)? else {
+            return Ok(None);
+        };
+        let response: ResourceResponse = serde_json::from_slice(&bytes)?;
+        Ok(Some(serde_json::from_str(&response.text)?))
+    }
+
+    fn language(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let stats: Option<serde_json::Value> = self.resource("resources/stats.json")?;
+        Ok(stats
+            .and_then(|stats| stats["language"].as_str().map(str::to_string))
+            .unwrap_or_else(|| "en".to_string()))
+    }
+
+    /// Every article listed in `resources/articles.json`, with its body read
+    /// back from the `get_article` file that serves it.
+    fn articles(&self) -> Result<Vec<(String, Article)>, Box<dyn std::error::Error>> {
+        let titles: Vec<String> = self
+            .resource("resources/articles.json")?
+            .unwrap_or_default();
+
+        // Collided titles are served from the file the disambiguation index names
+        let mut filenames: HashMap<String, String> = HashMap::new();
+        let groups: Vec<serde_json::Value> = self
+            .resource("resources/disambiguation.json")?
+            .unwrap_or_default();
+        for group in groups {
+            for member in group["members"].as_array().into_iter().flatten() {
+                if let (Some(title), Some(filename)) =
+                    (member["title"].as_str(), member["filename"].as_str())
+                {
+                    filenames.insert(title.to_string(), filename.to_string());
+                }
+            }
+        }
+        let mut shared: HashMap<&str, usize> = HashMap::new();
+        for filename in filenames.values() {
+            *shared.entry(filename.as_str()).or_default() += 1;
+        }
+
+        let mut ids: BTreeMap<String, u64> = BTreeMap::new();
+        if let Some(mut article_ids) =
+            self.resource::<serde_json::Value>("resources/article_ids.json")?
+        {
+            ids = serde_json::from_value(article_ids["by_title"].take())?;
+        }
+        let mut coordinates: HashMap<String, (f64, f64)> = HashMap::new();
+        let geo: Vec<serde_json::Value> = self.resource("resources/geo.json")?.unwrap_or_default();
+        for entry in geo {
+            if let (Some(title), Some(lat), Some(lon)) = (
+                entry["title"].as_str(),
+                entry["lat"].as_f64(),
+                entry["lon"].as_f64(),
+            ) {
+                coordinates.insert(title.to_string(), (lat, lon));
+            }
+        }
+
+        // Files are found by name so trees written with any `shard_depth` are read alike
+        let paths: HashSet<String> = self.sink.paths()?.into_iter().collect();
+        let mut files: HashMap<String, String> = HashMap::new();
+        for path in &paths {
+            // Parts of a chunked article sit in a directory named after its file
+            if let Some((dir, _)) = path.rsplit_once('/')
+                && paths.contains(&format!("{dir}.json"))
+            {
+                continue;
+            }
+            if let Some(name) = path.strip_prefix("tools/get_article/")
+                && let Some(stem) = name
+                    .rsplit('/')
+                    .next()
+                    .and_then(|n| n.strip_suffix(".json"))
+            {
+                files.insert(stem.to_string(), path.clone());
+            }
+        }
+
+        let mut articles = Vec::with_capacity(titles.len());
+        for title in titles {
+            let filename = filenames
+                .get(&title)
+                .cloned()
+                .unwrap_or_else(|| encode_staticmcp_filename(&title));
+            let Some(path) = files.get(&filename) else {
+                eprintln!(
+                    "Warning: {} has no get_article file for '{title}'; skipping it",
+                    self.dir.display()
+                );
+                continue;
+            };
+            let Some(text) = self.tool_text(path)? else {
+                continue;
+            };
+
+            // A split article's parts hold its body alone, without the header
+            let chunked = article_body(&text).starts_with(CHUNKED_ARTICLE_PREFIX);
+            let text = if chunked {
+                self.chunked_body(&filename, path)?
+            } else {
+                text
+            };
+            let content = if shared.get(filename.as_str()).copied().unwrap_or(0) > 1 {
+                merged_section(&text, &title)
+            } else if chunked {
+                &text
+            } else {
+                article_body(&text)
+            };
+            articles.push((
+                title.clone(),
+                Article {
+                    id: ids.get(&title).copied().unwrap_or(0),
+                    content: content.to_string(),
+                    coordinates: coordinates.get(&title).copied(),
+                    title,
+                    ..Default::default()
+                },
+            ));
+        }
+        Ok(articles)
+    }
+
+    /// The text of the `get_article` response at `path`
+    fn tool_text(&self, path: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let Some(bytes) = self.sink.read(path)? else {
+            return Ok(None);
+        };
+        let response: ToolResponse = serde_json::from_slice(&bytes)?;
+        Ok(response
+            .content
+            .into_iter()
+            .next()
+            .map(|content| content.text))
+    }
+
+    /// The body of an article written in parts by `chunk_articles`, put back
+    /// together from the parts next to its file `filename` at `path`.
+    fn chunked_body(
+        &self,
+        filename: &str,
+        path: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        // The shard directories between `tools/get_article/` and the file give its depth
+        let depth = path
+            .trim_start_matches("tools/get_article/")
+            .matches('/')
+            .count();
+        let mut body = String::new();
+        for part in 1.. {
+            let Some(text) = self.tool_text(&layout::article_chunk_path(filename, depth, part))?
+            else {
+                break;
+            };
+            body.push_str(&text);
+        }
+        Ok(body)
+    }
+}
+
+/// The body of a `get_article` text: the text without its front matter block
+/// and `# Title` header.
+fn article_body(text: &str) -> &str {
+    let mut text = text;
+    if let Some(rest) = text.strip_prefix("---\n")
+        && let Some(end) = rest.find("\n---")
+    {
+        text = rest[end + "\n---".len()..].trim_start_matches('\n');
+    }
+    if text.starts_with("# ") {
+        text = text.split_once("\n\n").map_or("", |(_, body)| body);
+    }
+    text
+}
+
+/// The body of `title` in a file that several colliding articles were merged
+/// into: the first article's body, or the `## title` section that follows it.
+fn merged_section<'a>(text: &'a str, title: &str) -> &'a str {
+    let mut sections = text.split(MERGED_SECTION);
+    let first = sections.next().unwrap_or_default();
+    for section in sections {
+        if let Some((heading, body)) = section.split_once("\n\n")
+            && heading == title
+        {
+            return body;
+        }
+    }
+    article_body(first)
+}
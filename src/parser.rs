@@ -1,18 +1,80 @@
+use crate::article_store::SpillStore;
 use crate::filters::TopicFilter;
-use crate::types::Article;
+use crate::types::{
+    Article, ArticleCategorizer, CancellationToken, ExcludedTitle, ExclusionReason, FileInfo,
+    GenerationStats, ParseMetrics, Progress, ProgressCallback, RedirectTarget, RelevanceFilter,
+    WikipediaError,
+};
+#[cfg(feature = "bzip2")]
 use bzip2::read::BzDecoder;
 use quick_xml::Reader;
-use quick_xml::events::Event;
+use quick_xml::events::{BytesStart, Event};
 use regex::Regex;
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs::File;
-use std::io::{BufReader, Read};
-use std::path::Path;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use unicode_normalization::UnicodeNormalization;
 
 pub struct WikipediaParser {
     pub language: String,
     pub articles: HashMap<String, Article>,
-    pub redirects: HashMap<String, String>,
+    pub redirects: HashMap<String, RedirectTarget>,
+    pub files: HashMap<String, FileInfo>,
+    pub include_files: bool,
+    /// Pages skipped because their title was missing or empty
+    pub invalid_titles: usize,
+    /// Namespace ids (`<ns>`) whose pages are kept; defaults to the main namespace
+    pub include_namespaces: Vec<i64>,
+    /// Normalized titles that are always dropped, whatever the filters say
+    pub exclude_titles: HashSet<String>,
+    /// Normalized titles that, when set, are the only pages kept; topic filters are ignored
+    pub only_titles: Option<HashSet<String>>,
+    /// Category name → titles, filled in when parsing with `parse_categorized`
+    pub categories: Option<HashMap<String, Vec<String>>>,
+    /// Recoverable anomalies met by `parse`, in document order
+    pub warnings: Vec<ParseWarning>,
+    /// Language code stated by the dump's `<siteinfo>`, once `parse` has read it
+    pub site_language: Option<String>,
+    /// Directory for the on-disk log that `parse` writes articles to instead of `articles`
+    pub spill_dir: Option<PathBuf>,
+    /// Articles parsed while `spill_dir` was set
+    pub spilled: Option<SpillStore>,
+    /// Keep each article's `<text>` verbatim instead of running `clean_wikitext`
+    pub raw_wikitext: bool,
+    /// Also keep each article's `<text>` in `Article::raw`, next to the cleaned content
+    pub include_raw: bool,
+    /// Keep articles whose content is empty or whitespace-only after cleaning
+    pub keep_empty_articles: bool,
+    /// Keep only every Nth article that passes the filters (1 keeps them all)
+    pub sample_every: usize,
+    /// Treat pages whose text starts with a `#REDIRECT [[Target]]` directive as redirects
+    pub body_redirects: bool,
+    /// Throughput of the `parse` calls made so far
+    pub metrics: ParseMetrics,
+    /// Articles scoring below its threshold are dropped, after any topic filter
+    pub relevance: Option<RelevanceFilter>,
+    /// When set, `parse` records up to this many skipped pages in `excluded`
+    pub record_excluded: Option<usize>,
+    /// Pages skipped by the filters, with the reason, in document order
+    pub excluded: Vec<ExcludedTitle>,
+    /// Pages skipped by the filters, including those past the `record_excluded` cap
+    pub excluded_total: usize,
+    /// Inclusive range of page ids kept; pages outside it, or without an id, are skipped
+    pub id_range: Option<(u64, u64)>,
+    /// Treat titles differing only in case as one article, the first one seen
+    pub case_insensitive_titles: bool,
+    /// Folded title → the title it was first seen as, filled with `case_insensitive_titles`
+    pub folded_titles: HashMap<String, String>,
+    /// Stops `parse` before the next page once cancelled
+    pub cancellation: Option<CancellationToken>,
+    /// Articles kept between two `on_progress` reports
+    pub progress_interval: usize,
+    /// Receives a `Progress::Parsed` report every `progress_interval` articles
+    pub on_progress: ProgressCallback,
 }
 
 impl WikipediaParser {
@@ -21,6 +83,192 @@ impl WikipediaParser {
             language,
             articles: HashMap::new(),
             redirects: HashMap::new(),
+            files: HashMap::new(),
+            include_files: false,
+            invalid_titles: 0,
+            include_namespaces: vec![0],
+            exclude_titles: HashSet::new(),
+            only_titles: None,
+            categories: None,
+            warnings: Vec::new(),
+            site_language: None,
+            spill_dir: None,
+            spilled: None,
+            raw_wikitext: false,
+            include_raw: false,
+            keep_empty_articles: false,
+            sample_every: 1,
+            body_redirects: true,
+            metrics: ParseMetrics::default(),
+            relevance: None,
+            record_excluded: None,
+            excluded: Vec::new(),
+            excluded_total: 0,
+            id_range: None,
+            case_insensitive_titles: false,
+            folded_titles: HashMap::new(),
+            cancellation: None,
+            progress_interval: 1000,
+            on_progress: ProgressCallback::default(),
+        }
+    }
+
+    /// Keep pages from these namespace ids (e.g. `10` for templates) instead of only articles.
+    pub fn include_namespaces(mut self, namespaces: Vec<i64>) -> Self {
+        self.include_namespaces = namespaces;
+        self
+    }
+
+    /// Drop pages with these exact titles (compared after normalization).
+    pub fn exclude_titles(mut self, titles: HashSet<String>) -> Self {
+        self.exclude_titles = titles.iter().map(|title| normalize_title(title)).collect();
+        self
+    }
+
+    /// Keep only pages with these exact titles (compared after normalization),
+    /// bypassing the namespace and topic filters.
+    pub fn only_titles(mut self, titles: Option<HashSet<String>>) -> Self {
+        self.only_titles =
+            titles.map(|titles| titles.iter().map(|title| normalize_title(title)).collect());
+        self
+    }
+
+    /// Write parsed articles to a temporary log in `dir` and keep only their
+    /// titles in memory. Use `parse_categorized` so categories are collected
+    /// in the same pass; the generator reads articles back as it writes them.
+    pub fn spill_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.spill_dir = dir;
+        self
+    }
+
+    /// Store article content as the unprocessed wikitext rather than cleaned text.
+    pub fn raw_wikitext(mut self, enabled: bool) -> Self {
+        self.raw_wikitext = enabled;
+        self
+    }
+
+    /// Keep each article's unprocessed wikitext in `Article::raw` as well as the cleaned content.
+    pub fn include_raw(mut self, enabled: bool) -> Self {
+        self.include_raw = enabled;
+        self
+    }
+
+    /// Keep articles with no content left after cleaning instead of dropping them.
+    pub fn keep_empty_articles(mut self, enabled: bool) -> Self {
+        self.keep_empty_articles = enabled;
+        self
+    }
+
+    /// Keep only the 1st, (n+1)th, (2n+1)th, ... article that passes the
+    /// filters, for a sample spread across the whole dump. Applies in both
+    /// `parse` and `parse_streaming`; `max_articles` counts the kept ones.
+    pub fn sample_every(mut self, n: usize) -> Self {
+        self.sample_every = n.max(1);
+        self
+    }
+
+    /// Detect redirects written only as a `#REDIRECT [[Target]]` line at the
+    /// start of the text, for exports that leave out the `<redirect>` element.
+    pub fn body_redirects(mut self, enabled: bool) -> Self {
+        self.body_redirects = enabled;
+        self
+    }
+
+    /// Keep only articles that `relevance` scores at or above its threshold,
+    /// judged on the cleaned content. Applies on top of any topic filter.
+    pub fn relevance(mut self, relevance: Option<RelevanceFilter>) -> Self {
+        self.relevance = relevance;
+        self
+    }
+
+    /// Record the title of each page the filters skip, and why, keeping at
+    /// most `max` of them. Titles rejected by the topic filter also have
+    /// their text read, to tell whether the content would have matched.
+    pub fn record_excluded(mut self, max: Option<usize>) -> Self {
+        self.record_excluded = max;
+        self
+    }
+
+    /// Keep only pages whose id lies in `first..=last`, e.g. to split one dump
+    /// between machines. Applies to redirects and file pages too.
+    pub fn id_range(mut self, range: Option<(u64, u64)>) -> Self {
+        self.id_range = range;
+        self
+    }
+
+    /// Collapse pages whose titles differ only in case (`iPhone`, `IPhone`)
+    /// into the first one parsed, which keeps its title as written. Later
+    /// case variants, and redirects between variants, are dropped before they
+    /// count towards `max_articles`; use `find_article` to look titles up
+    /// without regard to case.
+    pub fn case_insensitive_titles(mut self, enabled: bool) -> Self {
+        self.case_insensitive_titles = enabled;
+        self
+    }
+
+    /// The parsed article titled `title`, or with `case_insensitive_titles`
+    /// the one whose title matches it up to case. Spilled articles aren't searched.
+    pub fn find_article(&self, title: &str) -> Option<&Article> {
+        self.articles.get(title).or_else(|| {
+            let display = self.folded_titles.get(&fold_title(title))?;
+            self.articles.get(display)
+        })
+    }
+
+    /// Check `token` before each page, failing with `WikipediaError::Cancelled` once it is cancelled.
+    pub fn cancellation(mut self, token: Option<CancellationToken>) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// Report progress to `on_progress` every `interval` kept articles instead of every 1000.
+    pub fn progress_interval(mut self, interval: usize) -> Self {
+        self.progress_interval = interval.max(1);
+        self
+    }
+
+    /// Send progress reports to `callback` instead of printing them.
+    pub fn on_progress(mut self, callback: ProgressCallback) -> Self {
+        self.on_progress = callback;
+        self
+    }
+
+    /// Route `File:` description pages into `files` instead of discarding them.
+    pub fn include_files(mut self, enabled: bool) -> Self {
+        self.include_files = enabled;
+        self
+    }
+
+    /// Number of parsed articles, redirects excluded, including spilled ones.
+    pub fn article_count(&self) -> usize {
+        self.articles.len() + self.spilled.as_ref().map_or(0, SpillStore::len)
+    }
+
+    /// Number of parsed redirects.
+    pub fn redirect_count(&self) -> usize {
+        self.redirects.len()
+    }
+
+    /// Whether an article (not a redirect) titled `title` was parsed.
+    pub fn contains_article(&self, title: &str) -> bool {
+        self.articles.contains_key(title)
+            || self
+                .spilled
+                .as_ref()
+                .is_some_and(|spilled| spilled.contains_key(title))
+    }
+
+    /// The parsed article titled `title`, read back from disk if it was spilled.
+    pub fn get_article(
+        &self,
+        title: &str,
+    ) -> Result<Option<Cow<'_, Article>>, Box<dyn std::error::Error>> {
+        if let Some(article) = self.articles.get(title) {
+            return Ok(Some(Cow::Borrowed(article)));
+        }
+        match &self.spilled {
+            Some(spilled) => Ok(spilled.get(title)?.map(Cow::Owned)),
+            None => Ok(None),
         }
     }
 
@@ -30,118 +278,228 @@ impl WikipediaParser {
         max_articles: Option<usize>,
         topic_filter: &Option<TopicFilter>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let file = File::open(file_path)?;
-
-        let reader_box: Box<dyn Read> = if file_path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| ext.to_lowercase())
-            .as_deref()
-            == Some("bz2")
-        {
-            Box::new(BzDecoder::new(file))
-        } else {
-            Box::new(file)
-        };
-
-        let buf_reader = BufReader::new(reader_box);
-        let mut reader = Reader::from_reader(buf_reader);
-        reader.trim_text(true);
-
-        let mut buf = Vec::new();
-        let mut current_article = None::<Article>;
-        let mut current_content = String::new();
-        let mut articles_processed = 0;
-        let mut skip_content = false;
+        self.parse_with(file_path, max_articles, topic_filter, None)
+    }
 
-        loop {
-            match reader.read_event_into(&mut buf) {
-                Ok(Event::Start(ref e)) => {
-                    current_content.clear();
+    /// Like `parse`, but runs `categorizer` over each article as it is read and
+    /// stores the result in `categories`, so generation doesn't have to walk
+    /// the whole corpus a second time. Articles the categorizer vetoes through
+    /// `should_include` are dropped.
+    pub fn parse_categorized(
+        &mut self,
+        file_path: &Path,
+        max_articles: Option<usize>,
+        topic_filter: &Option<TopicFilter>,
+        categorizer: &dyn ArticleCategorizer,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.parse_with(file_path, max_articles, topic_filter, Some(categorizer))
+    }
 
-                    let tag_name = String::from_utf8_lossy(e.name().as_ref()).to_string();
-                    if tag_name == "page" {
-                        current_article = Some(Article {
-                            title: String::new(),
-                            content: String::new(),
-                            id: 0,
-                            redirect: None,
-                        });
-                        skip_content = false;
-                    }
-                }
-                Ok(Event::Text(e)) => {
-                    current_content.push_str(&e.unescape()?);
-                }
-                Ok(Event::End(ref e)) => {
-                    let tag_name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+    fn parse_with(
+        &mut self,
+        file_path: &Path,
+        max_articles: Option<usize>,
+        topic_filter: &Option<TopicFilter>,
+        categorizer: Option<&dyn ArticleCategorizer>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut articles = HashMap::new();
+        let mut redirects = HashMap::new();
+        let mut files = HashMap::new();
+        let mut categories: HashMap<String, Vec<String>> = HashMap::new();
+        let mut invalid_titles = 0;
+        let mut warnings = Vec::new();
+        let mut site_language = None;
+        let mut excluded = Vec::new();
+        let mut excluded_total = 0;
+        let max_excluded = self.record_excluded.unwrap_or(0);
+        let mut state = InputState {
+            folded_titles: std::mem::take(&mut self.folded_titles),
+            ..Default::default()
+        };
+        let mut spilled = match (self.spilled.take(), &self.spill_dir) {
+            (Some(store), _) => Some(store),
+            (None, Some(dir)) => Some(SpillStore::create(dir)?),
+            (None, None) => None,
+        };
 
-                    if let Some(ref mut article) = current_article {
-                        match tag_name.as_ref() {
-                            "title" => {
-                                article.title = current_content.clone();
-                                if !should_include_by_title(&article.title, topic_filter) {
-                                    skip_content = true;
-                                }
-                            }
-                            "id" => {
-                                if article.id == 0 {
-                                    article.id = current_content.parse().unwrap_or(0);
+        let metrics = self.read_input_pages(
+            file_path,
+            topic_filter,
+            max_articles,
+            true,
+            &mut state,
+            |page| {
+                match page {
+                    PageEvent::Article(article) => {
+                        if let Some(redirect) = &article.redirect {
+                            redirects.insert(
+                                article.title.clone(),
+                                RedirectTarget {
+                                    title: redirect.clone(),
+                                    section: article.redirect_section.clone(),
+                                },
+                            );
+                        } else {
+                            if let Some(categorizer) = categorizer {
+                                if !categorizer.should_include(&article.title, &article.content) {
+                                    return Ok(());
                                 }
-                            }
-                            "text" => {
-                                if !skip_content {
-                                    article.content = clean_wikitext(&current_content);
+                                for category in
+                                    categorizer.categorize(&article.title, &article.content)
+                                {
+                                    categories
+                                        .entry(category)
+                                        .or_default()
+                                        .push(article.title.clone());
                                 }
                             }
-                            "redirect" => {
-                                article.redirect = Some(current_content.clone());
-                            }
-                            "page" => {
-                                if let Some(article) = current_article.take()
-                                    && !skip_content
-                                    && should_include_by_content(&article, topic_filter)
+                            let previous = match &mut spilled {
+                                Some(store) => store.insert(&article)?,
+                                None => articles.insert(article.title.clone(), article),
+                            };
+                            if let Some(previous) = previous
+                                && let Some(categorizer) = categorizer
+                            {
+                                // A later copy of the page replaced this one; drop its entries
+                                for category in
+                                    categorizer.categorize(&previous.title, &previous.content)
                                 {
-                                    if let Some(redirect) = &article.redirect {
-                                        self.redirects
-                                            .insert(article.title.clone(), redirect.clone());
-                                    } else {
-                                        self.articles.insert(article.title.clone(), article);
-                                    }
-
-                                    articles_processed += 1;
-                                    if articles_processed % 1000 == 0 {
-                                        println!("Processed {articles_processed} articles...");
-                                    }
-
-                                    if let Some(max) = max_articles
-                                        && articles_processed >= max
+                                    if let Some(titles) = categories.get_mut(&category)
+                                        && let Some(pos) =
+                                            titles.iter().position(|title| *title == previous.title)
                                     {
-                                        break;
+                                        titles.remove(pos);
+                                        if titles.is_empty() {
+                                            categories.remove(&category);
+                                        }
                                     }
                                 }
-                                skip_content = false;
                             }
-                            _ => {}
                         }
                     }
-                    current_content.clear();
+                    PageEvent::File(file) => {
+                        files.insert(file.title.clone(), file);
+                    }
+                    PageEvent::InvalidTitle { .. } => invalid_titles += 1,
+                    PageEvent::Warning(warning) => warnings.push(warning),
+                    PageEvent::SiteLanguage(code) => site_language = Some(code),
+                    PageEvent::Excluded(page) => {
+                        excluded_total += 1;
+                        if self.excluded.len() + excluded.len() < max_excluded {
+                            excluded.push(page);
+                        }
+                    }
                 }
-                Ok(Event::Eof) => break,
-                Err(e) => return Err(Box::new(e)),
-                _ => {}
+                Ok(())
+            },
+        )?;
+
+        self.articles.extend(articles);
+        self.folded_titles = state.folded_titles;
+        self.spilled = spilled;
+        self.redirects.extend(redirects);
+        self.files.extend(files);
+        self.invalid_titles += invalid_titles;
+        self.warnings.extend(warnings);
+        self.excluded.extend(excluded);
+        self.excluded_total += excluded_total;
+        self.metrics.add(metrics);
+        if site_language.is_some() {
+            self.site_language = site_language;
+        }
+        if categorizer.is_some() {
+            let parsed = self.categories.get_or_insert_default();
+            for (category, titles) in categories {
+                parsed.entry(category).or_default().extend(titles);
             }
-            buf.clear();
         }
 
         println!(
             "Parsed {} articles and {} redirects",
-            self.articles.len(),
-            self.redirects.len()
+            self.article_count(),
+            self.redirect_count()
         );
         Ok(())
     }
 
+    /// Pulls the named articles out of a bz2 multistream dump without
+    /// decompressing the whole file. `index_path` is the companion
+    /// `offset:page_id:title` index (plain or `.bz2`); each stream holding a
+    /// requested title is seeked to and decompressed on its own. Titles missing
+    /// from the index are skipped.
+    pub fn extract_titles(
+        &self,
+        dump_path: &Path,
+        index_path: &Path,
+        titles: &[&str],
+    ) -> Result<Vec<Article>, Box<dyn std::error::Error>> {
+        let wanted: HashSet<String> = titles.iter().map(|title| title.nfc().collect()).collect();
+
+        let index_file = File::open(index_path)?;
+        let index_reader: Box<dyn Read> = if has_bz2_extension(index_path) {
+            bz2_reader(index_file)?
+        } else {
+            Box::new(index_file)
+        };
+
+        let mut offsets = BTreeSet::new();
+        for line in BufReader::new(index_reader).lines() {
+            let line = line?;
+            let mut fields = line.splitn(3, ':');
+            if let (Some(offset), Some(_id), Some(title)) =
+                (fields.next(), fields.next(), fields.next())
+                && wanted.contains(&title.nfc().collect::<String>())
+            {
+                offsets.insert(offset.parse::<u64>()?);
+            }
+        }
+
+        let mut dump = File::open(dump_path)?;
+        let mut found = Vec::new();
+        let mut state = InputState::default();
+        for offset in offsets {
+            dump.seek(SeekFrom::Start(offset))?;
+            // Each stream is a bare run of <page> elements, so give it a root
+            let stream = b"<mediawiki>"
+                .chain(bz2_reader(&mut dump)?)
+                .chain(&b"</mediawiki>"[..]);
+            let mut pages = Vec::new();
+            self.read_pages(stream, &None, None, true, &mut state, |page| {
+                if let PageEvent::Article(article) = page
+                    && wanted.contains(&article.title)
+                {
+                    pages.push(article);
+                }
+                Ok(())
+            })?;
+            found.extend(pages);
+        }
+
+        Ok(found)
+    }
+
+    /// Counts the articles (redirects excluded) that would pass the title and
+    /// topic filters, without cleaning wikitext or keeping any content. Empty
+    /// pages are judged on the raw wikitext, so pages that clean down to
+    /// nothing can make this slightly higher than a full `parse`.
+    pub fn count(
+        &self,
+        file_path: &Path,
+        topic_filter: &Option<TopicFilter>,
+    ) -> Result<usize, Box<dyn std::error::Error>> {
+        let mut count = 0;
+        let mut state = InputState::default();
+        self.read_input_pages(file_path, topic_filter, None, false, &mut state, |page| {
+            if let PageEvent::Article(article) = page
+                && article.redirect.is_none()
+            {
+                count += 1;
+            }
+            Ok(())
+        })?;
+        Ok(count)
+    }
+
     pub fn parse_streaming<F>(
         &self,
         reader: Box<dyn Read>,
@@ -152,21 +510,228 @@ impl WikipediaParser {
     where
         F: FnMut(&str, &Article) -> Result<(), Box<dyn std::error::Error>>,
     {
-        let reader_box: Box<dyn Read> = if is_bz2 {
-            Box::new(BzDecoder::new(reader))
+        self.parse_streaming_events(reader, is_bz2, topic_filter, |event| match event {
+            PageEvent::Article(article) => article_handler(&article.title, &article),
+            PageEvent::File(_)
+            | PageEvent::InvalidTitle { .. }
+            | PageEvent::Warning(_)
+            | PageEvent::SiteLanguage(_)
+            | PageEvent::Excluded(_) => Ok(()),
+        })
+    }
+
+    /// Like `parse_streaming`, but hands the handler every page event, including
+    /// `File:` pages (when enabled) and pages dropped for having no usable title.
+    pub fn parse_streaming_events<F>(
+        &self,
+        reader: Box<dyn Read>,
+        is_bz2: bool,
+        topic_filter: &Option<TopicFilter>,
+        event_handler: F,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: FnMut(PageEvent) -> Result<(), Box<dyn std::error::Error>>,
+    {
+        let reader_box: Box<dyn Read> = if is_bz2 { bz2_reader(reader)? } else { reader };
+
+        let metrics = self.read_pages(
+            reader_box,
+            topic_filter,
+            None,
+            true,
+            &mut InputState::default(),
+            event_handler,
+        )?;
+
+        println!(
+            "Streaming processing complete: {} articles processed",
+            metrics.articles
+        );
+        Ok(())
+    }
+
+    /// Streams every article that passes the filters to `handler` as it is
+    /// parsed, without keeping any of them: redirects, `File:` pages and
+    /// skipped pages are left out. Returns the parse metrics.
+    pub fn for_each_article<F>(
+        &self,
+        file_path: &Path,
+        max_articles: Option<usize>,
+        topic_filter: &Option<TopicFilter>,
+        mut handler: F,
+    ) -> Result<ParseMetrics, Box<dyn std::error::Error>>
+    where
+        F: FnMut(Article) -> Result<(), Box<dyn std::error::Error>>,
+    {
+        self.read_input_pages(
+            file_path,
+            topic_filter,
+            max_articles,
+            true,
+            &mut InputState::default(),
+            |page| match page {
+                PageEvent::Article(article) if article.redirect.is_none() => handler(article),
+                _ => Ok(()),
+            },
+        )
+    }
+
+    /// Opens `file_path` and runs `read_pages` over it: once for a plain or
+    /// `.bz2` dump, or once per `.xml` / `.xml.bz2` member of a tar archive,
+    /// with `max_articles`, `sample_every` and progress counted across members.
+    fn read_input_pages<F>(
+        &self,
+        file_path: &Path,
+        topic_filter: &Option<TopicFilter>,
+        max_articles: Option<usize>,
+        clean_text: bool,
+        state: &mut InputState,
+        on_page: F,
+    ) -> Result<ParseMetrics, Box<dyn std::error::Error>>
+    where
+        F: FnMut(PageEvent) -> Result<(), Box<dyn std::error::Error>>,
+    {
+        let file = File::open(file_path)?;
+        if let Some(gzipped) = tar_compression(file_path) {
+            let reader: Box<dyn Read> = if gzipped {
+                gzip_reader(file)?
+            } else {
+                Box::new(file)
+            };
+            return self.read_tar_pages(
+                reader,
+                topic_filter,
+                max_articles,
+                clean_text,
+                state,
+                on_page,
+            );
+        }
+
+        let reader_box: Box<dyn Read> = if has_bz2_extension(file_path) {
+            bz2_reader(file)?
         } else {
-            reader
+            Box::new(file)
         };
+        self.read_pages(
+            reader_box,
+            topic_filter,
+            max_articles,
+            clean_text,
+            state,
+            on_page,
+        )
+    }
+
+    /// Runs `read_pages` over every `.xml` / `.xml.bz2` member of the tar
+    /// archive in `reader`, in archive order. Other members are skipped.
+    #[cfg(feature = "tar")]
+    fn read_tar_pages<F>(
+        &self,
+        reader: Box<dyn Read>,
+        topic_filter: &Option<TopicFilter>,
+        max_articles: Option<usize>,
+        clean_text: bool,
+        state: &mut InputState,
+        mut on_page: F,
+    ) -> Result<ParseMetrics, Box<dyn std::error::Error>>
+    where
+        F: FnMut(PageEvent) -> Result<(), Box<dyn std::error::Error>>,
+    {
+        let mut archive = tar::Archive::new(reader);
+        let mut metrics = ParseMetrics::default();
+        for entry in archive.entries()? {
+            let entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let name = entry.path()?.into_owned();
+            let member: Box<dyn Read> = if has_bz2_extension(&name) {
+                bz2_reader(entry)?
+            } else if name
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("xml"))
+            {
+                Box::new(entry)
+            } else {
+                continue;
+            };
+
+            if max_articles.is_some_and(|max| state.processed >= max) {
+                break;
+            }
+            metrics.add(self.read_pages(
+                member,
+                topic_filter,
+                max_articles,
+                clean_text,
+                state,
+                &mut on_page,
+            )?);
+        }
+        Ok(metrics)
+    }
+
+    /// Always fails: tar support was compiled out.
+    #[cfg(not(feature = "tar"))]
+    fn read_tar_pages<F>(
+        &self,
+        _reader: Box<dyn Read>,
+        _topic_filter: &Option<TopicFilter>,
+        _max_articles: Option<usize>,
+        _clean_text: bool,
+        _state: &mut InputState,
+        _on_page: F,
+    ) -> Result<ParseMetrics, Box<dyn std::error::Error>>
+    where
+        F: FnMut(PageEvent) -> Result<(), Box<dyn std::error::Error>>,
+    {
+        Err(UnsupportedFormat(
+            "Unsupported format: reading tar archives requires the `tar` feature".to_string(),
+        )
+        .into())
+    }
 
-        let buf_reader = BufReader::new(reader_box);
+    /// Drives the XML event loop shared by `parse` and `parse_streaming`,
+    /// handing every accepted page to `on_page`. Returns the number of
+    /// articles (including redirects) that were accepted, with the bytes read
+    /// and time taken. With `clean_text`
+    /// off, article content is left as raw wikitext and links aren't extracted.
+    fn read_pages<R, F>(
+        &self,
+        reader: R,
+        topic_filter: &Option<TopicFilter>,
+        max_articles: Option<usize>,
+        clean_text: bool,
+        state: &mut InputState,
+        mut on_page: F,
+    ) -> Result<ParseMetrics, Box<dyn std::error::Error>>
+    where
+        R: Read,
+        F: FnMut(PageEvent) -> Result<(), Box<dyn std::error::Error>>,
+    {
+        let started = Instant::now();
+        let mut cleaning = Duration::ZERO;
+        let buf_reader = BufReader::new(reader);
         let mut reader = Reader::from_reader(buf_reader);
         reader.trim_text(true);
 
         let mut buf = Vec::new();
         let mut current_article = None::<Article>;
         let mut current_content = String::new();
-        let mut articles_processed = 0;
-        let mut skip_content = false;
+        let processed_before = state.processed;
+        let mut deleted_text = 0;
+        // Why the page is being skipped, once the title or namespace settle it
+        let mut exclusion = None::<ExclusionReason>;
+        let mut is_file_page = false;
+        let mut namespace_known = false;
+        let mut id_seen = false;
+        let mut flavor = None::<DumpFlavor>;
+        let mut text_elements = 0;
+        let mut empty_text_elements = 0;
+        let mut revisions = RevisionSelector::default();
+        let mut language_code_seen = false;
+        let mut title_relevant = false;
 
         loop {
             match reader.read_event_into(&mut buf) {
@@ -174,56 +739,319 @@ impl WikipediaParser {
                     current_content.clear();
 
                     let tag_name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                    let flavor = match flavor {
+                        Some(flavor) => flavor,
+                        None => *flavor.insert(DumpFlavor::detect(&tag_name)?),
+                    };
+                    let tag_name = flavor.canonical_tag(&tag_name);
+
+                    if tag_name == "redirect"
+                        && let Some(article) = current_article.as_mut()
+                        && let Some(target) = redirect_title_attribute(e)
+                    {
+                        set_redirect(article, &target);
+                    }
+                    if tag_name == "text" {
+                        text_elements += 1;
+                        revisions.text_deleted = is_deleted_text(e);
+                    }
                     if tag_name == "page" {
-                        current_article = Some(Article {
-                            title: String::new(),
-                            content: String::new(),
-                            id: 0,
-                            redirect: None,
-                        });
-                        skip_content = false;
+                        if self
+                            .cancellation
+                            .as_ref()
+                            .is_some_and(CancellationToken::is_cancelled)
+                        {
+                            return Err(WikipediaError::Cancelled {
+                                stats: GenerationStats {
+                                    parse: ParseMetrics {
+                                        articles: state.processed,
+                                        bytes: reader.buffer_position() as u64,
+                                        elapsed: started.elapsed(),
+                                        cleaning,
+                                        deleted_text,
+                                    },
+                                    ..Default::default()
+                                },
+                            }
+                            .into());
+                        }
+                        if let Some(unfinished) = current_article.take() {
+                            on_page(PageEvent::Warning(ParseWarning::new(
+                                &unfinished,
+                                ParseWarningKind::UnexpectedNesting,
+                            )))?;
+                        }
+                        current_article = Some(Article::default());
+                        id_seen = false;
+                        exclusion = None;
+                        is_file_page = false;
+                        namespace_known = false;
+                        title_relevant = false;
+                        revisions = RevisionSelector::default();
                     }
                 }
                 Ok(Event::Text(e)) => {
                     current_content.push_str(&e.unescape()?);
                 }
+                Ok(Event::Empty(ref e)) => {
+                    // Self-closing elements carry everything in their attributes
+                    let tag_name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                    let flavor = flavor.unwrap_or(DumpFlavor::PagesArticles);
+                    match flavor.canonical_tag(&tag_name) {
+                        "redirect" => {
+                            if let Some(article) = current_article.as_mut()
+                                && let Some(target) = redirect_title_attribute(e)
+                            {
+                                set_redirect(article, &target);
+                            }
+                        }
+                        "text" if is_deleted_text(e) => revisions.text_deleted = true,
+                        // Stub dumps give the size of text they leave out
+                        "text" if has_elided_text(e) => empty_text_elements += 1,
+                        "text" => {
+                            // A blanked page: its revision text really is empty
+                            text_elements += 1;
+                            if is_file_page || exclusion.is_none() {
+                                revisions.text = Some(String::new());
+                            }
+                        }
+                        _ => {}
+                    }
+                }
                 Ok(Event::End(ref e)) => {
                     let tag_name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                    let flavor = flavor.unwrap_or(DumpFlavor::PagesArticles);
+                    let tag_name = flavor.canonical_tag(&tag_name);
 
                     if let Some(ref mut article) = current_article {
-                        match tag_name.as_ref() {
+                        match tag_name {
                             "title" => {
-                                article.title = current_content.clone();
-                                if !should_include_by_title(&article.title, topic_filter) {
-                                    skip_content = true;
-                                }
+                                // NFC, so one title decomposed differently across dumps stays one key
+                                article.title =
+                                    flavor.strip_title_prefix(&current_content).nfc().collect();
+                                // The only keyword scan the page gets
+                                title_relevant = topic_filter
+                                    .as_ref()
+                                    .is_none_or(|filter| filter.is_relevant_title(&article.title));
+                                (is_file_page, exclusion) = self.classify_page(
+                                    article,
+                                    id_seen,
+                                    namespace_known,
+                                    title_relevant,
+                                );
                             }
-                            "id" => {
-                                if article.id == 0 {
-                                    article.id = current_content.parse().unwrap_or(0);
-                                }
+                            "ns" => {
+                                article.namespace = match current_content.trim().parse() {
+                                    Ok(namespace) => namespace,
+                                    Err(_) => {
+                                        on_page(PageEvent::Warning(ParseWarning::new(
+                                            article,
+                                            ParseWarningKind::InvalidNamespace(
+                                                current_content.clone(),
+                                            ),
+                                        )))?;
+                                        0
+                                    }
+                                };
+                                namespace_known = true;
+                                (is_file_page, exclusion) = self.classify_page(
+                                    article,
+                                    id_seen,
+                                    namespace_known,
+                                    title_relevant,
+                                );
                             }
-                            "text" => {
-                                if !skip_content {
-                                    article.content = clean_wikitext(&current_content);
+                            "id" if !id_seen => {
+                                // Only the page's own id; revision and contributor ids follow it
+                                id_seen = true;
+                                article.id = match current_content.trim().parse() {
+                                    Ok(id) => id,
+                                    Err(_) => {
+                                        on_page(PageEvent::Warning(ParseWarning::new(
+                                            article,
+                                            ParseWarningKind::InvalidId(current_content.clone()),
+                                        )))?;
+                                        0
+                                    }
+                                };
+                                (is_file_page, exclusion) = self.classify_page(
+                                    article,
+                                    id_seen,
+                                    namespace_known,
+                                    title_relevant,
+                                );
+                            }
+                            "timestamp" => {
+                                revisions.timestamp = Some(current_content.clone());
+                            }
+                            "text"
+                                if (is_file_page || self.reads_text(exclusion))
+                                    && !revisions.text_deleted =>
+                            {
+                                revisions.text = Some(current_content.clone());
+                            }
+                            "revision" => {
+                                if revisions.timestamp.is_none() {
+                                    on_page(PageEvent::Warning(ParseWarning::new(
+                                        article,
+                                        ParseWarningKind::MissingTimestamp,
+                                    )))?;
                                 }
+                                revisions.finish_revision();
                             }
-                            "redirect" => {
-                                article.redirect = Some(current_content.clone());
+                            "redirect" if !current_content.trim().is_empty() => {
+                                set_redirect(article, &current_content);
                             }
                             "page" => {
-                                if let Some(article) = current_article.take()
-                                    && !skip_content
-                                    && should_include_by_content(&article, topic_filter)
+                                if text_elements == 0 && empty_text_elements > 0 {
+                                    return Err(STUB_DUMP_ERROR.into());
+                                }
+
+                                revisions.finish_revision();
+                                if self.id_range.is_some() && !id_seen {
+                                    (is_file_page, exclusion) =
+                                        (false, Some(ExclusionReason::IdRange));
+                                }
+                                // A skipped page's text was only read to audit it
+                                let skipped_text = if exclusion.is_some()
+                                    && matches!(revisions.best, Some((_, Some(_))))
                                 {
-                                    article_handler(&article.title, &article)?;
-                                    articles_processed += 1;
+                                    revisions.best.take().and_then(|(_, text)| text)
+                                } else {
+                                    None
+                                };
+                                let mut text_deleted = false;
+                                match revisions.best.take() {
+                                    Some((timestamp, Some(text))) => {
+                                        let text: String = text.nfc().collect();
+                                        article.timestamp = timestamp;
+                                        if self.body_redirects
+                                            && !is_file_page
+                                            && article.redirect.is_none()
+                                            && let Some(target) = body_redirect_target(&text)
+                                        {
+                                            set_redirect(article, &target);
+                                        }
+                                        if is_file_page || !clean_text {
+                                            // File description pages keep their raw wikitext so
+                                            // the templates carrying the metadata can still be read.
+                                            article.content = text;
+                                        } else {
+                                            article.links = extract_links(&text);
+                                            article.langlinks = extract_langlinks(&text);
+                                            article.short_description =
+                                                extract_short_description(&text);
+                                            article.coordinates = extract_coordinates(&text);
+                                            if self.include_raw {
+                                                article.raw = Some(text.clone());
+                                            }
+                                            article.content = if self.raw_wikitext {
+                                                text
+                                            } else {
+                                                let cleaning_started = Instant::now();
+                                                let cleaned = clean_wikitext(&text);
+                                                cleaning += cleaning_started.elapsed();
+                                                cleaned
+                                            };
+                                        }
+                                    }
+                                    Some((_, None)) => text_deleted = true,
+                                    None => {}
+                                }
+
+                                if let Some(article) = current_article.take() {
+                                    if article.title.trim().is_empty() {
+                                        on_page(PageEvent::InvalidTitle { id: article.id })?;
+                                    } else if text_deleted {
+                                        deleted_text += 1;
+                                    } else if is_file_page {
+                                        if article.redirect.is_none() {
+                                            on_page(PageEvent::File(parse_file_description(
+                                                &article,
+                                            )))?;
+                                        }
+                                    } else if let Some(reason) = exclusion {
+                                        if self.record_excluded.is_some() {
+                                            // Title gating skipped it; say whether the content missed too
+                                            let reason = match (reason, &skipped_text, topic_filter)
+                                            {
+                                                (
+                                                    ExclusionReason::TitleKeywordMiss,
+                                                    Some(text),
+                                                    Some(filter),
+                                                ) if !filter.is_relevant(&article.title, text) => {
+                                                    ExclusionReason::ContentRelevanceMiss
+                                                }
+                                                _ => reason,
+                                            };
+                                            on_page(PageEvent::Excluded(ExcludedTitle {
+                                                title: article.title,
+                                                reason,
+                                            }))?;
+                                        }
+                                    } else if let Some(reason) = self.content_exclusion(&article) {
+                                        if self.record_excluded.is_some() {
+                                            on_page(PageEvent::Excluded(ExcludedTitle {
+                                                title: article.title,
+                                                reason,
+                                            }))?;
+                                        }
+                                    } else if self.case_insensitive_titles
+                                        && state.is_case_variant(&article)
+                                    {
+                                        if self.record_excluded.is_some() {
+                                            on_page(PageEvent::Excluded(ExcludedTitle {
+                                                title: article.title,
+                                                reason: ExclusionReason::CaseVariant,
+                                            }))?;
+                                        }
+                                    } else {
+                                        state.matched += 1;
+                                        // `sample_every` keeps the 1st, (n+1)th, ... match
+                                        if (state.matched - 1).is_multiple_of(self.sample_every) {
+                                            if self.case_insensitive_titles {
+                                                state.folded_titles.insert(
+                                                    fold_title(&article.title),
+                                                    article.title.clone(),
+                                                );
+                                            }
+                                            on_page(PageEvent::Article(article))?;
 
-                                    if articles_processed % 1000 == 0 {
-                                        println!("Processed {articles_processed} articles...");
+                                            state.processed += 1;
+                                            if state
+                                                .processed
+                                                .is_multiple_of(self.progress_interval)
+                                            {
+                                                self.on_progress
+                                                    .report(Progress::Parsed(state.processed));
+                                            }
+
+                                            if let Some(max) = max_articles
+                                                && state.processed >= max
+                                            {
+                                                break;
+                                            }
+                                        }
                                     }
                                 }
-                                skip_content = false;
+                                exclusion = None;
+                                is_file_page = false;
+                            }
+                            _ => {}
+                        }
+                    } else {
+                        // Outside any page: the `<siteinfo>` header
+                        match tag_name {
+                            "languagecode" if !current_content.trim().is_empty() => {
+                                language_code_seen = true;
+                                on_page(PageEvent::SiteLanguage(
+                                    current_content.trim().to_string(),
+                                ))?;
+                            }
+                            "dbname" if !language_code_seen => {
+                                if let Some(code) = language_from_dbname(&current_content) {
+                                    on_page(PageEvent::SiteLanguage(code))?;
+                                }
                             }
                             _ => {}
                         }
@@ -237,14 +1065,491 @@ impl WikipediaParser {
             buf.clear();
         }
 
-        println!("Streaming processing complete: {articles_processed} articles processed");
-        Ok(())
+        Ok(ParseMetrics {
+            articles: state.processed - processed_before,
+            bytes: reader.buffer_position() as u64,
+            elapsed: started.elapsed(),
+            cleaning,
+            deleted_text,
+        })
+    }
+
+    /// Page-end check on the finished article, `None` to keep it: articles
+    /// with nothing left after cleaning are dropped unless
+    /// `keep_empty_articles` is set, as are those `relevance` scores below its
+    /// threshold. The topic filter was settled from the title by
+    /// `classify_page`; a page whose title matched would match on title plus
+    /// content as well. Redirects are always kept.
+    fn content_exclusion(&self, article: &Article) -> Option<ExclusionReason> {
+        if article.redirect.is_some() {
+            return None;
+        }
+        if !self.keep_empty_articles && article.content.trim().is_empty() {
+            return Some(ExclusionReason::Empty);
+        }
+        match &self.relevance {
+            Some(relevance) if !relevance.is_relevant(&article.title, &article.content) => {
+                Some(ExclusionReason::ContentRelevanceMiss)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the `<text>` of a page skipped for `exclusion` is read: only
+    /// when recording a topic miss, to check the content as well.
+    fn reads_text(&self, exclusion: Option<ExclusionReason>) -> bool {
+        match exclusion {
+            None => true,
+            Some(reason) => {
+                self.record_excluded.is_some() && reason == ExclusionReason::TitleKeywordMiss
+            }
+        }
     }
+
+    /// Decides whether a page is a file page and, if its content should be
+    /// skipped, why, from its title and (once `<id>` and `<ns>` have been
+    /// read) its id and namespace. `title_relevant` is the topic filter's
+    /// verdict on the title.
+    fn classify_page(
+        &self,
+        article: &Article,
+        id_known: bool,
+        namespace_known: bool,
+        title_relevant: bool,
+    ) -> (bool, Option<ExclusionReason>) {
+        if let Some((first, last)) = self.id_range
+            && id_known
+            && !(first..=last).contains(&article.id)
+        {
+            return (false, Some(ExclusionReason::IdRange));
+        }
+
+        if self
+            .exclude_titles
+            .contains(&normalize_title(&article.title))
+        {
+            return (false, Some(ExclusionReason::DenyList));
+        }
+
+        if let Some(only_titles) = &self.only_titles {
+            let listed = only_titles.contains(&normalize_title(&article.title));
+            let is_file = self.include_files && is_file_title(&article.title);
+            return (
+                listed && is_file,
+                (!listed).then_some(ExclusionReason::NotListed),
+            );
+        }
+
+        if self.include_files && is_file_title(&article.title) {
+            return (true, None);
+        }
+
+        let namespace = namespace_known.then_some(article.namespace);
+        let exclusion = title_exclusion(
+            &article.title,
+            title_relevant,
+            namespace,
+            &self.include_namespaces,
+        );
+        (false, exclusion)
+    }
+}
+
+/// Returned for inputs this build can't read, such as `.bz2` dumps when the
+/// `bzip2` feature is disabled or tar archives without the `tar` feature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedFormat(pub String);
+
+impl std::fmt::Display for UnsupportedFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UnsupportedFormat {}
+
+/// Decompresses a bz2 stream.
+#[cfg(feature = "bzip2")]
+fn bz2_reader<'a, R: Read + 'a>(
+    reader: R,
+) -> Result<Box<dyn Read + 'a>, Box<dyn std::error::Error>> {
+    Ok(Box::new(BzDecoder::new(reader)))
+}
+
+/// Decompresses a gzip stream, for `.tar.gz` archives.
+#[cfg(feature = "tar")]
+fn gzip_reader<'a, R: Read + 'a>(
+    reader: R,
+) -> Result<Box<dyn Read + 'a>, Box<dyn std::error::Error>> {
+    Ok(Box::new(flate2::read::GzDecoder::new(reader)))
+}
+
+/// Always fails: tar support was compiled out.
+#[cfg(not(feature = "tar"))]
+fn gzip_reader<'a, R: Read + 'a>(
+    _reader: R,
+) -> Result<Box<dyn Read + 'a>, Box<dyn std::error::Error>> {
+    Err(UnsupportedFormat(
+        "Unsupported format: reading tar archives requires the `tar` feature".to_string(),
+    )
+    .into())
+}
+
+/// Always fails: bz2 support was compiled out.
+#[cfg(not(feature = "bzip2"))]
+fn bz2_reader<'a, R: Read + 'a>(
+    _reader: R,
+) -> Result<Box<dyn Read + 'a>, Box<dyn std::error::Error>> {
+    Err(UnsupportedFormat(
+        "Unsupported format: reading .bz2 input requires the `bzip2` feature".to_string(),
+    )
+    .into())
+}
+
+const STUB_DUMP_ERROR: &str = "Detected a stub-meta dump: pages carry revision metadata \
+                               but no article text. Use a pages-articles dump instead.";
+
+/// The XML shape of a dump, detected from its root element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpFlavor {
+    /// `pages-articles` (and `stub-meta`) dumps: `<mediawiki><page><revision><text>`
+    PagesArticles,
+    /// `abstract` dumps: `<feed><doc><title>`/`<abstract>`
+    Abstract,
+}
+
+impl DumpFlavor {
+    pub fn detect(root: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        match root {
+            "mediawiki" | "page" => Ok(Self::PagesArticles),
+            "feed" => Ok(Self::Abstract),
+            other => Err(format!(
+                "Unrecognized dump format: root element <{other}> is neither a \
+                 pages-articles (<mediawiki>) nor an abstract (<feed>) dump"
+            )
+            .into()),
+        }
+    }
+
+    /// Maps the flavor's element names onto the pages-articles names the parser handles.
+    fn canonical_tag<'a>(&self, tag: &'a str) -> &'a str {
+        match (self, tag) {
+            (Self::Abstract, "doc") => "page",
+            (Self::Abstract, "abstract") => "text",
+            (_, tag) => tag,
+        }
+    }
+
+    /// Abstract dumps prefix every title with `Wikipedia: `.
+    fn strip_title_prefix(&self, title: &str) -> String {
+        match self {
+            Self::Abstract => title
+                .strip_prefix("Wikipedia: ")
+                .unwrap_or(title)
+                .to_string(),
+            Self::PagesArticles => title.to_string(),
+        }
+    }
+}
+
+/// State carried across the `read_pages` calls that read one input, such as
+/// the members of a tar archive
+#[derive(Default)]
+struct InputState {
+    /// Pages that passed every filter so far, whether `sample_every` kept them or not
+    matched: usize,
+    /// Pages handed on so far, which `max_articles` and progress reports count
+    processed: usize,
+    /// With `case_insensitive_titles`, folded title → the title first kept under it
+    folded_titles: HashMap<String, String>,
+}
+
+impl InputState {
+    /// Whether `article` differs only in case from a title already kept, or
+    /// is a redirect between two case variants of one title.
+    fn is_case_variant(&self, article: &Article) -> bool {
+        let folded = fold_title(&article.title);
+        article
+            .redirect
+            .as_deref()
+            .is_some_and(|target| fold_title(target) == folded)
+            || self
+                .folded_titles
+                .get(&folded)
+                .is_some_and(|first| *first != article.title)
+    }
+}
+
+/// Picks the current revision of a page: the one with the latest `<timestamp>`,
+/// falling back to the last `<revision>` in document order when timestamps are
+/// missing or equal.
+#[derive(Default)]
+struct RevisionSelector {
+    timestamp: Option<String>,
+    text: Option<String>,
+    /// The revision being read has `<text deleted="deleted">`
+    text_deleted: bool,
+    /// Timestamp and text of the current revision; the text is `None` when it was deleted
+    best: Option<(Option<String>, Option<String>)>,
+}
+
+impl RevisionSelector {
+    fn finish_revision(&mut self) {
+        let timestamp = self.timestamp.take();
+        let text = if std::mem::take(&mut self.text_deleted) {
+            None
+        } else {
+            match self.text.take() {
+                Some(text) => Some(text),
+                None => return,
+            }
+        };
+
+        // ISO 8601 timestamps from the dump compare correctly as strings.
+        let is_current = match (&self.best, &timestamp) {
+            (Some((Some(best), _)), Some(timestamp)) => timestamp >= best,
+            (Some((Some(_), _)), None) => false,
+            _ => true,
+        };
+        if is_current {
+            self.best = Some((timestamp, text));
+        }
+    }
+}
+
+/// A page reported by the event loop
+#[derive(Debug)]
+pub enum PageEvent {
+    /// An article or redirect that passed the filters
+    Article(Article),
+    /// A `File:` description page, when file pages are included
+    File(FileInfo),
+    /// A page dropped because its title was missing or empty
+    InvalidTitle { id: u64 },
+    /// A recoverable anomaly; parsing carried on with a default
+    Warning(ParseWarning),
+    /// The language code stated by the dump's `<siteinfo>` header
+    SiteLanguage(String),
+    /// A page the filters skipped, reported when `record_excluded` is set
+    Excluded(ExcludedTitle),
+}
+
+/// Derives a language code from a wiki database name, e.g. `dewiki` → `de`
+/// and `zh_yuewiki` → `zh-yue`. Other projects (`enwiktionary`, ...) yield `None`.
+fn language_from_dbname(dbname: &str) -> Option<String> {
+    let code = dbname.trim().strip_suffix("wiki")?;
+    if code.is_empty() {
+        return None;
+    }
+    Some(code.replace('_', "-"))
+}
+
+/// A recoverable anomaly met while parsing, for auditing data quality
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+    /// Title of the page being read, if it had been seen yet
+    pub title: Option<String>,
+    pub kind: ParseWarningKind,
+}
+
+impl ParseWarning {
+    fn new(article: &Article, kind: ParseWarningKind) -> Self {
+        Self {
+            title: (!article.title.is_empty()).then(|| article.title.clone()),
+            kind,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWarningKind {
+    /// The page `<id>` wasn't a number; the id defaults to 0
+    InvalidId(String),
+    /// `<ns>` wasn't a number; the namespace defaults to 0
+    InvalidNamespace(String),
+    /// A `<revision>` had no `<timestamp>`
+    MissingTimestamp,
+    /// A `<page>` opened before the previous one closed; the unfinished page is dropped
+    UnexpectedNesting,
+}
+
+/// True for a `<text>` element carrying the `deleted` attribute that dumps use
+/// for revision text hidden by an administrator.
+fn is_deleted_text(element: &BytesStart) -> bool {
+    element
+        .attributes()
+        .flatten()
+        .any(|attribute| attribute.key.as_ref() == b"deleted")
+}
+
+/// True for a self-closing `<text bytes="N"/>` with N > 0, which stub dumps
+/// use in place of the revision text.
+fn has_elided_text(element: &BytesStart) -> bool {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key.as_ref() == b"bytes")
+        .is_some_and(|attribute| attribute.value.as_ref() != b"0")
+}
+
+/// The target named by a `<redirect title="..."/>` element, as written in current dumps.
+fn redirect_title_attribute(element: &BytesStart) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key.as_ref() == b"title")
+        .and_then(|attribute| attribute.unescape_value().ok())
+        .map(|value| value.into_owned())
+}
+
+/// Records `target` on `article`, keeping any `#Section` fragment apart from the title.
+fn set_redirect(article: &mut Article, target: &str) {
+    let target = RedirectTarget::parse(&target.nfc().collect::<String>());
+    article.redirect = Some(target.title);
+    article.redirect_section = target.section;
+}
+
+/// Redirect magic words: English plus the localized forms of the larger wikis.
+/// Matched case-insensitively.
+const REDIRECT_MAGIC_WORDS: [&str; 14] = [
+    "#redirect",
+    "#weiterleitung",
+    "#redirection",
+    "#redirección",
+    "#rinvia",
+    "#przekieruj",
+    "#doorverwijzing",
+    "#перенаправление",
+    "#redirecionamento",
+    "#omdirigering",
+    "#yönlendi\u{307}rme",
+    "#転送",
+    "#重定向",
+    "#đổi",
+];
+
+/// The target of a `#REDIRECT [[Target]]` directive opening `text`, if any.
+fn body_redirect_target(text: &str) -> Option<String> {
+    let text = text.trim_start();
+    // Cheap rejection for the ordinary article
+    if !text.starts_with('#') {
+        return None;
+    }
+    let start = text.find("[[")?;
+    let directive = text[..start]
+        .trim_end()
+        .trim_end_matches(':')
+        .trim_end()
+        .to_lowercase();
+    if !REDIRECT_MAGIC_WORDS.contains(&directive.as_str()) {
+        return None;
+    }
+    let inner = &text[start + 2..];
+    let target = &inner[..inner.find("]]")?];
+    let target = target.split('|').next().unwrap_or(target).trim();
+    (!target.is_empty()).then(|| target.to_string())
+}
+
+/// For `.tar`, `.tar.gz` and `.tgz` paths, whether the archive is gzipped;
+/// `None` for anything else.
+pub(crate) fn tar_compression(path: &Path) -> Option<bool> {
+    let name = path.file_name()?.to_str()?.to_lowercase();
+    if name.ends_with(".tar") {
+        Some(false)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+fn has_bz2_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("bz2"))
+}
+
+fn is_file_title(title: &str) -> bool {
+    title.starts_with("File:")
+}
+
+/// Extracts the useful metadata from a `File:` description page: the fields
+/// of its `{{Information}}` template and the first recognised license tag.
+pub fn parse_file_description(article: &Article) -> FileInfo {
+    static FIELD: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?mi)^\s*\|\s*(description|date|source|author)\s*=\s*(.*)$")
+            .expect("valid regex")
+    });
+    // Descriptions are usually wrapped in language templates like `{{en|1=...}}`,
+    // which `clean_wikitext` would otherwise drop along with their text.
+    static LANGUAGE_TEMPLATE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"\{\{\s*[a-z]{2,3}\s*\|\s*(?:1\s*=\s*)?([^}]*)\}\}").expect("valid regex")
+    });
+    static LICENSE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(
+            r"(?i)\{\{\s*((?:self\|)?(?:cc-[a-z0-9.\-]+|pd-[a-z0-9.\-]+|gfdl[a-z0-9.\-]*|fairuse|non-free[a-z0-9 \-]*)[^}]*)\}\}",
+        )
+        .expect("valid regex")
+    });
+
+    let text = &article.content;
+    let mut info = FileInfo {
+        title: article.title.clone(),
+        id: article.id,
+        description: None,
+        date: None,
+        source: None,
+        author: None,
+        license: None,
+    };
+
+    // One pass over the template parameters; the first line for each field wins
+    let mut seen = HashSet::new();
+    for caps in FIELD.captures_iter(text) {
+        let name = caps[1].to_ascii_lowercase();
+        let slot = match name.as_str() {
+            "description" => &mut info.description,
+            "date" => &mut info.date,
+            "source" => &mut info.source,
+            _ => &mut info.author,
+        };
+        if !seen.insert(name) {
+            continue;
+        }
+        let value = LANGUAGE_TEMPLATE.replace_all(&caps[2], "$1");
+        let value = clean_wikitext(&value);
+        let value = value.trim();
+        if !value.is_empty() {
+            *slot = Some(value.to_string());
+        }
+    }
+
+    info.license = LICENSE
+        .captures(text)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().trim_start_matches("self|").trim().to_string());
+
+    info
 }
 
-fn should_include_by_title(title: &str, topic_filter: &Option<TopicFilter>) -> bool {
+/// Title-level inclusion check: why the page is skipped, or `None` to keep it.
+/// When the page's `<ns>` is known it must be one of `include_namespaces`;
+/// otherwise non-article namespaces are recognised by their title prefix.
+fn title_exclusion(
+    title: &str,
+    title_relevant: bool,
+    namespace: Option<i64>,
+    include_namespaces: &[i64],
+) -> Option<ExclusionReason> {
     if title.is_empty() {
-        return false;
+        // Reported as an invalid title once the page ends
+        return Some(ExclusionReason::Namespace);
+    }
+
+    if let Some(namespace) = namespace
+        && !include_namespaces.contains(&namespace)
+    {
+        return Some(ExclusionReason::Namespace);
     }
 
     let excluded_prefixes = [
@@ -260,58 +1565,553 @@ fn should_include_by_title(title: &str, topic_filter: &Option<TopicFilter>) -> b
         "Module:",
     ];
 
-    if excluded_prefixes
-        .iter()
-        .any(|&prefix| title.starts_with(prefix))
+    if namespace.is_none()
+        && excluded_prefixes
+            .iter()
+            .any(|&prefix| title.starts_with(prefix))
     {
-        return false;
+        return Some(ExclusionReason::Namespace);
+    }
+
+    (!title_relevant).then_some(ExclusionReason::TitleKeywordMiss)
+}
+
+/// Collects the distinct targets of internal `[[wikilinks]]`, normalized the way
+/// MediaWiki resolves titles (first letter uppercased, underscores as spaces).
+/// Links into other namespaces and section-only links are skipped.
+pub fn extract_links(content: &str) -> Vec<String> {
+    static WIKILINK: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"\[\[([^\]\|#]*)(?:#[^\]\|]*)?(?:\|[^\]]*)?\]\]").expect("valid regex")
+    });
+
+    let mut seen = std::collections::HashSet::new();
+    let mut links = Vec::new();
+    for caps in WIKILINK.captures_iter(content) {
+        let target = caps[1].replace('_', " ");
+        let target = target.trim();
+        if target.is_empty() || is_namespaced_link(target) {
+            continue;
+        }
+
+        let normalized = normalize_title(target);
+        if seen.insert(normalized.clone()) {
+            links.push(normalized);
+        }
+    }
+    links
+}
+
+/// Collects the interlanguage links (`[[fr:Empire romain]]`) in wikitext as
+/// `(language code, title)` pairs, keeping the first link for each language.
+pub fn extract_langlinks(content: &str) -> Vec<(String, String)> {
+    static LINK: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\[\[([^\]\|]*)(?:\|[^\]]*)?\]\]").expect("valid regex"));
+
+    let mut seen = std::collections::HashSet::new();
+    let mut langlinks = Vec::new();
+    for caps in LINK.captures_iter(content) {
+        let target = caps.get(1).map_or("", |target| target.as_str());
+        if let Some((language, title)) = split_language_link(target)
+            && seen.insert(language)
+        {
+            langlinks.push((language.to_string(), title.to_string()));
+        }
     }
+    langlinks
+}
+
+/// Reads the `{{Short description|...}}` template MediaWiki uses for the one-line
+/// summary shown in search results. The placeholder value `none` counts as absent.
+pub fn extract_short_description(content: &str) -> Option<String> {
+    static SHORT_DESCRIPTION: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?i)\{\{\s*short description\s*\|([^|}]*)").expect("valid regex")
+    });
+
+    let description = SHORT_DESCRIPTION.captures(content)?[1].trim().to_string();
+    (!description.is_empty() && !description.eq_ignore_ascii_case("none")).then_some(description)
+}
 
-    if let Some(filter) = topic_filter {
-        let title_lower = title.to_lowercase();
-        filter
-            .keywords()
+/// Reads latitude and longitude, in decimal degrees, from the first
+/// `{{Coord|...}}` template that parses. Takes the decimal form
+/// (`{{Coord|48.8566|2.3522}}`, optionally with `N`/`S` and `E`/`W`) and the
+/// degrees-minutes-seconds form (`{{Coord|48|51|24|N|2|21|8|E}}`); named
+/// parameters and trailing ones such as `type:city` are ignored.
+pub fn extract_coordinates(content: &str) -> Option<(f64, f64)> {
+    static COORD: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?i)\{\{\s*coord\s*\|([^}]*)\}\}").expect("valid regex"));
+
+    COORD
+        .captures_iter(content)
+        .find_map(|caps| parse_coord_params(caps.get(1).map_or("", |params| params.as_str())))
+}
+
+fn parse_coord_params(params: &str) -> Option<(f64, f64)> {
+    // Numbers and hemisphere letters up to the first parameter that is neither
+    let fields: Vec<&str> = params
+        .split('|')
+        .map(str::trim)
+        .filter(|field| !field.contains('='))
+        .take_while(|field| field.parse::<f64>().is_ok() || matches!(*field, "N" | "S" | "E" | "W"))
+        .collect();
+
+    let to_degrees = |parts: &[&str]| -> Option<f64> {
+        if parts.is_empty() || parts.len() > 3 {
+            return None;
+        }
+        parts
             .iter()
-            .any(|&keyword| title_lower.contains(keyword))
-    } else {
-        true
+            .zip([1.0, 60.0, 3600.0])
+            .map(|(part, divisor)| part.parse::<f64>().ok().map(|value| value / divisor))
+            .sum()
+    };
+
+    let (latitude, longitude) = match fields.iter().position(|field| matches!(*field, "N" | "S")) {
+        Some(lat_end) => {
+            let lon_end = lat_end
+                + 1
+                + fields[lat_end + 1..]
+                    .iter()
+                    .position(|field| matches!(*field, "E" | "W"))?;
+            let latitude = to_degrees(&fields[..lat_end])?;
+            let longitude = to_degrees(&fields[lat_end + 1..lon_end])?;
+            (
+                if fields[lat_end] == "S" {
+                    -latitude
+                } else {
+                    latitude
+                },
+                if fields[lon_end] == "W" {
+                    -longitude
+                } else {
+                    longitude
+                },
+            )
+        }
+        None => match fields.as_slice() {
+            [latitude, longitude, ..] => (latitude.parse().ok()?, longitude.parse().ok()?),
+            _ => return None,
+        },
+    };
+
+    (latitude.abs() <= 90.0 && longitude.abs() <= 180.0).then_some((latitude, longitude))
+}
+
+/// Normalizes a title the way MediaWiki resolves it: NFC, surrounding
+/// whitespace trimmed, underscores as spaces and the first letter uppercased.
+pub fn normalize_title(title: &str) -> String {
+    let title: String = title
+        .nfc()
+        .map(|c| if c == '_' { ' ' } else { c })
+        .collect();
+    let mut chars = title.trim().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
     }
 }
 
-fn should_include_by_content(article: &Article, topic_filter: &Option<TopicFilter>) -> bool {
-    if let Some(filter) = topic_filter {
-        filter.is_relevant(&article.title, &article.content)
-    } else {
-        true
+/// The key `case_insensitive_titles` compares titles by: the normalized title, lowercased.
+pub fn fold_title(title: &str) -> String {
+    normalize_title(title).to_lowercase()
+}
+
+/// True for link targets outside the main namespace: namespace prefixes such
+/// as `Category:`, interlanguage/interwiki prefixes like `de:`, and colon-escaped links.
+fn is_namespaced_link(target: &str) -> bool {
+    const NAMESPACES: [&str; 14] = [
+        "file",
+        "image",
+        "media",
+        "category",
+        "template",
+        "user",
+        "talk",
+        "wikipedia",
+        "help",
+        "portal",
+        "mediawiki",
+        "module",
+        "draft",
+        "special",
+    ];
+
+    if target.starts_with(':') {
+        return true;
+    }
+
+    match target.split_once(':') {
+        Some((prefix, _)) => {
+            let prefix = prefix.trim();
+            let lower = prefix.to_lowercase();
+            NAMESPACES.contains(&lower.as_str())
+                || lower.ends_with(" talk")
+                || (prefix.len() <= 3 && prefix.chars().all(|c| c.is_ascii_lowercase()))
+        }
+        None => false,
+    }
+}
+
+/// Splits an interlanguage link target like `de:Römisches Reich` into its
+/// language code and title. Colon-escaped `[[:de:...]]` links are inline
+/// links to the other wiki, not interlanguage links.
+fn split_language_link(target: &str) -> Option<(&str, &str)> {
+    let (prefix, title) = target.split_once(':')?;
+    let title = title.trim();
+    (is_language_code(prefix) && !title.is_empty()).then_some((prefix, title))
+}
+
+/// Whether `prefix` is shaped like a Wikipedia language code: `simple`, or two
+/// or three lowercase letters with optional `-variant` parts (`zh-yue`), other
+/// than the project interwiki prefixes shaped the same way.
+fn is_language_code(prefix: &str) -> bool {
+    const PROJECT_PREFIXES: [&str; 6] = ["wp", "mw", "voy", "doi", "wmf", "rfc"];
+    let is_lowercase_word =
+        |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase());
+
+    let mut parts = prefix.split('-');
+    let base = parts.next().unwrap_or_default();
+    prefix == "simple"
+        || ((2..=3).contains(&base.len())
+            && is_lowercase_word(base)
+            && parts.all(is_lowercase_word)
+            && !PROJECT_PREFIXES.contains(&base))
+}
+
+/// Toggles for the individual transformations applied by
+/// `clean_wikitext_with_options`. The default enables all of them, matching
+/// `clean_wikitext`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanOptions {
+    /// Remove `{{template}}` calls
+    pub strip_templates: bool,
+    /// Remove `[[Category:...]]` links
+    pub strip_category_links: bool,
+    /// Remove `[[File:...]]` embeds
+    pub strip_file_links: bool,
+    /// Remove interlanguage links like `[[de:Römisches Reich]]`
+    pub strip_language_links: bool,
+    /// Replace `[[target|label]]` and `[[target]]` with their visible text
+    pub unwrap_links: bool,
+    /// Drop `'''bold'''` and `''italic''` quote markup
+    pub strip_emphasis: bool,
+    /// Remove `<ref>` footnotes
+    pub strip_references: bool,
+    /// Remove `<nowiki>` spans and any remaining HTML tags
+    pub strip_html: bool,
+    /// Drop the `==` markers around section headings
+    pub strip_heading_markers: bool,
+    /// Render `<blockquote>` and leading-colon indentation as Markdown quotes
+    pub quote_indentation: bool,
+    /// Use the regex-per-step cleaner instead of the single-pass scanner
+    pub use_regex: bool,
+}
+
+impl Default for CleanOptions {
+    fn default() -> Self {
+        Self {
+            strip_templates: true,
+            strip_category_links: true,
+            strip_file_links: true,
+            strip_language_links: true,
+            unwrap_links: true,
+            strip_emphasis: true,
+            strip_references: true,
+            strip_html: true,
+            strip_heading_markers: true,
+            quote_indentation: true,
+            use_regex: false,
+        }
     }
 }
 
 pub fn clean_wikitext(content: &str) -> String {
-    let patterns = [
-        (r"\{\{[^}]*\}\}", ""),
-        (r"\[\[Category:[^\]]*\]\]", ""),
-        (r"\[\[File:[^\]]*\]\]", ""),
-        (r"\[\[[^\]]*\|([^\]]*)\]\]", "$1"),
-        (r"\[\[([^\]]*)\]\]", "$1"),
-        (r"'''([^']*?)'''", "$1"),
-        (r"''([^']*?)''", "$1"),
-        (r"<ref[^>]*>[^<]*</ref>", ""),
-        (r"<nowiki>[^<]*</nowiki>", ""),
-        (r"<[^>]*>", ""),
-        (r"={2,6}([^=]*?)={2,6}", "$1"),
-    ];
+    clean_wikitext_with_options(content, &CleanOptions::default())
+}
 
-    let mut cleaned = content.to_string();
-    for (pattern, replacement) in patterns {
-        if let Ok(re) = Regex::new(pattern) {
-            cleaned = re.replace_all(&cleaned, replacement).to_string();
+/// Cleans wikitext into readable text, applying only the transformations
+/// enabled in `options`. Unless `options.use_regex` is set, this scans the
+/// input once and writes into a single buffer.
+pub fn clean_wikitext_with_options(content: &str, options: &CleanOptions) -> String {
+    if options.use_regex {
+        return clean_wikitext_regex(content, options);
+    }
+
+    let mut cleaned = String::with_capacity(content.len());
+    scan_markup(content, options, &mut cleaned);
+    finish_lines(&cleaned, options)
+}
+
+/// Bytes that may open a construct handled by `scan_markup`
+fn is_markup_start(byte: u8) -> bool {
+    matches!(byte, b'{' | b'[' | b'\'' | b'<' | b'=')
+}
+
+/// Single-pass counterpart of the regex steps in `clean_wikitext_regex`:
+/// walks `text` once, copying plain runs into `out` and rewriting each
+/// construct as it is met. Unwrapped text (link labels, emphasis, headings)
+/// is scanned recursively so nested markup is cleaned the same way.
+fn scan_markup(text: &str, options: &CleanOptions, out: &mut String) {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &text[i..];
+        let consumed = match bytes[i] {
+            b'{' if options.strip_templates => skip_template(rest),
+            b'[' => rewrite_link(rest, options, out),
+            b'\'' if options.strip_emphasis => rewrite_emphasis(rest, options, out),
+            b'<' => rewrite_tag(rest, options, out),
+            b'=' if options.strip_heading_markers => rewrite_heading(rest, options, out),
+            _ => None,
+        };
+        match consumed {
+            Some(length) => i += length,
+            None => {
+                let next = bytes[i + 1..]
+                    .iter()
+                    .position(|&byte| is_markup_start(byte))
+                    .map_or(bytes.len(), |offset| i + 1 + offset);
+                out.push_str(&text[i..next]);
+                i = next;
+            }
+        }
+    }
+}
+
+/// `{{...}}` up to the first `}}`; returns the length to skip.
+fn skip_template(rest: &str) -> Option<usize> {
+    if !rest.starts_with("{{") {
+        return None;
+    }
+    let close = rest[2..].find('}')? + 2;
+    rest[close..].starts_with("}}").then_some(close + 2)
+}
+
+/// `[[target]]`, `[[target|label]]`, and category or file links.
+fn rewrite_link(rest: &str, options: &CleanOptions, out: &mut String) -> Option<usize> {
+    if !rest.starts_with("[[") {
+        return None;
+    }
+    let close = rest[2..].find(']')? + 2;
+    if !rest[close..].starts_with("]]") {
+        return None;
+    }
+    let inner = &rest[2..close];
+
+    if (options.strip_category_links && inner.starts_with("Category:"))
+        || (options.strip_file_links && inner.starts_with("File:"))
+        || (options.strip_language_links && split_language_link(inner).is_some())
+    {
+        return Some(close + 2);
+    }
+    if !options.unwrap_links {
+        return None;
+    }
+    let label = inner.rsplit('|').next().unwrap_or(inner);
+    scan_markup(label, options, out);
+    Some(close + 2)
+}
+
+/// `'''''bold italic'''''`, `'''bold'''` and `''italic''` without apostrophes inside.
+fn rewrite_emphasis(rest: &str, options: &CleanOptions, out: &mut String) -> Option<usize> {
+    for marker in ["'''''", "'''", "''"] {
+        if let Some(body) = rest.strip_prefix(marker)
+            && let Some(end) = body.find('\'')
+            && body[end..].starts_with(marker)
+        {
+            scan_markup(&body[..end], options, out);
+            return Some(marker.len() + end + marker.len());
         }
     }
+    None
+}
+
+/// Blockquotes, `<ref>` footnotes, `<nowiki>` spans and other HTML tags.
+fn rewrite_tag(rest: &str, options: &CleanOptions, out: &mut String) -> Option<usize> {
+    if options.quote_indentation
+        && let Some(length) = rewrite_blockquote(rest, options, out)
+    {
+        return Some(length);
+    }
 
-    cleaned
+    if options.strip_references && rest.starts_with("<ref") {
+        let open_end = rest.find('>')? + 1;
+        if let Some(body_end) = rest[open_end..].find('<').map(|offset| open_end + offset)
+            && rest[body_end..].starts_with("</ref>")
+        {
+            return Some(body_end + "</ref>".len());
+        }
+    }
+
+    if !options.strip_html {
+        return None;
+    }
+    if let Some(body) = rest.strip_prefix("<nowiki>")
+        && let Some(end) = body.find('<')
+        && body[end..].starts_with("</nowiki>")
+    {
+        return Some("<nowiki>".len() + end + "</nowiki>".len());
+    }
+    rest.find('>').map(|end| end + 1)
+}
+
+/// `<blockquote>...</blockquote>` as colon-indented lines, which
+/// `finish_lines` then turns into Markdown quotes.
+fn rewrite_blockquote(rest: &str, options: &CleanOptions, out: &mut String) -> Option<usize> {
+    if !rest.get(..11)?.eq_ignore_ascii_case("<blockquote") {
+        return None;
+    }
+    let open_end = rest.find('>')? + 1;
+    let close = find_ignore_ascii_case(&rest[open_end..], "</blockquote>")? + open_end;
+
+    let mut quoted = String::new();
+    scan_markup(&rest[open_end..close], options, &mut quoted);
+    out.push('\n');
+    for line in quoted
         .lines()
-        .map(|line| line.trim())
+        .map(str::trim)
         .filter(|line| !line.is_empty())
-        .collect::<Vec<_>>()
-        .join("\n")
+    {
+        out.push(':');
+        out.push_str(line);
+        out.push('\n');
+    }
+    Some(close + "</blockquote>".len())
+}
+
+/// Byte offset of the first ASCII-case-insensitive match of `needle`.
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+/// `== Heading ==` with two to six equals signs on each side.
+fn rewrite_heading(rest: &str, options: &CleanOptions, out: &mut String) -> Option<usize> {
+    let open = rest.bytes().take_while(|&byte| byte == b'=').count().min(6);
+    if open < 2 {
+        return None;
+    }
+    let end = rest[open..].find('=')? + open;
+    let close = rest[end..]
+        .bytes()
+        .take_while(|&byte| byte == b'=')
+        .count()
+        .min(6);
+    if close < 2 {
+        return None;
+    }
+    scan_markup(&rest[open..end], options, out);
+    Some(end + close)
+}
+
+/// Trims every line, drops blank ones and quotes colon-indented lines.
+fn finish_lines(cleaned: &str, options: &CleanOptions) -> String {
+    let mut out = String::with_capacity(cleaned.len());
+    for line in cleaned.lines().map(str::trim) {
+        let line = if options.quote_indentation {
+            quote_indented_line(line)
+        } else {
+            (!line.is_empty()).then(|| line.to_string())
+        };
+        if let Some(line) = line {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&line);
+        }
+    }
+    out
+}
+
+/// The original cleaner: one regex `replace_all` per transformation. Kept for
+/// parity testing against the single-pass scanner (`CleanOptions::use_regex`).
+fn clean_wikitext_regex(content: &str, options: &CleanOptions) -> String {
+    let steps: [(bool, &[(&str, &str)]); 8] = [
+        (options.strip_templates, &[(r"\{\{[^}]*\}\}", "")]),
+        (
+            options.strip_category_links,
+            &[(r"\[\[Category:[^\]]*\]\]", "")],
+        ),
+        (options.strip_file_links, &[(r"\[\[File:[^\]]*\]\]", "")]),
+        (
+            options.unwrap_links,
+            &[
+                (r"\[\[[^\]]*\|([^\]]*)\]\]", "$1"),
+                (r"\[\[([^\]]*)\]\]", "$1"),
+            ],
+        ),
+        (
+            options.strip_emphasis,
+            &[(r"'''([^']*?)'''", "$1"), (r"''([^']*?)''", "$1")],
+        ),
+        (options.strip_references, &[(r"<ref[^>]*>[^<]*</ref>", "")]),
+        (
+            options.strip_html,
+            &[(r"<nowiki>[^<]*</nowiki>", ""), (r"<[^>]*>", "")],
+        ),
+        (
+            options.strip_heading_markers,
+            &[(r"={2,6}([^=]*?)={2,6}", "$1")],
+        ),
+    ];
+
+    // Blockquotes are rewritten as colon-indented lines so they survive tag
+    // stripping and come out as Markdown quotes alongside `:` indentation.
+    let mut cleaned = content.to_string();
+    if options.quote_indentation
+        && let Ok(re) = Regex::new(r"(?is)<blockquote[^>]*>(.*?)</blockquote>")
+    {
+        cleaned = re
+            .replace_all(&cleaned, |caps: &regex::Captures| {
+                let quoted = caps[1]
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(|line| format!(":{line}"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                format!("\n{quoted}\n")
+            })
+            .to_string();
+    }
+
+    if options.strip_language_links
+        && let Ok(re) = Regex::new(r"\[\[([^\]\[]*)\]\]")
+    {
+        cleaned = re
+            .replace_all(&cleaned, |caps: &regex::Captures| {
+                if split_language_link(&caps[1]).is_some() {
+                    String::new()
+                } else {
+                    caps[0].to_string()
+                }
+            })
+            .to_string();
+    }
+
+    for (enabled, patterns) in steps {
+        if !enabled {
+            continue;
+        }
+        for (pattern, replacement) in patterns {
+            if let Ok(re) = Regex::new(pattern) {
+                cleaned = re.replace_all(&cleaned, *replacement).to_string();
+            }
+        }
+    }
+
+    finish_lines(&cleaned, options)
+}
+
+/// Turns a line indented with leading colons into a Markdown quote nested one
+/// level per colon. Colons elsewhere in the line are left alone.
+fn quote_indented_line(line: &str) -> Option<String> {
+    let text = line.trim_start_matches(':');
+    let depth = line.len() - text.len();
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some(format!("{}{}", "> ".repeat(depth), text))
 }
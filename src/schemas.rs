@@ -0,0 +1,139 @@
+use serde_json::{Value, json};
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Every schema the generator can emit, keyed by the file name it is written under in `schemas/`
+pub fn all_schemas() -> Vec<(&'static str, Value)> {
+    vec![
+        ("tool_response.schema.json", tool_response_schema()),
+        ("resource_response.schema.json", resource_response_schema()),
+        (
+            "list_articles_page.schema.json",
+            list_articles_page_schema(),
+        ),
+        ("category.schema.json", category_schema()),
+        ("stats.schema.json", stats_schema()),
+    ]
+}
+
+/// Shape of every file under `tools/`, including `get_article` responses
+pub fn tool_response_schema() -> Value {
+    json!({
+        "$schema": DRAFT,
+        "title": "ToolResponse",
+        "type": "object",
+        "required": ["content"],
+        "properties": {
+            "content": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["type", "text"],
+                    "properties": {
+                        "type": { "type": "string", "const": "text" },
+                        "text": { "type": "string" },
+                        "mimeType": { "type": "string" }
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Shape of every file under `resources/`
+pub fn resource_response_schema() -> Value {
+    json!({
+        "$schema": DRAFT,
+        "title": "ResourceResponse",
+        "type": "object",
+        "required": ["uri", "mimeType", "text"],
+        "properties": {
+            "uri": { "type": "string" },
+            "mimeType": { "type": "string" },
+            "text": { "type": "string" }
+        }
+    })
+}
+
+/// JSON carried in the `text` of a `list_articles` page response
+pub fn list_articles_page_schema() -> Value {
+    json!({
+        "$schema": DRAFT,
+        "title": "ListArticlesPage",
+        "type": "object",
+        "required": ["pagination"],
+        "properties": {
+            "pagination": {
+                "type": "object",
+                "required": ["current_page", "total_pages", "per_page", "total_articles"],
+                "properties": {
+                    "current_page": { "type": ["integer", "null"], "minimum": 1 },
+                    "total_pages": { "type": "integer", "minimum": 0 },
+                    "per_page": { "type": "integer", "minimum": 1 },
+                    "total_articles": { "type": "integer", "minimum": 0 },
+                    "start_index": { "type": "integer", "minimum": 0 },
+                    "end_index": { "type": "integer", "minimum": 0 }
+                }
+            },
+            "articles": { "type": "array", "items": { "type": "string" } },
+            "message": { "type": "string" }
+        }
+    })
+}
+
+/// JSON carried in the `text` of a `categories` response
+pub fn category_schema() -> Value {
+    json!({
+        "$schema": DRAFT,
+        "title": "Category",
+        "type": "object",
+        "required": ["category", "articles", "count"],
+        "properties": {
+            "category": { "type": "string" },
+            "articles": { "type": "array", "items": { "type": "string" } },
+            "count": { "type": "integer", "minimum": 0 },
+            "page": { "type": "integer", "minimum": 1 },
+            "total_pages": { "type": "integer", "minimum": 1 },
+            "truncated": { "type": "boolean" },
+            "note": { "type": "string" }
+        }
+    })
+}
+
+/// JSON carried in the `text` of the `wikipedia://stats` resource
+pub fn stats_schema() -> Value {
+    json!({
+        "$schema": DRAFT,
+        "title": "Stats",
+        "type": "object",
+        "required": ["total_articles", "language", "generated_at"],
+        "properties": {
+            "total_articles": { "type": "integer", "minimum": 0 },
+            "total_redirects": { "type": "integer", "minimum": 0 },
+            "language": { "type": "string" },
+            "topic_filter": { "type": ["string", "null"] },
+            "generated_at": { "type": "string" },
+            "streaming_mode": { "type": "boolean" },
+            "content_lengths": {
+                "type": "object",
+                "properties": {
+                    "min": { "type": "integer", "minimum": 0 },
+                    "max": { "type": "integer", "minimum": 0 },
+                    "mean": { "type": "number", "minimum": 0 },
+                    "histogram": {
+                        "type": "object",
+                        "additionalProperties": { "type": "integer", "minimum": 0 }
+                    }
+                }
+            },
+            "links": {
+                "type": "object",
+                "properties": {
+                    "total": { "type": "integer", "minimum": 0 },
+                    "dangling": { "type": "integer", "minimum": 0 },
+                    "dangling_ratio": { "type": "number", "minimum": 0, "maximum": 1 }
+                }
+            }
+        }
+    })
+}
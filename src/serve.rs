@@ -0,0 +1,163 @@
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Component, Path, PathBuf};
+
+/// Serves the tree in `output_dir` on `addr` until the process exits, for
+/// trying it against a local MCP client. Files are served read-only, one
+/// request per connection; it is a development aid, not a production host.
+pub fn serve(output_dir: impl Into<PathBuf>, addr: impl ToSocketAddrs) -> io::Result<()> {
+    PreviewServer::bind(output_dir, addr)?.run()
+}
+
+/// A bound preview server; bind to port 0 and read `local_addr` to get an ephemeral port.
+pub struct PreviewServer {
+    root: PathBuf,
+    listener: TcpListener,
+}
+
+impl PreviewServer {
+    pub fn bind(output_dir: impl Into<PathBuf>, addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Ok(Self {
+            root: output_dir.into(),
+            listener: TcpListener::bind(addr)?,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Answers requests until the listener fails. A connection that errors is
+    /// reported and dropped without stopping the server.
+    pub fn run(self) -> io::Result<()> {
+        println!(
+            "Serving {} on http://{}",
+            self.root.display(),
+            self.local_addr()?
+        );
+        for stream in self.listener.incoming() {
+            if let Err(error) = self.handle(stream?) {
+                eprintln!("Warning: preview request failed: {error}");
+            }
+        }
+        Ok(())
+    }
+
+    fn handle(&self, mut stream: TcpStream) -> io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut accepts_gzip = false;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':')
+                && name.trim().eq_ignore_ascii_case("accept-encoding")
+                && value
+                    .split(',')
+                    .any(|coding| coding.split(';').next().unwrap_or("").trim() == "gzip")
+            {
+                accepts_gzip = true;
+            }
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or("/"));
+        if method != "GET" && method != "HEAD" {
+            let response = Response::new("405 Method Not Allowed", "text/plain", b"");
+            return response.send(&mut stream, true);
+        }
+
+        let path = target.split(['?', '#']).next().unwrap_or("/");
+        let Some(file) = self.resolve(path) else {
+            let response = Response::new("404 Not Found", "text/plain", b"Not found\n");
+            return response.send(&mut stream, method == "GET");
+        };
+
+        // A precompressed `<file>.gz` next to the file is sent to clients that accept it
+        let gzipped = PathBuf::from(format!("{}.gz", file.display()));
+        let response = if accepts_gzip && gzipped.is_file() {
+            let mut response = Response::new("200 OK", content_type(&file), fs::read(&gzipped)?);
+            response.headers = &[("Content-Encoding", "gzip"), ("Vary", "Accept-Encoding")];
+            response
+        } else {
+            Response::new("200 OK", content_type(&file), fs::read(&file)?)
+        };
+        response.send(&mut stream, method == "GET")
+    }
+
+    /// The file for a request path: `/` is `mcp.json`, and a path without an
+    /// extension, such as the one for a resource or tool call, gets `.json`.
+    /// Paths that would leave the root are refused.
+    fn resolve(&self, path: &str) -> Option<PathBuf> {
+        let relative = Path::new(path.trim_start_matches('/'));
+        if relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+        {
+            return None;
+        }
+        if relative.as_os_str().is_empty() {
+            return Some(self.root.join("mcp.json")).filter(|file| file.is_file());
+        }
+
+        let file = self.root.join(relative);
+        if file.is_file() {
+            return Some(file);
+        }
+        let with_json = PathBuf::from(format!("{}.json", file.display()));
+        with_json.is_file().then_some(with_json)
+    }
+}
+
+fn content_type(file: &Path) -> &'static str {
+    match file.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => "application/json",
+        Some("md") => "text/markdown; charset=utf-8",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("html") => "text/html; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+struct Response<B> {
+    status: &'static str,
+    content_type: &'static str,
+    headers: &'static [(&'static str, &'static str)],
+    body: B,
+}
+
+impl<B: AsRef<[u8]>> Response<B> {
+    fn new(status: &'static str, content_type: &'static str, body: B) -> Self {
+        Self {
+            status,
+            content_type,
+            headers: &[],
+            body,
+        }
+    }
+
+    /// Writes the response; `with_body` is false for `HEAD`, which gets the
+    /// headers of the `GET` response alone.
+    fn send(&self, stream: &mut TcpStream, with_body: bool) -> io::Result<()> {
+        let body = self.body.as_ref();
+        let mut head = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+            self.status,
+            self.content_type,
+            body.len()
+        );
+        for (name, value) in self.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str("\r\n");
+        stream.write_all(head.as_bytes())?;
+        if with_body {
+            stream.write_all(body)?;
+        }
+        stream.flush()
+    }
+}
@@ -0,0 +1,108 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Destination for the generated files. Paths are relative to the output root
+/// and `/`-separated, e.g. `tools/get_article/roman_empire.json`.
+pub trait OutputSink {
+    /// Stores `bytes` at `path`, replacing any earlier file there.
+    fn write(&mut self, path: &str, bytes: &[u8]) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// The file at `path`, or `None` if there is none. Collision handling and
+    /// `update` read back what was written before.
+    fn read(&self, path: &str) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>>;
+
+    /// Deletes the file at `path`, or every file below it when `path` is a
+    /// directory. Missing paths are not an error.
+    fn remove(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Paths of every file currently stored.
+    fn paths(&self) -> Result<Vec<String>, Box<dyn std::error::Error>>;
+
+    fn exists(&self, path: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        Ok(self.read(path)?.is_some())
+    }
+
+    /// Creates an empty directory, for sinks that have them. A no-op by default.
+    fn create_dir(&mut self, _path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+}
+
+/// Writes files under a root directory on disk, creating parent directories as needed
+pub struct FilesystemSink {
+    root: PathBuf,
+}
+
+impl FilesystemSink {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl OutputSink for FilesystemSink {
+    fn write(&mut self, path: &str, bytes: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        let path = self.root.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    fn read(&self, path: &str) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
+        match fs::read(self.root.join(path)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn remove(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let path = self.root.join(path);
+        if path.is_dir() {
+            fs::remove_dir_all(path)?;
+        } else if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    fn paths(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        fn walk(
+            dir: &Path,
+            prefix: &str,
+            paths: &mut Vec<String>,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            for entry in fs::read_dir(dir)? {
+                let entry = entry?;
+                let relative = format!("{prefix}{}", entry.file_name().to_string_lossy());
+                if entry.file_type()?.is_dir() {
+                    walk(&entry.path(), &format!("{relative}/"), paths)?;
+                } else {
+                    paths.push(relative);
+                }
+            }
+            Ok(())
+        }
+
+        let mut paths = Vec::new();
+        if self.root.is_dir() {
+            walk(&self.root, "", &mut paths)?;
+        }
+        Ok(paths)
+    }
+
+    fn exists(&self, path: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        Ok(self.root.join(path).is_file())
+    }
+
+    fn create_dir(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        fs::create_dir_all(self.root.join(path))?;
+        Ok(())
+    }
+}
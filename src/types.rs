@@ -1,12 +1,490 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Article {
     pub title: String,
     pub content: String,
     pub id: u64,
     pub redirect: Option<String>,
+    /// Section named by a `#fragment` on the redirect target, split off `redirect`
+    pub redirect_section: Option<String>,
+    /// Targets of the internal `[[wikilinks]]` in the article body
+    pub links: Vec<String>,
+    /// ISO 8601 timestamp of the revision the content was taken from
+    pub timestamp: Option<String>,
+    /// MediaWiki namespace id from `<ns>` (0 for articles, 10 for templates, ...)
+    pub namespace: i64,
+    /// Text of the page's `{{Short description|...}}` template, if it has one
+    pub short_description: Option<String>,
+    /// `(language code, title)` of each interlanguage link like `[[de:Römisches Reich]]`
+    pub langlinks: Vec<(String, String)>,
+    /// `(latitude, longitude)` in decimal degrees from the page's first `{{Coord}}` template
+    pub coordinates: Option<(f64, f64)>,
+    /// The page's `<text>` as written, kept next to the cleaned `content` with `include_raw`
+    pub raw: Option<String>,
+}
+
+/// The `generated_at` time of a run, as written to `stats.json` (e.g.
+/// `2024-01-02 03:04:05 UTC`). Built from a pre-formatted string, or from a
+/// `chrono::DateTime<Utc>` when the `chrono` feature is on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timestamp(String);
+
+impl Timestamp {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Timestamp {
+    fn from(formatted: String) -> Self {
+        Self(formatted)
+    }
+}
+
+impl From<&str> for Timestamp {
+    fn from(formatted: &str) -> Self {
+        Self(formatted.to_string())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::Utc>> for Timestamp {
+    fn from(at: chrono::DateTime<chrono::Utc>) -> Self {
+        Self(at.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+    }
+}
+
+/// Formats the header placed above each article body in `get_article` responses
+#[derive(Clone)]
+pub struct ArticleHeader(Arc<dyn Fn(&Article) -> String + Send + Sync>);
+
+impl ArticleHeader {
+    pub fn new(format: impl Fn(&Article) -> String + Send + Sync + 'static) -> Self {
+        Self(Arc::new(format))
+    }
+
+    pub fn format(&self, article: &Article) -> String {
+        (self.0)(article)
+    }
+}
+
+impl Default for ArticleHeader {
+    /// The `# Title` Markdown heading
+    fn default() -> Self {
+        Self::new(|article| format!("# {}", article.title))
+    }
+}
+
+impl fmt::Debug for ArticleHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ArticleHeader(..)")
+    }
+}
+
+/// Rewrites an article's content right before it is written, e.g. to redact
+/// text or prepend a notice
+#[derive(Clone)]
+pub struct ContentTransform(Arc<TransformFn>);
+
+type TransformFn = dyn Fn(&str, &Article) -> String + Send + Sync;
+
+impl ContentTransform {
+    pub fn new(transform: impl Fn(&str, &Article) -> String + Send + Sync + 'static) -> Self {
+        Self(Arc::new(transform))
+    }
+
+    pub fn apply(&self, content: &str, article: &Article) -> String {
+        (self.0)(content, article)
+    }
+}
+
+impl fmt::Debug for ContentTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ContentTransform(..)")
+    }
+}
+
+/// A milestone of a long-running step, reported every `progress_interval` articles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// The parser has kept this many articles
+    Parsed(usize),
+    /// This many `get_article` responses have been written
+    Generated(usize),
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Progress::Parsed(count) => write!(f, "Processed {count} articles..."),
+            Progress::Generated(count) => write!(f, "Generated {count} article responses..."),
+        }
+    }
+}
+
+/// Receives the `Progress` reports of parsing and generation
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(Progress) + Send + Sync>);
+
+impl ProgressCallback {
+    pub fn new(report: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(report))
+    }
+
+    pub fn report(&self, progress: Progress) {
+        (self.0)(progress)
+    }
+}
+
+impl Default for ProgressCallback {
+    /// Prints each report to stdout
+    fn default() -> Self {
+        Self::new(|progress| println!("{progress}"))
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback(..)")
+    }
+}
+
+/// Stops a running parse or generation from another thread. Clones share
+/// one flag; the run checks it before each page and each file it writes and
+/// fails with `WikipediaError::Cancelled`.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The parsed and filtered dump, as returned by `parse_only`
+#[derive(Debug, Clone, Default)]
+pub struct ParsedCorpus {
+    pub articles: HashMap<String, Article>,
+    pub redirects: HashMap<String, RedirectTarget>,
+    pub files: HashMap<String, FileInfo>,
+    /// Category name → titles of the articles in it
+    pub categories: HashMap<String, Vec<String>>,
+}
+
+/// Where a redirect page points: an article, and optionally a section of it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedirectTarget {
+    pub title: String,
+    pub section: Option<String>,
+}
+
+impl RedirectTarget {
+    /// Splits a raw target like `Article#Section` into its title and section.
+    pub fn parse(target: &str) -> Self {
+        let (title, section) = match target.split_once('#') {
+            Some((title, section)) => (title, Some(section.trim())),
+            None => (target, None),
+        };
+        Self {
+            title: title.trim().to_string(),
+            section: section
+                .filter(|section| !section.is_empty())
+                .map(str::to_string),
+        }
+    }
+}
+
+impl fmt::Display for RedirectTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.section {
+            Some(section) => write!(f, "{}#{section}", self.title),
+            None => f.write_str(&self.title),
+        }
+    }
+}
+
+/// Errors `generate` reports about the run itself rather than its input
+#[derive(Debug)]
+pub enum WikipediaError {
+    /// Parsing kept no articles, with `Config::require_nonempty` set
+    NoArticles,
+    /// Writing `path` failed after `written_so_far` files had been written
+    Io {
+        path: String,
+        written_so_far: usize,
+        source: std::io::Error,
+    },
+    /// `path`, built from a category name or title, would resolve outside the output directory
+    UnsafePath { path: String },
+    /// The run's `CancellationToken` was cancelled; `stats` covers the work done until then
+    Cancelled { stats: GenerationStats },
+}
+
+impl fmt::Display for WikipediaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoArticles => f.write_str(
+                "No articles matched the filters; check the topic filter, title lists and dump flavor",
+            ),
+            Self::Io {
+                path,
+                written_so_far,
+                source,
+            } => write!(
+                f,
+                "Failed to write {path} after writing {written_so_far} files: {source}"
+            ),
+            Self::UnsafePath { path } => {
+                write!(f, "Refusing to write {path}: it is outside the output directory")
+            }
+            Self::Cancelled { stats } => write!(
+                f,
+                "Cancelled after parsing {} and writing {} articles",
+                stats.parse.articles, stats.articles_written
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WikipediaError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::NoArticles | Self::UnsafePath { .. } | Self::Cancelled { .. } => None,
+            Self::Io { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Summary of a generation run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenerationStats {
+    /// Number of articles passed through `write_article_with_collision_handling`
+    pub articles_written: usize,
+    /// Number of base filenames shared by more than one title
+    pub collisions: usize,
+    /// Pages skipped because their title was missing or empty
+    pub invalid_titles: usize,
+    /// Throughput of the parse that produced the articles
+    #[serde(default)]
+    pub parse: ParseMetrics,
+}
+
+/// Counts and timings gathered while parsing, summed across every `parse` call on one parser
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParseMetrics {
+    /// Pages accepted, redirects included
+    pub articles: usize,
+    /// Uncompressed XML bytes read
+    pub bytes: u64,
+    /// Wall-clock time spent in `parse`
+    pub elapsed: Duration,
+    /// Part of `elapsed` spent cleaning wikitext
+    pub cleaning: Duration,
+    /// Pages skipped because their current revision's text was deleted
+    pub deleted_text: usize,
+}
+
+impl ParseMetrics {
+    pub fn articles_per_sec(&self) -> f64 {
+        per_second(self.articles as f64, self.elapsed)
+    }
+
+    pub fn bytes_per_sec(&self) -> f64 {
+        per_second(self.bytes as f64, self.elapsed)
+    }
+
+    /// Time not spent cleaning: reading, decompressing and XML parsing.
+    pub fn io(&self) -> Duration {
+        self.elapsed.saturating_sub(self.cleaning)
+    }
+
+    pub fn add(&mut self, other: ParseMetrics) {
+        self.articles += other.articles;
+        self.bytes += other.bytes;
+        self.elapsed += other.elapsed;
+        self.cleaning += other.cleaning;
+        self.deleted_text += other.deleted_text;
+    }
+}
+
+fn per_second(amount: f64, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64();
+    if seconds > 0.0 { amount / seconds } else { 0.0 }
+}
+
+/// How titles sharing one encoded filename were resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionResolution {
+    /// Short articles concatenated into a single response
+    Merged,
+    /// Each title written to a `__disambig_N` variant behind a disambiguation page
+    Disambiguated,
+    /// A redirect pointing at the shared filename replaced by the real article
+    ReplacedRedirect,
+    /// A case-only variant dropped in favour of the title written first
+    KeptFirst,
+    /// A case-only variant written to `<name>_<hash>` next to the first title
+    HashSuffixed,
+    /// A page outside the main namespace written to `<name>__ns<id>`
+    NamespaceSuffixed,
+}
+
+/// A built-in tool that can be switched on or off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolKind {
+    GetArticle,
+    GetArticleById,
+    ListArticles,
+    ListCategories,
+    Categories,
+    ArticleCategories,
+}
+
+impl ToolKind {
+    pub const ALL: [ToolKind; 6] = [
+        ToolKind::GetArticle,
+        ToolKind::GetArticleById,
+        ToolKind::ListArticles,
+        ToolKind::ListCategories,
+        ToolKind::Categories,
+        ToolKind::ArticleCategories,
+    ];
+
+    /// The tool's name in the manifest and its directory under `tools/`
+    pub fn name(&self) -> &'static str {
+        match self {
+            ToolKind::GetArticle => "get_article",
+            ToolKind::GetArticleById => "get_article_by_id",
+            ToolKind::ListArticles => "list_articles",
+            ToolKind::ListCategories => "list_categories",
+            ToolKind::Categories => "categories",
+            ToolKind::ArticleCategories => "article_categories",
+        }
+    }
+}
+
+/// Format of the text in `get_article` responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentStyle {
+    /// A `# Title` heading above the body, with quotes as `> ` lines
+    #[default]
+    Markdown,
+    /// The bare body text, without the heading or any Markdown markup
+    PlainText,
+}
+
+/// Why `parse` skipped a page, as recorded with `record_excluded`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExclusionReason {
+    /// Outside `include_namespaces`, or titled with a non-article prefix like `Template:`
+    Namespace,
+    /// The title has no topic keyword, though the content does
+    TitleKeywordMiss,
+    /// Neither the title nor the content has a topic keyword
+    ContentRelevanceMiss,
+    /// Listed in `exclude_titles`
+    DenyList,
+    /// Missing from `only_titles`
+    NotListed,
+    /// Nothing left after cleaning
+    Empty,
+    /// Page id outside `id_range`
+    IdRange,
+    /// A case variant of a title already kept, with `case_insensitive_titles`
+    CaseVariant,
+}
+
+/// A page `parse` skipped, and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExcludedTitle {
+    pub title: String,
+    pub reason: ExclusionReason,
+}
+
+/// What `generate` writes to the output directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// The StaticMCP tree: `mcp.json`, resources and tool files
+    #[default]
+    StaticMcp,
+    /// One `articles.jsonl` with a `{"title", "content", "id"}` object per article
+    JsonLines,
+}
+
+/// Window sizes for the `chunks` tool, in whitespace-separated tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkConfig {
+    /// Most tokens in one chunk
+    pub size: usize,
+    /// Tokens each chunk repeats from the end of the one before
+    pub overlap: usize,
+}
+
+/// Order of the names in `list_categories.json`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CategoryOrder {
+    /// By name
+    #[default]
+    Alphabetical,
+    /// Largest category first, ties broken by name
+    BySize,
+}
+
+/// What to do with titles that differ only by letter case (e.g. "NASA" and
+/// "Nasa"), which always encode to the same filename
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseVariantPolicy {
+    /// Append the variant to the first title's response
+    Merge,
+    /// Keep the first title and drop later variants
+    KeepFirst,
+    /// Write each variant to its own file suffixed with a hash of its title
+    HashSuffix,
+}
+
+/// A group of titles that encoded to the same `get_article` filename
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollisionRecord {
+    pub base_filename: String,
+    pub titles: Vec<String>,
+    /// The `get_article` file (without `.json`) each of `titles` is served from
+    #[serde(default)]
+    pub filenames: Vec<String>,
+    pub resolution: CollisionResolution,
+}
+
+/// Metadata extracted from a `File:` description page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileInfo {
+    pub title: String,
+    pub id: u64,
+    pub description: Option<String>,
+    pub date: Option<String>,
+    pub source: Option<String>,
+    pub author: Option<String>,
+    pub license: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -18,6 +496,63 @@ pub struct Manifest {
     pub capabilities: Capabilities,
 }
 
+impl Manifest {
+    /// Serializes the manifest in the shape `compat` expects. The current
+    /// shape is plain `serde`; legacy profiles are built from it.
+    pub fn to_json(&self, compat: McpCompat) -> serde_json::Result<String> {
+        match compat {
+            McpCompat::Current => serde_json::to_string_pretty(self),
+            McpCompat::Legacy => {
+                let resources: Vec<Value> = self
+                    .capabilities
+                    .resources
+                    .iter()
+                    .map(|resource| {
+                        serde_json::json!({
+                            "uri": resource.uri,
+                            "name": resource.name,
+                            "description": resource.description,
+                            "mime_type": resource.mime_type
+                        })
+                    })
+                    .collect();
+                let tools: Vec<Value> = self
+                    .capabilities
+                    .tools
+                    .iter()
+                    .map(|tool| {
+                        serde_json::json!({
+                            "name": tool.name,
+                            "description": tool.description,
+                            "input_schema": tool.input_schema
+                        })
+                    })
+                    .collect();
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "protocol_version": self.protocol_version,
+                    "server_info": self.server_info,
+                    "resources": resources,
+                    "tools": tools
+                }))
+            }
+        }
+    }
+}
+
+/// Shape of the serialized `mcp.json`, for clients written against older
+/// drafts of the protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpCompat {
+    /// The 2024-11-05 shape: `protocolVersion`, `serverInfo`, and `resources`
+    /// and `tools` under `capabilities` with `mimeType` / `inputSchema`
+    #[default]
+    Current,
+    /// Snake-case field names (`protocol_version`, `server_info`, `mime_type`,
+    /// `input_schema`) with `resources` and `tools` at the top level
+    Legacy,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ServerInfo {
     pub name: String,
@@ -65,6 +600,8 @@ pub struct ToolContent {
     #[serde(rename = "type")]
     pub content_type: String,
     pub text: String,
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
 }
 
 /// Trait for customizable article categorization
@@ -72,6 +609,18 @@ pub trait ArticleCategorizer {
     /// Categorize an article based on its title and content
     /// Returns a vector of category names that this article belongs to
     fn categorize(&self, title: &str, content: &str) -> Vec<String>;
+
+    /// Whether the article should be generated at all; vetoed articles are
+    /// left out of every output. Includes everything by default.
+    fn should_include(&self, _title: &str, _content: &str) -> bool {
+        true
+    }
+
+    /// Human-readable description of `category`, listed next to its name in
+    /// `list_categories`. None by default.
+    fn describe(&self, _category: &str) -> Option<String> {
+        None
+    }
 }
 
 /// Default no-op categorizer that doesn't categorize articles
@@ -82,3 +631,58 @@ impl ArticleCategorizer for NoCategorizer {
         Vec::new()
     }
 }
+
+/// Trait for deciding which articles are on topic, as an alternative to the
+/// keyword lists of `TopicFilter` (which implements it too)
+pub trait RelevanceScorer {
+    /// How relevant the article is; higher is more relevant. Articles are
+    /// kept when the score reaches the configured threshold.
+    fn score(&self, title: &str, content: &str) -> f32;
+}
+
+/// A `RelevanceScorer` together with the score an article needs to be kept
+#[derive(Clone)]
+pub struct RelevanceFilter {
+    scorer: Arc<dyn RelevanceScorer + Send + Sync>,
+    threshold: f32,
+}
+
+impl RelevanceFilter {
+    pub fn new(scorer: impl RelevanceScorer + Send + Sync + 'static, threshold: f32) -> Self {
+        Self {
+            scorer: Arc::new(scorer),
+            threshold,
+        }
+    }
+
+    /// Whether the article scores at least the threshold.
+    pub fn is_relevant(&self, title: &str, content: &str) -> bool {
+        self.scorer.score(title, content) >= self.threshold
+    }
+}
+
+impl fmt::Debug for RelevanceFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RelevanceFilter")
+            .field("threshold", &self.threshold)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Trait for splitting text into search index tokens
+pub trait SearchTokenizer {
+    /// Split the given text into the tokens it should be indexed under
+    fn tokenize(&self, text: &str) -> Vec<String>;
+}
+
+/// Default tokenizer that splits on whitespace and punctuation and lowercases each token
+pub struct WhitespaceTokenizer;
+
+impl SearchTokenizer for WhitespaceTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|token| !token.is_empty())
+            .map(|token| token.to_lowercase())
+            .collect()
+    }
+}
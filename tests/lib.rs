@@ -1,7 +1,11 @@
 use std::fs;
 use std::path::PathBuf;
 use tempfile::TempDir;
-use wikipedia_core::{ArticleCategorizer, Config, NoCategorizer, TopicFilter, generate};
+use wikipedia_core::{
+    Article, ArticleCategorizer, Config, ContentStyle, NoCategorizer, SearchTokenizer,
+    StaticMcpGenerator, TopicFilter, WikipediaParser, generate, generate_from_parser, parse_only,
+    update,
+};
 
 struct TestCategorizer;
 
@@ -51,6 +55,40 @@ fn create_test_xml() -> String {
 </mediawiki>"#.to_string()
 }
 
+/// Pages whose titles collide on their article filename: a template and an
+/// article (namespace suffix), two short articles (merged) and two long ones
+/// (disambiguation list). Contents are the wikitext with links unwrapped.
+fn collision_fixture() -> Vec<Article> {
+    let page = |title: &str, raw: &str, namespace: i64| Article {
+        title: title.to_string(),
+        content: raw.replace("[[", "").replace("]]", ""),
+        raw: Some(raw.to_string()),
+        namespace,
+        ..Default::default()
+    };
+    vec![
+        page("Template:Foo", "A [[template]] called Foo.", 10),
+        page("Template Foo", "An [[article]] about template foo.", 0),
+        page("War Article", "Short content about [[war]].", 0),
+        page("War/Article", "Another short article about [[war]].", 0),
+        page("Long Article", &"A long [[article]]. ".repeat(80), 0),
+        page("Long/Article", &"Another long [[article]]. ".repeat(80), 0),
+    ]
+}
+
+/// The text of each content entry of the tool response stored at `path`.
+fn response_texts(
+    path: impl AsRef<std::path::Path>,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+    Ok(response["content"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|content| content["text"].as_str().unwrap().to_string())
+        .collect())
+}
+
 #[test]
 fn test_topic_filter_history() {
     let filter = TopicFilter::History;
@@ -241,6 +279,45 @@ fn test_wikitext_cleaning() {
     assert_eq!(cleaned, "Text with  and .");
 }
 
+#[test]
+fn test_wikitext_clean_options() {
+    use wikipedia_core::parser::{CleanOptions, clean_wikitext, clean_wikitext_with_options};
+
+    let input = "== History ==\n'''Rome''' was founded {{circa}} 753 BC.<ref>Livy</ref> See [[Roman Kingdom|the kingdom]].";
+
+    assert_eq!(
+        clean_wikitext_with_options(input, &CleanOptions::default()),
+        clean_wikitext(input)
+    );
+    assert_eq!(
+        clean_wikitext(input),
+        "History\nRome was founded  753 BC. See the kingdom."
+    );
+
+    let keep_markup = CleanOptions {
+        strip_emphasis: false,
+        unwrap_links: false,
+        strip_heading_markers: false,
+        ..CleanOptions::default()
+    };
+    assert_eq!(
+        clean_wikitext_with_options(input, &keep_markup),
+        "== History ==\n'''Rome''' was founded  753 BC. See [[Roman Kingdom|the kingdom]]."
+    );
+}
+
+#[test]
+fn test_wikitext_blockquotes_and_indentation() {
+    use wikipedia_core::parser::clean_wikitext;
+
+    let input = "Caesar wrote:\n<blockquote>\nVeni, vidi, vici.\nI came: I saw.\n</blockquote>\nReply:\n:First indent\n::Second indent\nRatio 3:1 holds.";
+    let cleaned = clean_wikitext(input);
+    assert_eq!(
+        cleaned,
+        "Caesar wrote:\n> Veni, vidi, vici.\n> I came: I saw.\nReply:\n> First indent\n> > Second indent\nRatio 3:1 holds."
+    );
+}
+
 #[test]
 fn test_collision_handling_short_articles() -> Result<(), Box<dyn std::error::Error>> {
     let temp_dir = TempDir::new()?;
@@ -519,3 +596,4638 @@ fn test_filename_encoding_collision() -> Result<(), Box<dyn std::error::Error>>
 
     Ok(())
 }
+
+#[test]
+fn test_include_files_as_resources() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>File:Colosseum.jpg</title>
+    <id>777</id>
+    <revision>
+      <text>== Summary ==
+{{Information
+|description={{en|1=The Colosseum in Rome at dusk}}
+|date=2010-05-01
+|source=Own work
+|author=[[User:Example|Example]]
+}}
+== Licensing ==
+{{self|cc-by-sa-4.0}}</text>
+    </revision>
+  </page>
+  <page>
+    <title>Roman Empire</title>
+    <id>25458</id>
+    <revision>
+      <text>The Roman Empire was ruled by emperors.</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+
+    fs::write(&input_file, test_xml)?;
+
+    let config = Config::new(input_file, output_dir.clone())
+        .topic_filter(TopicFilter::History)
+        .include_files(true);
+
+    generate(config, NoCategorizer)?;
+
+    let files_content = fs::read_to_string(output_dir.join("resources/files.json"))?;
+    assert!(files_content.contains("File:Colosseum.jpg"));
+    assert!(files_content.contains("The Colosseum in Rome at dusk"));
+    assert!(files_content.contains("cc-by-sa-4.0"));
+
+    let articles_content = fs::read_to_string(output_dir.join("resources/articles.json"))?;
+    assert!(articles_content.contains("Roman Empire"));
+    assert!(!articles_content.contains("Colosseum"));
+
+    let manifest_content = fs::read_to_string(output_dir.join("mcp.json"))?;
+    assert!(manifest_content.contains("wikipedia://files"));
+
+    Ok(())
+}
+
+#[test]
+fn test_article_categories_index() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file, output_dir.clone()).topic_filter(TopicFilter::History);
+
+    generate(config, TestCategorizer)?;
+
+    let index_file = output_dir.join("tools/article_categories/world_war_ii.json");
+    assert!(index_file.exists());
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(index_file)?)?;
+    let text = response["content"][0]["text"].as_str().unwrap();
+    let index: serde_json::Value = serde_json::from_str(text)?;
+    assert_eq!(index["articles"][0]["title"], "World War II");
+    assert_eq!(
+        index["articles"][0]["categories"],
+        serde_json::json!(["war"])
+    );
+
+    assert!(
+        !output_dir
+            .join("tools/article_categories/roman_empire.json")
+            .exists()
+    );
+
+    let manifest_content = fs::read_to_string(output_dir.join("mcp.json"))?;
+    assert!(manifest_content.contains("article_categories"));
+
+    Ok(())
+}
+
+struct HyphenTokenizer;
+
+impl SearchTokenizer for HyphenTokenizer {
+    fn tokenize(&self, text: &str) -> Vec<String> {
+        text.split('-')
+            .map(|token| token.trim().to_lowercase())
+            .collect()
+    }
+}
+
+#[test]
+fn test_search_index_custom_tokenizer() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("output");
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    for (id, title) in [(1, "Anglo-Saxon Kingdom"), (2, "Saxon Kingdom-Wessex")] {
+        parser.articles.insert(
+            title.to_string(),
+            Article {
+                title: title.to_string(),
+                content: "An early medieval kingdom.".to_string(),
+                id,
+                ..Default::default()
+            },
+        );
+    }
+
+    let mut generator =
+        StaticMcpGenerator::new(output_dir.clone(), "en".to_string(), parser, NoCategorizer)
+            .search_index(true)
+            .tokenizer(HyphenTokenizer);
+    generator.generate(false, None)?;
+
+    let search_dir = output_dir.join("tools/search");
+    assert!(search_dir.join("anglo.json").exists());
+    assert!(search_dir.join("wessex.json").exists());
+    assert!(!search_dir.join("kingdom.json").exists());
+
+    let shared = fs::read_to_string(search_dir.join("saxon_kingdom.json"))?;
+    assert!(shared.contains("Anglo-Saxon Kingdom"));
+    assert!(shared.contains("Saxon Kingdom-Wessex"));
+
+    let manifest_content = fs::read_to_string(output_dir.join("mcp.json"))?;
+    assert!(manifest_content.contains("\"search\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_dangling_link_report() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>Roman Empire</title>
+    <id>1</id>
+    <revision>
+      <text>The empire fought the [[Punic Wars|Carthaginians]] and succeeded the [[Roman Republic]]. [[Category:Empires]]</text>
+    </revision>
+  </page>
+  <page>
+    <title>Roman Republic</title>
+    <id>2</id>
+    <revision>
+      <text>The republic preceded the empire.</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+
+    fs::write(&input_file, test_xml)?;
+
+    let config = Config::new(input_file.clone(), output_dir.clone())
+        .topic_filter(TopicFilter::History)
+        .report_dangling_links(true);
+
+    generate(config, NoCategorizer)?;
+
+    let stats: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/stats.json"),
+    )?)?;
+    let stats: serde_json::Value = serde_json::from_str(stats["text"].as_str().unwrap())?;
+    assert_eq!(stats["links"]["total"], 2);
+    assert_eq!(stats["links"]["dangling"], 1);
+
+    let strict = Config::new(input_file, temp_dir.path().join("strict"))
+        .topic_filter(TopicFilter::History)
+        .max_dangling_ratio(0.25);
+    let error = generate(strict, NoCategorizer).unwrap_err();
+    assert!(error.to_string().contains("dangling"));
+
+    Ok(())
+}
+
+#[test]
+fn test_custom_article_header() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file, output_dir.clone())
+        .topic_filter(TopicFilter::History)
+        .article_header(|article| {
+            format!("---\ntitle: {}\nid: {}\n---", article.title, article.id)
+        });
+
+    generate(config, NoCategorizer)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("tools/get_article/roman_empire.json"),
+    )?)?;
+    let text = response["content"][0]["text"].as_str().unwrap();
+    assert!(text.starts_with("---\ntitle: Roman Empire\nid: 25458\n---\n\n"));
+    assert!(text.contains("ancient Rome"));
+    assert!(!text.contains("# Roman Empire"));
+
+    Ok(())
+}
+
+#[test]
+fn test_collisions_report() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>War Article</title>
+    <id>1</id>
+    <revision>
+      <text>Short content about historical war events.</text>
+    </revision>
+  </page>
+  <page>
+    <title>War/Article</title>
+    <id>2</id>
+    <revision>
+      <text>Another short article about war history.</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+
+    fs::write(&input_file, test_xml)?;
+
+    let config = Config::new(input_file, output_dir.clone()).topic_filter(TopicFilter::History);
+
+    let stats = generate(config, NoCategorizer)?;
+    assert_eq!(stats.collisions, 1);
+    assert_eq!(stats.articles_written, 2);
+
+    let report: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/collisions.json"),
+    )?)?;
+    let records: serde_json::Value = serde_json::from_str(report["text"].as_str().unwrap())?;
+    assert_eq!(records.as_array().unwrap().len(), 1);
+    assert_eq!(records[0]["base_filename"], "war_article");
+    assert_eq!(records[0]["resolution"], "merged");
+
+    let titles = records[0]["titles"].as_array().unwrap();
+    assert!(titles.contains(&serde_json::json!("War Article")));
+    assert!(titles.contains(&serde_json::json!("War/Article")));
+
+    Ok(())
+}
+
+#[test]
+fn test_abstract_dump_parsing() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("abstract.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let test_xml = r#"<feed>
+<doc>
+<title>Wikipedia: Roman Empire</title>
+<url>https://en.wikipedia.org/wiki/Roman_Empire</url>
+<abstract>The Roman Empire was the post-Republican period of ancient Rome.</abstract>
+<links>
+<sublink linktype="nav"><anchor>History</anchor><link>https://en.wikipedia.org/wiki/Roman_Empire#History</link></sublink>
+</links>
+</doc>
+</feed>"#;
+
+    fs::write(&input_file, test_xml)?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &Some(TopicFilter::History))?;
+
+    let article = parser.articles.get("Roman Empire").unwrap();
+    assert!(article.content.contains("post-Republican period"));
+
+    let stub_file = temp_dir.path().join("stub.xml");
+    fs::write(
+        &stub_file,
+        r#"<mediawiki>
+  <page>
+    <title>Roman Empire</title>
+    <id>25458</id>
+    <revision>
+      <id>1</id>
+      <text bytes="1234" id="99" />
+    </revision>
+  </page>
+</mediawiki>"#,
+    )?;
+
+    let config = Config::new(stub_file, output_dir);
+    let error = generate(config, NoCategorizer).unwrap_err();
+    assert!(error.to_string().contains("stub-meta"));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn test_pinned_generation_timestamp() -> Result<(), Box<dyn std::error::Error>> {
+    use chrono::TimeZone;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    fs::write(&input_file, create_test_xml())?;
+
+    let pinned = chrono::Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+    let config = Config::new(input_file, output_dir.clone())
+        .topic_filter(TopicFilter::History)
+        .generated_at(pinned);
+
+    generate(config, NoCategorizer)?;
+
+    let stats: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/stats.json"),
+    )?)?;
+    let stats: serde_json::Value = serde_json::from_str(stats["text"].as_str().unwrap())?;
+    assert_eq!(stats["generated_at"], "2024-01-02 03:04:05 UTC");
+
+    Ok(())
+}
+
+#[test]
+fn test_pinned_generation_timestamp_from_string() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    fs::write(&input_file, create_test_xml())?;
+
+    // The same call compiles with and without the `chrono` feature
+    let config = Config::new(input_file, output_dir.clone())
+        .topic_filter(TopicFilter::History)
+        .generated_at("2024-01-02 03:04:05 UTC");
+
+    generate(config, NoCategorizer)?;
+
+    let stats: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/stats.json"),
+    )?)?;
+    let stats: serde_json::Value = serde_json::from_str(stats["text"].as_str().unwrap())?;
+    assert_eq!(stats["generated_at"], "2024-01-02 03:04:05 UTC");
+
+    Ok(())
+}
+
+#[test]
+fn test_invalid_titles_are_counted() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <id>41</id>
+    <revision>
+      <text>A page without any title element about an ancient war.</text>
+    </revision>
+  </page>
+  <page>
+    <title>   </title>
+    <id>42</id>
+    <revision>
+      <text>A page with a blank title about an ancient war.</text>
+    </revision>
+  </page>
+  <page>
+    <title>Roman Empire</title>
+    <id>25458</id>
+    <revision>
+      <text>The Roman Empire was ruled by emperors.</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+
+    fs::write(&input_file, test_xml)?;
+
+    let config = Config::new(input_file.clone(), output_dir).topic_filter(TopicFilter::History);
+    let stats = generate(config, NoCategorizer)?;
+    assert_eq!(stats.invalid_titles, 2);
+    assert_eq!(stats.articles_written, 1);
+
+    let parser = WikipediaParser::new("en".to_string());
+    let mut invalid_ids = Vec::new();
+    parser.parse_streaming_events(
+        Box::new(fs::File::open(&input_file)?),
+        false,
+        &None,
+        |event| {
+            if let wikipedia_core::parser::PageEvent::InvalidTitle { id } = event {
+                invalid_ids.push(id);
+            }
+            Ok(())
+        },
+    )?;
+    assert_eq!(invalid_ids, vec![41, 42]);
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_only_returns_corpus() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file, output_dir.clone()).topic_filter(TopicFilter::History);
+    let corpus = parse_only(config, TestCategorizer)?;
+
+    let mut titles: Vec<&String> = corpus.articles.keys().collect();
+    titles.sort();
+    assert_eq!(titles, vec!["Roman Empire", "World War II"]);
+    assert_eq!(corpus.categories["war"], vec!["World War II".to_string()]);
+    assert!(!output_dir.exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_latest_revision_is_selected() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("history.xml");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>Roman Empire</title>
+    <id>25458</id>
+    <revision>
+      <id>2</id>
+      <timestamp>2024-03-01T12:00:00Z</timestamp>
+      <text>The newer text about the ancient empire.</text>
+    </revision>
+    <revision>
+      <id>1</id>
+      <timestamp>2019-06-15T08:30:00Z</timestamp>
+      <text>The older text about the ancient empire.</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+
+    fs::write(&input_file, test_xml)?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &Some(TopicFilter::History))?;
+
+    let article = &parser.articles["Roman Empire"];
+    assert_eq!(article.content, "The newer text about the ancient empire.");
+    assert_eq!(article.timestamp.as_deref(), Some("2024-03-01T12:00:00Z"));
+    assert_eq!(article.id, 25458);
+
+    Ok(())
+}
+
+#[test]
+fn test_article_content_mime() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file, output_dir.clone()).topic_filter(TopicFilter::History);
+    generate(config, NoCategorizer)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("tools/get_article/roman_empire.json"),
+    )?)?;
+    assert_eq!(response["content"][0]["mimeType"], "text/markdown");
+
+    let manifest_content = fs::read_to_string(output_dir.join("mcp.json"))?;
+    assert!(manifest_content.contains("text/markdown"));
+
+    let list_content = fs::read_to_string(output_dir.join("tools/list_articles.json"))?;
+    assert!(!list_content.contains("mimeType"));
+
+    Ok(())
+}
+
+#[test]
+fn test_namespace_selection() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>Roman Empire</title>
+    <ns>0</ns>
+    <id>25458</id>
+    <revision>
+      <text>The Roman Empire was ruled by emperors.</text>
+    </revision>
+  </page>
+  <page>
+    <title>Template:Infobox country</title>
+    <ns>10</ns>
+    <id>100</id>
+    <revision>
+      <text>An infobox template for countries.</text>
+    </revision>
+  </page>
+  <page>
+    <title>Help:Editing</title>
+    <ns>12</ns>
+    <id>200</id>
+    <revision>
+      <text>How to edit pages.</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+
+    fs::write(&input_file, test_xml)?;
+
+    let mut parser = WikipediaParser::new("en".to_string()).include_namespaces(vec![0, 10]);
+    parser.parse(&input_file, None, &None)?;
+    assert_eq!(parser.articles["Template:Infobox country"].namespace, 10);
+    assert_eq!(parser.articles["Roman Empire"].namespace, 0);
+    assert!(!parser.articles.contains_key("Help:Editing"));
+
+    let config = Config::new(input_file, output_dir.clone()).include_namespaces(vec![0, 10]);
+    generate(config, NoCategorizer)?;
+
+    let articles_content = fs::read_to_string(output_dir.join("resources/articles.json"))?;
+    assert!(articles_content.contains("Template:Infobox country"));
+    assert!(articles_content.contains("Roman Empire"));
+    assert!(!articles_content.contains("Help:Editing"));
+
+    Ok(())
+}
+
+#[test]
+fn test_emit_schemas() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file.clone(), output_dir.clone()).emit_schemas(true);
+    generate(config, NoCategorizer)?;
+
+    let schema: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("schemas/tool_response.schema.json"),
+    )?)?;
+    assert_eq!(schema["title"], "ToolResponse");
+    assert!(output_dir.join("schemas/stats.schema.json").exists());
+
+    let plain_output = temp_dir.path().join("plain");
+    generate(Config::new(input_file, plain_output.clone()), NoCategorizer)?;
+    assert!(!plain_output.join("schemas").exists());
+
+    Ok(())
+}
+
+struct SingleCategory;
+
+impl ArticleCategorizer for SingleCategory {
+    fn categorize(&self, _title: &str, _content: &str) -> Vec<String> {
+        vec!["Big".to_string()]
+    }
+}
+
+#[test]
+fn test_max_category_entries_overflow() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("output");
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    for id in 1..=250 {
+        let title = format!("Article {id}");
+        parser.articles.insert(
+            title.clone(),
+            Article {
+                title,
+                content: "Member of a very large category.".to_string(),
+                id,
+                ..Default::default()
+            },
+        );
+    }
+
+    let mut generator =
+        StaticMcpGenerator::new(output_dir.clone(), "en".to_string(), parser, SingleCategory)
+            .max_category_entries(100);
+    generator.generate(false, None)?;
+
+    let read_payload = |path: &str| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let response: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_dir.join(path))?)?;
+        Ok(serde_json::from_str(
+            response["content"][0]["text"].as_str().unwrap_or_default(),
+        )?)
+    };
+
+    let primary = read_payload("tools/categories/Big.json")?;
+    assert_eq!(primary["articles"].as_array().map(Vec::len), Some(100));
+    assert_eq!(primary["count"], 250);
+    assert_eq!(primary["truncated"], true);
+    assert!(
+        primary["note"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("page 2")
+    );
+
+    let second = read_payload("tools/categories/Big/2.json")?;
+    assert_eq!(second["articles"].as_array().map(Vec::len), Some(100));
+    assert_eq!(second["truncated"], true);
+
+    let last = read_payload("tools/categories/Big/3.json")?;
+    assert_eq!(last["articles"].as_array().map(Vec::len), Some(50));
+    assert!(last.get("truncated").is_none());
+    assert!(!output_dir.join("tools/categories/Big/4.json").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_generate_from_parser() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("output");
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    for (id, title) in [(1, "Hadrian"), (2, "Trajan")] {
+        parser.articles.insert(
+            title.to_string(),
+            Article {
+                title: title.to_string(),
+                content: format!("{title} was a Roman emperor."),
+                id,
+                ..Default::default()
+            },
+        );
+    }
+
+    let config = Config::new(temp_dir.path().join("missing.xml"), output_dir.clone());
+    let stats = generate_from_parser(config, parser, NoCategorizer)?;
+    assert_eq!(stats.articles_written, 2);
+
+    let hadrian = fs::read_to_string(output_dir.join("tools/get_article/hadrian.json"))?;
+    assert!(hadrian.contains("Hadrian was a Roman emperor."));
+    assert!(output_dir.join("tools/get_article/trajan.json").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_content_addressed_filenames() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+
+    let hashed_filename =
+        |content: &str, output_dir: PathBuf| -> Result<String, Box<dyn std::error::Error>> {
+            let mut parser = WikipediaParser::new("en".to_string());
+            parser.articles.insert(
+                "Roman Empire".to_string(),
+                Article {
+                    title: "Roman Empire".to_string(),
+                    content: content.to_string(),
+                    id: 1,
+                    ..Default::default()
+                },
+            );
+            let config = Config::new(PathBuf::new(), output_dir.clone()).content_addressed(true);
+            generate_from_parser(config, parser, NoCategorizer)?;
+
+            let index: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+                output_dir.join("resources/article_index.json"),
+            )?)?;
+            let index: serde_json::Value = serde_json::from_str(index["text"].as_str().unwrap())?;
+            let filename = index["Roman Empire"].as_str().unwrap().to_string();
+            assert!(
+                output_dir
+                    .join("tools/get_article")
+                    .join(&filename)
+                    .exists()
+            );
+            assert!(
+                !output_dir
+                    .join("tools/get_article/roman_empire.json")
+                    .exists()
+            );
+            Ok(filename)
+        };
+
+    let first = hashed_filename("The empire was founded.", temp_dir.path().join("first"))?;
+    let same = hashed_filename("The empire was founded.", temp_dir.path().join("same"))?;
+    let updated = hashed_filename("The empire fell.", temp_dir.path().join("updated"))?;
+
+    assert!(first.starts_with("roman_empire.") && first.ends_with(".json"));
+    assert_eq!(first.len(), "roman_empire.".len() + 8 + ".json".len());
+    assert_eq!(first, same);
+    assert_ne!(first, updated);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "bzip2")]
+fn test_extract_titles_from_multistream() -> Result<(), Box<dyn std::error::Error>> {
+    use bzip2::Compression;
+    use bzip2::write::BzEncoder;
+    use std::io::Write;
+
+    let temp_dir = TempDir::new()?;
+    let dump_path = temp_dir.path().join("dump-multistream.xml.bz2");
+    let index_path = temp_dir.path().join("dump-multistream-index.txt");
+
+    let page = |id: u64, title: &str| {
+        format!(
+            "<page><title>{title}</title><ns>0</ns><id>{id}</id><revision><text>{title} is a notable subject.</text></revision></page>"
+        )
+    };
+    let streams = [
+        "<mediawiki><siteinfo><sitename>Wikipedia</sitename></siteinfo>".to_string(),
+        format!("{}{}", page(1, "Roman Empire"), page(2, "Computer Science")),
+        format!(
+            "{}{}",
+            page(3, "Ancient Greece"),
+            page(4, "Byzantine Empire")
+        ),
+        "</mediawiki>".to_string(),
+    ];
+
+    let mut dump = Vec::new();
+    let mut offsets = Vec::new();
+    for stream in &streams {
+        offsets.push(dump.len());
+        let mut encoder = BzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(stream.as_bytes())?;
+        dump.extend(encoder.finish()?);
+    }
+    fs::write(&dump_path, dump)?;
+    fs::write(
+        &index_path,
+        format!(
+            "{a}:1:Roman Empire\n{a}:2:Computer Science\n{b}:3:Ancient Greece\n{b}:4:Byzantine Empire\n",
+            a = offsets[1],
+            b = offsets[2]
+        ),
+    )?;
+
+    let parser = WikipediaParser::new("en".to_string());
+    let mut articles = parser.extract_titles(
+        &dump_path,
+        &index_path,
+        &["Byzantine Empire", "Roman Empire"],
+    )?;
+    articles.sort_by(|a, b| a.title.cmp(&b.title));
+
+    let titles: Vec<&str> = articles.iter().map(|a| a.title.as_str()).collect();
+    assert_eq!(titles, ["Byzantine Empire", "Roman Empire"]);
+    assert!(articles[0].content.contains("notable subject"));
+
+    let missing = parser.extract_titles(&dump_path, &index_path, &["Atlantis"])?;
+    assert!(missing.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_exclude_titles() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    fs::write(&input_file, create_test_xml())?;
+
+    let excluded = std::collections::HashSet::from(["Computer_Science".to_string()]);
+    let config =
+        Config::new(input_file.clone(), output_dir.clone()).exclude_titles(excluded.clone());
+    generate(config, NoCategorizer)?;
+
+    let articles_content = fs::read_to_string(output_dir.join("resources/articles.json"))?;
+    assert!(!articles_content.contains("Computer Science"));
+    assert!(articles_content.contains("Roman Empire"));
+    assert!(
+        !output_dir
+            .join("tools/get_article/computer_science.json")
+            .exists()
+    );
+
+    let parser = WikipediaParser::new("en".to_string()).exclude_titles(excluded);
+    let mut streamed = Vec::new();
+    parser.parse_streaming(
+        Box::new(fs::File::open(&input_file)?),
+        false,
+        &None,
+        |title, _article| {
+            streamed.push(title.to_string());
+            Ok(())
+        },
+    )?;
+    assert!(streamed.contains(&"Roman Empire".to_string()));
+    assert!(!streamed.contains(&"Computer Science".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn test_stats_content_length_histogram() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    fs::write(&input_file, create_test_xml())?;
+
+    generate(Config::new(input_file, output_dir.clone()), NoCategorizer)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/stats.json"),
+    )?)?;
+    let stats: serde_json::Value = serde_json::from_str(response["text"].as_str().unwrap())?;
+    let lengths = &stats["content_lengths"];
+    let total = stats["total_articles"].as_u64().unwrap();
+
+    assert_eq!(lengths["histogram"]["<1k"].as_u64(), Some(total));
+    assert_eq!(lengths["histogram"]["1k-5k"], 0);
+    assert_eq!(lengths["histogram"][">20k"], 0);
+    assert!(lengths["min"].as_u64().unwrap() > 0);
+    assert!(lengths["max"].as_u64().unwrap() < 1000);
+    let mean = lengths["mean"].as_f64().unwrap();
+    assert!(mean >= lengths["min"].as_f64().unwrap() && mean <= lengths["max"].as_f64().unwrap());
+
+    Ok(())
+}
+
+#[test]
+fn test_categorize_during_parse_matches_generation() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+
+    fs::write(&input_file, create_test_xml())?;
+
+    let sorted = |mut categories: std::collections::HashMap<String, Vec<String>>| {
+        for titles in categories.values_mut() {
+            titles.sort();
+        }
+        categories
+            .into_iter()
+            .collect::<std::collections::BTreeMap<_, _>>()
+    };
+
+    let output_dir = temp_dir.path().join("output");
+    let at_generation = parse_only(
+        Config::new(input_file.clone(), output_dir.clone()),
+        TestCategorizer,
+    )?;
+    let at_parse = parse_only(
+        Config::new(input_file.clone(), output_dir).categorize_during_parse(true),
+        TestCategorizer,
+    )?;
+    assert!(!at_generation.categories.is_empty());
+    assert_eq!(
+        sorted(at_generation.categories),
+        sorted(at_parse.categories)
+    );
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse_categorized(&input_file, None, &None, &TestCategorizer)?;
+    assert!(parser.categories.is_some());
+
+    let parse_output = temp_dir.path().join("parse_output");
+    let generation_output = temp_dir.path().join("generation_output");
+    generate(
+        Config::new(input_file.clone(), parse_output.clone()).categorize_during_parse(true),
+        TestCategorizer,
+    )?;
+    generate(
+        Config::new(input_file, generation_output.clone()),
+        TestCategorizer,
+    )?;
+    assert_eq!(
+        fs::read_to_string(parse_output.join("tools/categories/war.json"))?,
+        fs::read_to_string(generation_output.join("tools/categories/war.json"))?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_localized_server_name() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    fs::write(&input_file, create_test_xml())?;
+
+    let server_name = |output_dir: &std::path::Path| -> Result<String, Box<dyn std::error::Error>> {
+        let manifest: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(output_dir.join("mcp.json"))?)?;
+        Ok(manifest["serverInfo"]["name"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string())
+    };
+
+    let spanish = temp_dir.path().join("spanish");
+    generate(
+        Config::new(input_file.clone(), spanish.clone()).language("es"),
+        NoCategorizer,
+    )?;
+    assert_eq!(server_name(&spanish)?, "Wikipedia (Español) StaticMCP");
+
+    let unknown = temp_dir.path().join("unknown");
+    generate(
+        Config::new(input_file.clone(), unknown.clone()).language("xx"),
+        NoCategorizer,
+    )?;
+    assert_eq!(server_name(&unknown)?, "Wikipedia XX StaticMCP");
+
+    let custom = temp_dir.path().join("custom");
+    generate(
+        Config::new(input_file, custom.clone())
+            .language("es")
+            .server_name("Enciclopedia Histórica"),
+        NoCategorizer,
+    )?;
+    assert_eq!(server_name(&custom)?, "Enciclopedia Histórica");
+
+    Ok(())
+}
+
+#[test]
+fn test_count_matches_parse() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    fs::write(&input_file, create_test_xml())?;
+
+    let history = Some(TopicFilter::History);
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &history)?;
+
+    let counter = WikipediaParser::new("en".to_string());
+    assert_eq!(counter.count(&input_file, &history)?, parser.articles.len());
+    assert!(counter.count(&input_file, &history)? > 0);
+    assert!(counter.articles.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_deleted_text_pages_are_skipped() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>Suppressed Page</title>
+    <ns>0</ns>
+    <id>1</id>
+    <revision>
+      <timestamp>2020-01-01T00:00:00Z</timestamp>
+      <text bytes="28" xml:space="preserve">An earlier, visible revision.</text>
+    </revision>
+    <revision>
+      <timestamp>2021-01-01T00:00:00Z</timestamp>
+      <text deleted="deleted" />
+    </revision>
+  </page>
+  <page>
+    <title>Roman Empire</title>
+    <ns>0</ns>
+    <id>2</id>
+    <revision>
+      <timestamp>2021-01-01T00:00:00Z</timestamp>
+      <text bytes="39" xml:space="preserve">The Roman Empire was ruled by emperors.</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+
+    fs::write(&input_file, test_xml)?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+
+    assert!(!parser.articles.contains_key("Suppressed Page"));
+    assert_eq!(parser.metrics.deleted_text, 1);
+    assert_eq!(
+        parser.articles["Roman Empire"].content,
+        "The Roman Empire was ruled by emperors."
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_incremental_update() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("output");
+
+    let parser_with = |articles: &[(u64, &str, &str)]| {
+        let mut parser = WikipediaParser::new("en".to_string());
+        for (id, title, content) in articles {
+            parser.articles.insert(
+                title.to_string(),
+                Article {
+                    title: title.to_string(),
+                    content: content.to_string(),
+                    id: *id,
+                    ..Default::default()
+                },
+            );
+        }
+        parser
+    };
+    let config = || Config::new(PathBuf::new(), output_dir.clone());
+    let article_dir = output_dir.join("tools/get_article");
+
+    assert!(update(config(), parser_with(&[]), TestCategorizer).is_err());
+
+    generate_from_parser(
+        config(),
+        parser_with(&[
+            (1, "Hadrian", "Hadrian built a wall."),
+            (2, "Trajan", "Trajan expanded the empire."),
+            (3, "Marcomannic War", "A war on the Danube."),
+        ]),
+        TestCategorizer,
+    )?;
+    let hadrian_before = fs::read_to_string(article_dir.join("hadrian.json"))?;
+    let trajan_before = fs::read_to_string(article_dir.join("trajan.json"))?;
+
+    let stats = update(
+        config(),
+        parser_with(&[
+            (
+                2,
+                "Trajan",
+                "Trajan expanded the empire to its greatest extent.",
+            ),
+            (4, "Gothic War", "A war against the Goths."),
+        ]),
+        TestCategorizer,
+    )?;
+    assert_eq!(stats.articles_written, 2);
+
+    assert_eq!(
+        fs::read_to_string(article_dir.join("hadrian.json"))?,
+        hadrian_before
+    );
+    let trajan_after = fs::read_to_string(article_dir.join("trajan.json"))?;
+    assert_ne!(trajan_after, trajan_before);
+    assert!(trajan_after.contains("greatest extent"));
+    assert!(!trajan_after.contains("Multiple articles found"));
+    assert!(article_dir.join("gothic_war.json").exists());
+    let trajan_by_id = fs::read_to_string(output_dir.join("tools/get_article_by_id/2.json"))?;
+    assert!(trajan_by_id.contains("greatest extent"));
+
+    let articles_content = fs::read_to_string(output_dir.join("resources/articles.json"))?;
+    for title in ["Hadrian", "Trajan", "Marcomannic War", "Gothic War"] {
+        assert!(articles_content.contains(title));
+    }
+
+    let stats_response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/stats.json"),
+    )?)?;
+    let stats_json: serde_json::Value =
+        serde_json::from_str(stats_response["text"].as_str().unwrap())?;
+    assert_eq!(stats_json["total_articles"], 4);
+
+    let war_category = fs::read_to_string(output_dir.join("tools/categories/war.json"))?;
+    assert!(war_category.contains("Marcomannic War"));
+    assert!(war_category.contains("Gothic War"));
+
+    Ok(())
+}
+
+#[test]
+fn test_update_transforms_derived_files() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::ChunkConfig;
+
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("output");
+
+    let parser_with = |content: &str| {
+        let mut parser = WikipediaParser::new("en".to_string());
+        parser.articles.insert(
+            "Trajan".to_string(),
+            Article {
+                title: "Trajan".to_string(),
+                content: content.to_string(),
+                id: 2,
+                ..Default::default()
+            },
+        );
+        parser
+    };
+    let config = || {
+        Config::new(PathBuf::new(), output_dir.clone())
+            .content_transform(|content, _| content.to_uppercase())
+            .emit_summaries(true)
+            .chunking(Some(ChunkConfig {
+                size: 50,
+                overlap: 0,
+            }))
+    };
+
+    generate_from_parser(
+        config(),
+        parser_with("Trajan expanded the empire."),
+        TestCategorizer,
+    )?;
+    update(
+        config(),
+        parser_with("Trajan expanded the empire to its greatest extent."),
+        TestCategorizer,
+    )?;
+
+    let summary = fs::read_to_string(output_dir.join("tools/get_summary/trajan.json"))?;
+    assert!(summary.contains("GREATEST EXTENT"));
+    assert!(!summary.contains("greatest extent"));
+    let chunk = fs::read_to_string(output_dir.join("tools/chunks/trajan/1.json"))?;
+    assert!(chunk.contains("GREATEST EXTENT"));
+    assert!(!chunk.contains("greatest extent"));
+
+    Ok(())
+}
+
+#[test]
+fn test_case_variant_policies() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::CaseVariantPolicy;
+
+    let temp_dir = TempDir::new()?;
+
+    let run = |policy: CaseVariantPolicy, name: &str| {
+        let mut parser = WikipediaParser::new("en".to_string());
+        for (id, title, subject) in [(1, "NASA", "space agency"), (2, "Nasa", "river")] {
+            parser.articles.insert(
+                title.to_string(),
+                Article {
+                    title: title.to_string(),
+                    content: format!("The {subject}. {}", "Long article text. ".repeat(80)),
+                    id,
+                    ..Default::default()
+                },
+            );
+        }
+        let output_dir = temp_dir.path().join(name);
+        let config = Config::new(PathBuf::new(), output_dir.clone()).case_variants(policy);
+        generate_from_parser(config, parser, NoCategorizer).map(|_| output_dir)
+    };
+    let article_files = |output_dir: &std::path::Path| -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(output_dir.join("tools/get_article"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+    let resolution = |output_dir: &std::path::Path| -> serde_json::Value {
+        let response: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(output_dir.join("resources/collisions.json")).unwrap(),
+        )
+        .unwrap();
+        let records: serde_json::Value =
+            serde_json::from_str(response["text"].as_str().unwrap()).unwrap();
+        records[0]["resolution"].clone()
+    };
+
+    let merged = run(CaseVariantPolicy::Merge, "merge")?;
+    assert_eq!(article_files(&merged), ["nasa.json"]);
+    let merged_text = fs::read_to_string(merged.join("tools/get_article/nasa.json"))?;
+    assert!(merged_text.contains("space agency") && merged_text.contains("river"));
+    assert_eq!(resolution(&merged), "merged");
+
+    let kept = run(CaseVariantPolicy::KeepFirst, "keep_first")?;
+    assert_eq!(article_files(&kept), ["nasa.json"]);
+    let kept_text = fs::read_to_string(kept.join("tools/get_article/nasa.json"))?;
+    assert!(kept_text.contains("space agency") != kept_text.contains("river"));
+    assert_eq!(resolution(&kept), "kept_first");
+
+    let hashed = run(CaseVariantPolicy::HashSuffix, "hash_suffix")?;
+    let files = article_files(&hashed);
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0], "nasa.json");
+    assert!(files[1].starts_with("nasa_") && files[1].len() == "nasa_.json".len() + 8);
+    assert_eq!(resolution(&hashed), "hash_suffixed");
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_warnings() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::parser::{ParseWarning, ParseWarningKind};
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>Roman Empire</title>
+    <ns>0</ns>
+    <id>not-a-number</id>
+    <revision>
+      <id>12345</id>
+      <timestamp>2021-01-01T00:00:00Z</timestamp>
+      <text>The Roman Empire was ruled by emperors.</text>
+    </revision>
+  </page>
+  <page>
+    <title>Byzantine Empire</title>
+    <ns>0</ns>
+    <id>2</id>
+    <revision>
+      <text>The Byzantine Empire was the eastern half.</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+
+    fs::write(&input_file, test_xml)?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+
+    assert_eq!(parser.articles["Roman Empire"].id, 0);
+    assert_eq!(parser.articles["Byzantine Empire"].id, 2);
+    assert_eq!(
+        parser.warnings,
+        [
+            ParseWarning {
+                title: Some("Roman Empire".to_string()),
+                kind: ParseWarningKind::InvalidId("not-a-number".to_string()),
+            },
+            ParseWarning {
+                title: Some("Byzantine Empire".to_string()),
+                kind: ParseWarningKind::MissingTimestamp,
+            },
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_article_metadata_resource() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file.clone(), output_dir.clone()).article_metadata(true);
+    generate(config, TestCategorizer)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/article_metadata.json"),
+    )?)?;
+    let entries: Vec<serde_json::Value> = serde_json::from_str(response["text"].as_str().unwrap())?;
+    assert!(!entries.is_empty());
+
+    let articles: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/articles.json"),
+    )?)?;
+    let titles: Vec<String> = serde_json::from_str(articles["text"].as_str().unwrap())?;
+    assert_eq!(entries.len(), titles.len());
+
+    for entry in &entries {
+        let filename = entry["filename"].as_str().unwrap();
+        assert!(output_dir.join("tools/get_article").join(filename).exists());
+        assert!(entry["length"].as_u64().unwrap() > 0);
+    }
+    let world_war = entries
+        .iter()
+        .find(|entry| entry["title"] == "World War II")
+        .unwrap();
+    assert_eq!(world_war["filename"], "world_war_ii.json");
+    assert_eq!(world_war["categories"], serde_json::json!(["war"]));
+
+    let manifest_content = fs::read_to_string(output_dir.join("mcp.json"))?;
+    assert!(manifest_content.contains("wikipedia://article_metadata"));
+
+    let plain_output = temp_dir.path().join("plain");
+    generate(Config::new(input_file, plain_output.clone()), NoCategorizer)?;
+    assert!(
+        !plain_output
+            .join("resources/article_metadata.json")
+            .exists()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_short_description_extraction() {
+    use wikipedia_core::parser::extract_short_description;
+
+    assert_eq!(
+        extract_short_description("{{Short description|Ancient empire in Europe}}\nText"),
+        Some("Ancient empire in Europe".to_string())
+    );
+    assert_eq!(
+        extract_short_description("{{short description|none}}"),
+        None
+    );
+    assert_eq!(extract_short_description("No template here."), None);
+}
+
+#[test]
+fn test_enabled_tools() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::ToolKind;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    fs::write(&input_file, create_test_xml())?;
+
+    let config =
+        Config::new(input_file, output_dir.clone()).enabled_tools(vec![ToolKind::GetArticle]);
+    generate(config, TestCategorizer)?;
+
+    assert!(
+        output_dir
+            .join("tools/get_article/roman_empire.json")
+            .exists()
+    );
+    assert!(!output_dir.join("tools/list_articles.json").exists());
+    assert!(!output_dir.join("tools/list_categories.json").exists());
+    assert!(!output_dir.join("tools/categories").exists());
+    assert!(!output_dir.join("tools/article_categories").exists());
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(output_dir.join("mcp.json"))?)?;
+    let tool_names: Vec<&str> = manifest["capabilities"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(tool_names, ["get_article"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_language_detected_from_siteinfo() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let xml = create_test_xml().replacen(
+        "<mediawiki>",
+        "<mediawiki>\n  <siteinfo>\n    <sitename>Wikipedia</sitename>\n    <dbname>dewiki</dbname>\n    <languagecode>de</languagecode>\n  </siteinfo>",
+        1,
+    );
+    fs::write(&input_file, xml)?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+    assert_eq!(parser.site_language.as_deref(), Some("de"));
+    assert_eq!(parser.articles.len(), 3);
+
+    generate(Config::new(input_file, output_dir.clone()), TestCategorizer)?;
+    let stats: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/stats.json"),
+    )?)?;
+    let stats: serde_json::Value = serde_json::from_str(stats["text"].as_str().unwrap())?;
+    assert_eq!(stats["language"], "de");
+
+    Ok(())
+}
+
+#[test]
+fn test_generator_builder() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::StaticMcpGeneratorBuilder;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    fs::write(&input_file, create_test_xml())?;
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+
+    let config = Config::new(input_file, output_dir.clone())
+        .search_index(true)
+        .server_name("Builder Wiki");
+    let mut generator = StaticMcpGeneratorBuilder::new(config).build(parser, TestCategorizer);
+    generator.generate(false, None)?;
+
+    assert_eq!(generator.stats().articles_written, 3);
+    assert!(output_dir.join("tools/search").is_dir());
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(output_dir.join("mcp.json"))?)?;
+    assert_eq!(manifest["serverInfo"]["name"], "Builder Wiki");
+
+    Ok(())
+}
+
+#[test]
+fn test_article_frontmatter() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let xml = create_test_xml().replacen(
+        "</mediawiki>",
+        "  <page>\n    <title>WWII</title>\n    <id>1</id>\n    <redirect>World War II</redirect>\n    <revision>\n      <text>#REDIRECT [[World War II]]</text>\n    </revision>\n  </page>\n</mediawiki>",
+        1,
+    );
+    fs::write(&input_file, xml)?;
+
+    let config = Config::new(input_file, output_dir.clone())
+        .exact_matches(true)
+        .frontmatter(true);
+    generate(config, TestCategorizer)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("tools/get_article/world_war_ii.json"),
+    )?)?;
+    let text = response["content"][0]["text"].as_str().unwrap();
+    let yaml = text
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---\n"))
+        .map(|(yaml, _)| yaml)
+        .expect("front matter block");
+    let frontmatter: serde_yaml::Value = serde_yaml::from_str(yaml)?;
+
+    assert_eq!(frontmatter["title"].as_str(), Some("World War II"));
+    assert_eq!(frontmatter["id"].as_u64(), Some(32927));
+    assert_eq!(
+        frontmatter["url"].as_str(),
+        Some("https://en.wikipedia.org/wiki/World_War_II")
+    );
+    assert_eq!(frontmatter["categories"][0].as_str(), Some("war"));
+    assert_eq!(frontmatter["aliases"][0].as_str(), Some("WWII"));
+    assert!(text.contains("\n---\n\n# World War II\n\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_pagination_offsets() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("output");
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    for i in 0..120 {
+        let title = format!("Article {i}");
+        parser.articles.insert(
+            title.clone(),
+            Article {
+                id: i,
+                title,
+                content: "Body".to_string(),
+                ..Default::default()
+            },
+        );
+    }
+    let config = Config::new(temp_dir.path().join("unused.xml"), output_dir.clone());
+    generate_from_parser(config, parser, NoCategorizer)?;
+
+    let read_page = |page: usize| -> serde_json::Value {
+        let response: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(output_dir.join(format!("tools/list_articles/{page}.json")))
+                .unwrap(),
+        )
+        .unwrap();
+        serde_json::from_str(response["content"][0]["text"].as_str().unwrap()).unwrap()
+    };
+
+    let second = read_page(2);
+    assert_eq!(second["pagination"]["start_index"], 50);
+    assert_eq!(second["pagination"]["end_index"], 99);
+    let last = read_page(3);
+    assert_eq!(last["pagination"]["start_index"], 100);
+    assert_eq!(last["pagination"]["end_index"], 119);
+
+    Ok(())
+}
+
+#[test]
+fn test_only_titles() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file, output_dir)
+        .topic_filter(TopicFilter::Science)
+        .only_titles(Some(std::collections::HashSet::from([
+            "Roman_Empire".to_string()
+        ])));
+    let corpus = parse_only(config, NoCategorizer)?;
+
+    let titles: Vec<&String> = corpus.articles.keys().collect();
+    assert_eq!(titles, ["Roman Empire"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_get_article_by_id() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    fs::write(&input_file, create_test_xml())?;
+    generate(Config::new(input_file, output_dir.clone()), TestCategorizer)?;
+
+    let by_id = fs::read_to_string(output_dir.join("tools/get_article_by_id/25458.json"))?;
+    let by_title = fs::read_to_string(output_dir.join("tools/get_article/roman_empire.json"))?;
+    assert_eq!(by_id, by_title);
+    assert!(by_id.contains("# Roman Empire"));
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(output_dir.join("mcp.json"))?)?;
+    let tool = manifest["capabilities"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|tool| tool["name"] == "get_article_by_id")
+        .expect("get_article_by_id advertised");
+    assert_eq!(tool["inputSchema"]["properties"]["id"]["type"], "integer");
+
+    Ok(())
+}
+
+#[test]
+fn test_plain_text_content_style() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let xml = create_test_xml().replace(
+        "ruled by emperors.</text>",
+        "ruled by emperors.\n&lt;blockquote&gt;Senatus Populusque Romanus&lt;/blockquote&gt;</text>",
+    );
+    fs::write(&input_file, xml)?;
+
+    let config =
+        Config::new(input_file.clone(), output_dir.clone()).content_style(ContentStyle::PlainText);
+    generate(config, TestCategorizer)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("tools/get_article/roman_empire.json"),
+    )?)?;
+    let text = response["content"][0]["text"].as_str().unwrap();
+    assert!(text.starts_with("The Roman Empire was"));
+    assert!(!text.lines().any(|line| line.starts_with('#')));
+    assert!(!text.contains("> "));
+    assert!(text.ends_with("\nSenatus Populusque Romanus"));
+    assert_eq!(response["content"][0]["mimeType"], "text/plain");
+
+    // An explicit MIME type is kept, even when it's the Markdown default
+    let explicit_dir = temp_dir.path().join("explicit");
+    let config = Config::new(input_file, explicit_dir.clone())
+        .content_mime("text/markdown")
+        .content_style(ContentStyle::PlainText);
+    generate(config, TestCategorizer)?;
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        explicit_dir.join("tools/get_article/roman_empire.json"),
+    )?)?;
+    assert_eq!(response["content"][0]["mimeType"], "text/markdown");
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn test_spilled_parse_matches_in_memory() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let spill_dir = temp_dir.path().join("spill");
+    fs::write(&input_file, create_test_xml())?;
+
+    let generated_at: chrono::DateTime<chrono::Utc> =
+        chrono::DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")?.into();
+    let run = |output_dir: PathBuf, spill: bool| -> Result<(), Box<dyn std::error::Error>> {
+        let mut config = Config::new(input_file.clone(), output_dir)
+            .exact_matches(true)
+            .report_dangling_links(true)
+            .article_metadata(true)
+            .generated_at(generated_at);
+        if spill {
+            config = config.spill_dir(&spill_dir);
+        }
+        generate(config, TestCategorizer)?;
+        Ok(())
+    };
+    let memory_dir = temp_dir.path().join("memory");
+    let spilled_dir = temp_dir.path().join("spilled");
+    run(memory_dir.clone(), false)?;
+    run(spilled_dir.clone(), true)?;
+
+    let files = |root: &PathBuf| -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        let mut pending = vec![root.clone()];
+        while let Some(dir) = pending.pop() {
+            for entry in fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else {
+                    files.push(path.strip_prefix(root).unwrap().to_path_buf());
+                }
+            }
+        }
+        files.sort();
+        files
+    };
+    assert_eq!(files(&memory_dir), files(&spilled_dir));
+
+    for file in [
+        "tools/get_article/world_war_ii.json",
+        "tools/get_article/roman_empire.json",
+        "tools/get_article/computer_science.json",
+        "tools/categories/war.json",
+        "resources/stats.json",
+        "resources/article_metadata.json",
+        "mcp.json",
+    ] {
+        assert_eq!(
+            fs::read_to_string(memory_dir.join(file))?,
+            fs::read_to_string(spilled_dir.join(file))?,
+            "{file} differs"
+        );
+    }
+    // The log is removed once generation is done
+    assert_eq!(fs::read_dir(&spill_dir)?.count(), 0);
+
+    // `parse_only` applies the categorizer's veto to spilled articles too
+    struct NoRome;
+    impl ArticleCategorizer for NoRome {
+        fn categorize(&self, _title: &str, _content: &str) -> Vec<String> {
+            Vec::new()
+        }
+        fn should_include(&self, title: &str, _content: &str) -> bool {
+            !title.contains("Roman")
+        }
+    }
+    let titles = |spill: bool| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut config = Config::new(input_file.clone(), temp_dir.path().join("unused"));
+        if spill {
+            config = config.spill_dir(&spill_dir);
+        }
+        let mut titles: Vec<String> = parse_only(config, NoRome)?.articles.into_keys().collect();
+        titles.sort();
+        Ok(titles)
+    };
+    assert_eq!(titles(true)?, titles(false)?);
+    assert_eq!(titles(true)?, ["Computer Science", "World War II"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_section_redirect() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::RedirectTarget;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let xml = create_test_xml().replacen(
+        "</mediawiki>",
+        "  <page>\n    <title>Fall of Rome</title>\n    <id>2</id>\n    <redirect title=\"Roman Empire#Decline\" />\n    <revision>\n      <text>#REDIRECT [[Roman Empire#Decline]]</text>\n    </revision>\n  </page>\n</mediawiki>",
+        1,
+    );
+    fs::write(&input_file, xml)?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+    assert_eq!(
+        parser.redirects.get("Fall of Rome"),
+        Some(&RedirectTarget {
+            title: "Roman Empire".to_string(),
+            section: Some("Decline".to_string()),
+        })
+    );
+
+    // Streamed redirects are written as pointers naming both parts
+    let mut generator =
+        StaticMcpGenerator::new_streaming(output_dir.clone(), "en".to_string(), NoCategorizer);
+    fs::create_dir_all(output_dir.join("tools/get_article"))?;
+    let article = Article {
+        id: 2,
+        title: "Fall of Rome".to_string(),
+        redirect: Some("Roman Empire".to_string()),
+        redirect_section: Some("Decline".to_string()),
+        ..Default::default()
+    };
+    generator.write_article_with_collision_handling("Fall of Rome", &article)?;
+    let pointer = fs::read_to_string(output_dir.join("tools/get_article/fall_of_rome.json"))?;
+    assert!(pointer.contains("title 'Roman Empire'"));
+    assert!(pointer.contains("'Decline' section"));
+
+    Ok(())
+}
+
+#[test]
+fn test_emit_recent() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let xml = create_test_xml()
+        .replace(
+            "<id>32927</id>\n    <revision>",
+            "<id>32927</id>\n    <revision>\n      <timestamp>2023-05-01T10:00:00Z</timestamp>",
+        )
+        .replace(
+            "<id>25458</id>\n    <revision>",
+            "<id>25458</id>\n    <revision>\n      <timestamp>2024-02-01T10:00:00Z</timestamp>",
+        );
+    fs::write(&input_file, xml)?;
+
+    let config = Config::new(input_file, output_dir.clone()).emit_recent(Some(5));
+    generate(config, TestCategorizer)?;
+
+    let response: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(output_dir.join("tools/recent.json"))?)?;
+    let recent: serde_json::Value =
+        serde_json::from_str(response["content"][0]["text"].as_str().unwrap())?;
+    let titles: Vec<&str> = recent["articles"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, ["Roman Empire", "World War II"]);
+    assert_eq!(recent["articles"][0]["timestamp"], "2024-02-01T10:00:00Z");
+
+    Ok(())
+}
+
+#[test]
+#[cfg(not(feature = "bzip2"))]
+fn test_xml_only_build() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::parser::UnsupportedFormat;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    fs::write(&input_file, create_test_xml())?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+    assert_eq!(parser.articles.len(), 3);
+
+    let bz2_file = temp_dir.path().join("test.xml.bz2");
+    fs::write(&bz2_file, b"BZh9")?;
+    let error = WikipediaParser::new("en".to_string())
+        .parse(&bz2_file, None, &None)
+        .unwrap_err();
+    assert!(error.downcast_ref::<UnsupportedFormat>().is_some());
+
+    Ok(())
+}
+
+#[test]
+fn test_single_pass_cleaner_matches_regex() {
+    use wikipedia_core::parser::{CleanOptions, clean_wikitext, clean_wikitext_with_options};
+
+    let sample = r#"{{Short description|Ancient state}}{{Infobox country|name=Roman Empire}}
+'''Roman Empire''' ({{lang-la|Imperium Romanum}}) was the post-[[Roman Republic|Republican]] period of [[ancient Rome]].<ref name="a">{{cite book|title=Rome}}</ref>
+It was ruled by ''emperors'' and '''''Augustus''''' was the first.<ref name="b" />
+
+== History ==
+=== Early period ===
+The empire grew from [[Italy]].<br/>Trade with [[Han dynasty|China]] was common.
+<blockquote>
+Veni, vidi, [[vici]].
+</blockquote>
+:An indented ''aside''.
+::Nested <nowiki>[[not a link]]</nowiki> aside.
+
+[[File:Roman Empire map.png|thumb|The empire at its height]]
+[[Category:Ancient Rome]]
+[[Category:Former empires]]
+[[de:Römisches Reich]]
+Unicode: Ῥωμαίων – «Imperium» café."#;
+
+    let regex = CleanOptions {
+        use_regex: true,
+        ..CleanOptions::default()
+    };
+    assert_eq!(
+        clean_wikitext(sample),
+        clean_wikitext_with_options(sample, &regex)
+    );
+
+    let keep_links = CleanOptions {
+        unwrap_links: false,
+        strip_emphasis: false,
+        ..CleanOptions::default()
+    };
+    assert_eq!(
+        clean_wikitext_with_options(sample, &keep_links),
+        clean_wikitext_with_options(
+            sample,
+            &CleanOptions {
+                use_regex: true,
+                ..keep_links.clone()
+            }
+        )
+    );
+}
+
+#[test]
+fn test_categorizer_vetoes_articles() -> Result<(), Box<dyn std::error::Error>> {
+    struct CategorizedOnly;
+
+    impl ArticleCategorizer for CategorizedOnly {
+        fn categorize(&self, title: &str, content: &str) -> Vec<String> {
+            TestCategorizer.categorize(title, content)
+        }
+
+        fn should_include(&self, title: &str, content: &str) -> bool {
+            !self.categorize(title, content).is_empty()
+        }
+    }
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    fs::write(&input_file, create_test_xml())?;
+
+    for during_parse in [false, true] {
+        let output_dir = temp_dir.path().join(format!("output_{during_parse}"));
+        let config = Config::new(input_file.clone(), output_dir.clone())
+            .categorize_during_parse(during_parse);
+        let stats = generate(config, CategorizedOnly)?;
+        assert_eq!(stats.articles_written, 1);
+
+        let articles_dir = output_dir.join("tools/get_article");
+        assert!(articles_dir.join("world_war_ii.json").exists());
+        assert!(!articles_dir.join("roman_empire.json").exists());
+        assert!(!articles_dir.join("computer_science.json").exists());
+
+        let page = fs::read_to_string(output_dir.join("tools/list_articles/1.json"))?;
+        assert!(page.contains("World War II"));
+        assert!(!page.contains("Roman Empire"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_disambiguation_index() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let pages: String = ["Empire Article", "Empire/Article", "Empire_Article"]
+        .iter()
+        .enumerate()
+        .map(|(i, title)| {
+            format!(
+                "  <page>\n    <title>{title}</title>\n    <id>{}</id>\n    <revision>\n      <text>An empire article. {}</text>\n    </revision>\n  </page>\n",
+                i + 1,
+                "x".repeat(1400 + i)
+            )
+        })
+        .collect();
+    fs::write(&input_file, format!("<mediawiki>\n{pages}</mediawiki>"))?;
+
+    let config = Config::new(input_file, output_dir.clone());
+    generate(config, NoCategorizer)?;
+
+    let resource: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/disambiguation.json"),
+    )?)?;
+    let groups: serde_json::Value = serde_json::from_str(resource["text"].as_str().unwrap())?;
+    let groups = groups.as_array().unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0]["base_filename"], "empire_article");
+
+    let mut members: Vec<(String, String)> = groups[0]["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|member| {
+            (
+                member["title"].as_str().unwrap().to_string(),
+                member["filename"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    members.sort();
+    let titles: Vec<&str> = members.iter().map(|(title, _)| title.as_str()).collect();
+    assert_eq!(
+        titles,
+        ["Empire Article", "Empire/Article", "Empire_Article"]
+    );
+    let mut filenames: Vec<&str> = members.iter().map(|(_, file)| file.as_str()).collect();
+    filenames.sort();
+    assert_eq!(
+        filenames,
+        [
+            "empire_article__disambig_1",
+            "empire_article__disambig_2",
+            "empire_article__disambig_3"
+        ]
+    );
+    for filename in filenames {
+        assert!(
+            output_dir
+                .join(format!("tools/get_article/{filename}.json"))
+                .exists()
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_regenerate_category() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::StaticMcpGeneratorBuilder;
+
+    struct TopicCategorizer;
+
+    impl ArticleCategorizer for TopicCategorizer {
+        fn categorize(&self, title: &str, _content: &str) -> Vec<String> {
+            ["war", "empire"]
+                .into_iter()
+                .filter(|topic| title.to_lowercase().contains(topic))
+                .map(str::to_string)
+                .collect()
+        }
+    }
+
+    fn snapshot(dir: &std::path::Path, files: &mut std::collections::BTreeMap<PathBuf, Vec<u8>>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                snapshot(&path, files);
+            } else {
+                files.insert(path.clone(), fs::read(&path).unwrap());
+            }
+        }
+    }
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file, output_dir.clone());
+    generate(config.clone(), TopicCategorizer)?;
+
+    let mut before = std::collections::BTreeMap::new();
+    snapshot(&output_dir, &mut before);
+
+    let mut generator = StaticMcpGeneratorBuilder::new(config)
+        .build(WikipediaParser::new("en".to_string()), TopicCategorizer);
+    generator.regenerate_category(
+        "empire",
+        vec!["Roman Empire".to_string(), "World War II".to_string()],
+    )?;
+
+    let mut after = std::collections::BTreeMap::new();
+    snapshot(&output_dir, &mut after);
+    assert_eq!(
+        before.keys().collect::<Vec<_>>(),
+        after.keys().collect::<Vec<_>>()
+    );
+    let changed: Vec<&PathBuf> = after
+        .iter()
+        .filter(|(path, contents)| before[*path] != **contents)
+        .map(|(path, _)| path)
+        .collect();
+    let empire_path = output_dir.join("tools/categories/empire.json");
+    let list_path = output_dir.join("tools/list_categories.json");
+    assert!(changed.contains(&&empire_path));
+    assert!(
+        changed
+            .iter()
+            .all(|path| **path == empire_path || **path == list_path)
+    );
+
+    let empire: serde_json::Value = serde_json::from_str(
+        serde_json::from_str::<serde_json::Value>(&fs::read_to_string(&empire_path)?)?["content"]
+            [0]["text"]
+            .as_str()
+            .unwrap(),
+    )?;
+    assert_eq!(empire["count"], 2);
+    assert_eq!(empire["articles"][1], "World War II");
+
+    let list = fs::read_to_string(&list_path)?;
+    assert!(list.contains("empire") && list.contains("war"));
+
+    Ok(())
+}
+
+#[test]
+fn test_search_results_paginated() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("output");
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    for id in 1..=120 {
+        let title = format!("Battle {id}");
+        parser.articles.insert(
+            title.clone(),
+            Article {
+                title,
+                content: "A battle.".to_string(),
+                id,
+                ..Default::default()
+            },
+        );
+    }
+
+    let mut generator =
+        StaticMcpGenerator::new(output_dir.clone(), "en".to_string(), parser, NoCategorizer)
+            .search_index(true);
+    generator.generate(false, None)?;
+
+    let read_page = |path: PathBuf| -> serde_json::Value {
+        let response: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        serde_json::from_str(response["content"][0]["text"].as_str().unwrap()).unwrap()
+    };
+
+    let search_dir = output_dir.join("tools/search");
+    let first = read_page(search_dir.join("battle.json"));
+    assert_eq!(first["count"], 120);
+    assert_eq!(first["pagination"]["total_pages"], 3);
+    assert_eq!(first["results"].as_array().unwrap().len(), 50);
+    assert_eq!(first, read_page(search_dir.join("battle/1.json")));
+
+    let last = read_page(search_dir.join("battle/3.json"));
+    assert_eq!(last["pagination"]["current_page"], 3);
+    assert_eq!(last["results"].as_array().unwrap().len(), 20);
+    assert!(!search_dir.join("battle/4.json").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_titles_normalized_to_nfc() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+
+    let xml = "<mediawiki>
+  <page>
+    <title>Cafe\u{301} culture</title>
+    <id>1</id>
+    <revision>
+      <text>The cafe\u{301} in history.</text>
+    </revision>
+  </page>
+  <page>
+    <title>Caf\u{e9} culture</title>
+    <id>1</id>
+    <revision>
+      <text>The caf\u{e9} in history.</text>
+    </revision>
+  </page>
+</mediawiki>";
+    fs::write(&input_file, xml)?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+
+    assert_eq!(parser.articles.len(), 1);
+    let article = &parser.articles["Caf\u{e9} culture"];
+    assert_eq!(article.title, "Caf\u{e9} culture");
+    assert!(article.content.contains("caf\u{e9}"));
+
+    Ok(())
+}
+
+#[test]
+fn test_emit_hosting_meta() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    fs::write(&input_file, create_test_xml())?;
+
+    let plain_dir = temp_dir.path().join("plain");
+    generate(
+        Config::new(input_file.clone(), plain_dir.clone()),
+        NoCategorizer,
+    )?;
+    assert!(!plain_dir.join("robots.txt").exists());
+    assert!(!plain_dir.join("_headers").exists());
+
+    let output_dir = temp_dir.path().join("output");
+    let config = Config::new(input_file, output_dir.clone())
+        .content_addressed(true)
+        .emit_hosting_meta(true);
+    generate(config, NoCategorizer)?;
+
+    let robots = fs::read_to_string(output_dir.join("robots.txt"))?;
+    assert!(robots.contains("Allow: /mcp.json"));
+    let headers = fs::read_to_string(output_dir.join("_headers"))?;
+    assert!(
+        headers
+            .contains("/tools/get_article/*\n  Cache-Control: public, max-age=31536000, immutable")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_layout_paths() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::layout;
+
+    assert_eq!(
+        layout::article_path("Roman Empire"),
+        "tools/get_article/roman_empire.json"
+    );
+    assert_eq!(
+        layout::list_articles_page_path(2),
+        "tools/list_articles/2.json"
+    );
+    assert_eq!(layout::category_path("war"), "tools/categories/war.json");
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+    generate(Config::new(input_file, output_dir.clone()), TestCategorizer)?;
+
+    assert!(
+        output_dir
+            .join(layout::article_path("World War II"))
+            .exists()
+    );
+    assert!(output_dir.join(layout::list_articles_page_path(1)).exists());
+    assert!(output_dir.join(layout::category_path("war")).exists());
+    assert!(output_dir.join(layout::LIST_CATEGORIES_PATH).exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_self_closing_redirect_and_text() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+
+    let xml = r#"<mediawiki>
+  <page>
+    <title>Rome (empire)</title>
+    <id>1</id>
+    <redirect title="Roman Empire" />
+    <revision>
+      <text bytes="0" />
+    </revision>
+  </page>
+  <page>
+    <title>Blanked page</title>
+    <id>2</id>
+    <revision>
+      <text bytes="0" />
+    </revision>
+  </page>
+</mediawiki>"#;
+    fs::write(&input_file, xml)?;
+
+    let mut parser = WikipediaParser::new("en".to_string()).keep_empty_articles(true);
+    parser.parse(&input_file, None, &None)?;
+
+    assert_eq!(parser.redirects["Rome (empire)"].title, "Roman Empire");
+    assert_eq!(parser.articles["Blanked page"].content, "");
+
+    Ok(())
+}
+
+#[test]
+fn test_raw_wikitext() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let xml = r#"<mediawiki>
+  <page>
+    <title>Roman Empire</title>
+    <id>1</id>
+    <revision>
+      <text>The '''Roman Empire''' ruled [[Italy|the peninsula]] and more.{{Citation needed}}</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+    fs::write(&input_file, xml)?;
+
+    let config = Config::new(input_file, output_dir.clone()).raw_wikitext(true);
+    generate(config, NoCategorizer)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("tools/get_article/roman_empire.json"),
+    )?)?;
+    let text = response["content"][0]["text"].as_str().unwrap();
+    assert!(text.contains(
+        "The '''Roman Empire''' ruled [[Italy|the peninsula]] and more.{{Citation needed}}"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_emit_checksums() -> Result<(), Box<dyn std::error::Error>> {
+    use sha2::{Digest, Sha256};
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file, output_dir.clone()).emit_checksums(true);
+    generate(config, TestCategorizer)?;
+
+    let checksums: std::collections::BTreeMap<String, String> =
+        serde_json::from_str(&fs::read_to_string(output_dir.join("checksums.json"))?)?;
+    let article = "tools/get_article/roman_empire.json";
+    let expected: String = Sha256::digest(fs::read(output_dir.join(article))?)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    assert_eq!(checksums[article], expected);
+    assert!(checksums.contains_key("mcp.json"));
+    assert!(checksums.contains_key("tools/list_articles/1.json"));
+    assert!(checksums.contains_key("tools/categories/war.json"));
+    assert!(!checksums.contains_key("checksums.json"));
+
+    Ok(())
+}
+
+#[test]
+fn test_empty_articles_dropped() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+
+    let xml = r#"<mediawiki>
+  <page>
+    <title>Roman Empire</title>
+    <id>1</id>
+    <revision>
+      <text>The Roman Empire ruled the Mediterranean.</text>
+    </revision>
+  </page>
+  <page>
+    <title>Template soup</title>
+    <id>2</id>
+    <revision>
+      <text>{{Infobox|name=Soup}}
+{{Navbox|list=Soup}}
+[[Category:Soups]]</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+    fs::write(&input_file, xml)?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+    assert!(parser.articles.contains_key("Roman Empire"));
+    assert!(!parser.articles.contains_key("Template soup"));
+
+    let mut parser = WikipediaParser::new("en".to_string()).keep_empty_articles(true);
+    parser.parse(&input_file, None, &None)?;
+    assert!(parser.articles.contains_key("Template soup"));
+
+    Ok(())
+}
+
+#[test]
+fn test_in_memory_output_sink() -> Result<(), Box<dyn std::error::Error>> {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::rc::Rc;
+    use wikipedia_core::{OutputSink, StaticMcpGeneratorBuilder};
+
+    #[derive(Clone, Default)]
+    struct MemorySink(Rc<RefCell<HashMap<String, Vec<u8>>>>);
+
+    impl OutputSink for MemorySink {
+        fn write(&mut self, path: &str, bytes: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+            self.0.borrow_mut().insert(path.to_string(), bytes.to_vec());
+            Ok(())
+        }
+
+        fn read(&self, path: &str) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error>> {
+            Ok(self.0.borrow().get(path).cloned())
+        }
+
+        fn remove(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+            let prefix = format!("{path}/");
+            self.0
+                .borrow_mut()
+                .retain(|file, _| file != path && !file.starts_with(&prefix));
+            Ok(())
+        }
+
+        fn paths(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+            Ok(self.0.borrow().keys().cloned().collect())
+        }
+    }
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+
+    let sink = MemorySink::default();
+    let config = Config::new(input_file, output_dir.clone());
+    let mut generator = StaticMcpGeneratorBuilder::new(config)
+        .sink(sink.clone())
+        .build(parser, TestCategorizer);
+    generator.generate(false, None)?;
+
+    let files = sink.0.borrow();
+    let manifest: serde_json::Value = serde_json::from_slice(&files["mcp.json"])?;
+    assert!(manifest["capabilities"]["tools"].is_array());
+    let article = String::from_utf8(files["tools/get_article/roman_empire.json"].clone())?;
+    assert!(article.contains("Roman Empire"));
+    assert!(files.contains_key("tools/categories/war.json"));
+    assert!(!output_dir.exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_namespaces_sharing_a_filename_stay_apart() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>Template:Foo</title>
+    <ns>10</ns>
+    <id>100</id>
+    <revision>
+      <text>A template called Foo.</text>
+    </revision>
+  </page>
+  <page>
+    <title>Template Foo</title>
+    <ns>0</ns>
+    <id>101</id>
+    <revision>
+      <text>An article about template foo.</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+    fs::write(&input_file, test_xml)?;
+
+    let config = Config::new(input_file, output_dir.clone()).include_namespaces(vec![0, 10]);
+    generate(config, NoCategorizer)?;
+
+    let article_dir = output_dir.join("tools/get_article");
+    let article: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(article_dir.join("template_foo.json"))?)?;
+    let article_text = article["content"][0]["text"].as_str().unwrap();
+    assert!(article_text.contains("An article about template foo."));
+    assert!(!article_text.contains("A template called Foo."));
+
+    let template: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        article_dir.join("template_foo__ns10.json"),
+    )?)?;
+    let template_text = template["content"][0]["text"].as_str().unwrap();
+    assert!(template_text.contains("A template called Foo."));
+    assert!(!template_text.contains("An article about template foo."));
+
+    let collisions: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/collisions.json"),
+    )?)?;
+    let records: serde_json::Value = serde_json::from_str(collisions["text"].as_str().unwrap())?;
+    assert_eq!(records[0]["resolution"], "namespace_suffixed");
+
+    Ok(())
+}
+
+#[test]
+fn test_corrupt_existing_article_is_overwritten() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    let article_path = output_dir.join("tools/get_article/roman_empire.json");
+    fs::create_dir_all(article_path.parent().unwrap())?;
+    fs::write(&article_path, r#"{"content": [{"type": "text", "te"#)?;
+
+    let config = Config::new(input_file, output_dir.clone());
+    generate(config, NoCategorizer)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(&article_path)?)?;
+    let text = response["content"][0]["text"].as_str().unwrap();
+    assert!(text.starts_with("# Roman Empire"));
+    assert!(!text.contains("Multiple articles found"));
+
+    Ok(())
+}
+
+#[test]
+fn test_parser_accessors() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>Roman Empire</title>
+    <ns>0</ns>
+    <id>25458</id>
+    <revision>
+      <text>The Roman Empire was ruled by emperors.</text>
+    </revision>
+  </page>
+  <page>
+    <title>Computer Science</title>
+    <ns>0</ns>
+    <id>5323</id>
+    <revision>
+      <text>Computer science is the study of computation.</text>
+    </revision>
+  </page>
+  <page>
+    <title>Roman empire</title>
+    <ns>0</ns>
+    <id>25459</id>
+    <redirect title="Roman Empire" />
+    <revision>
+      <text>#REDIRECT [[Roman Empire]]</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+    fs::write(&input_file, test_xml)?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+
+    assert_eq!(parser.article_count(), 2);
+    assert_eq!(parser.redirect_count(), 1);
+    assert!(parser.contains_article("Roman Empire"));
+    assert!(!parser.contains_article("Roman empire"));
+    let article = parser.get_article("Computer Science")?.unwrap();
+    assert!(article.content.contains("study of computation"));
+    assert!(parser.get_article("Ancient Greece")?.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_zero_padded_pages() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("output");
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    for i in 0..560 {
+        let title = format!("Article {i}");
+        parser.articles.insert(
+            title.clone(),
+            Article {
+                id: i + 1,
+                title,
+                content: "Body".to_string(),
+                ..Default::default()
+            },
+        );
+    }
+    let config =
+        Config::new(temp_dir.path().join("unused.xml"), output_dir.clone()).zero_pad_pages(true);
+    generate_from_parser(config, parser, NoCategorizer)?;
+
+    let mut pages: Vec<String> = fs::read_dir(output_dir.join("tools/list_articles"))?
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    pages.sort();
+    let expected: Vec<String> = (1..=12).map(|page| format!("{page:02}.json")).collect();
+    assert_eq!(pages, expected);
+
+    let metadata: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("tools/list_articles.json"),
+    )?)?;
+    let text: serde_json::Value =
+        serde_json::from_str(metadata["content"][0]["text"].as_str().unwrap())?;
+    assert!(text["message"].as_str().unwrap().ends_with("(01-12)"));
+
+    Ok(())
+}
+
+#[test]
+fn test_category_descriptions() -> Result<(), Box<dyn std::error::Error>> {
+    struct DescribingCategorizer;
+
+    impl ArticleCategorizer for DescribingCategorizer {
+        fn categorize(&self, title: &str, content: &str) -> Vec<String> {
+            let mut categories = TestCategorizer.categorize(title, content);
+            if title.contains("Roman") {
+                categories.push("rome".to_string());
+            }
+            categories
+        }
+
+        fn describe(&self, category: &str) -> Option<String> {
+            (category == "war").then(|| "Armed conflicts and their history".to_string())
+        }
+    }
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file, output_dir.clone());
+    generate(config, DescribingCategorizer)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("tools/list_categories.json"),
+    )?)?;
+    let list: serde_json::Value =
+        serde_json::from_str(response["content"][0]["text"].as_str().unwrap())?;
+    let mut names: Vec<&str> = list["categories"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|name| name.as_str().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["rome", "war"]);
+    assert_eq!(
+        list["descriptions"],
+        serde_json::json!({"war": "Armed conflicts and their history"})
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "tar")]
+#[test]
+fn test_parse_tar_of_xml_shards() -> Result<(), Box<dyn std::error::Error>> {
+    let shard = |title: &str, id: u64| {
+        format!(
+            r#"<mediawiki>
+  <page>
+    <title>{title}</title>
+    <ns>0</ns>
+    <id>{id}</id>
+    <revision>
+      <text>{title} is an article in shard {id}.</text>
+    </revision>
+  </page>
+</mediawiki>"#
+        )
+    };
+
+    let mut builder = tar::Builder::new(Vec::new());
+    for (name, contents) in [
+        ("shards/part-1.xml", shard("Roman Empire", 1)),
+        ("shards/README.txt", "not a dump".to_string()),
+        ("shards/part-2.xml", shard("Computer Science", 2)),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, contents.as_bytes())?;
+    }
+    let archive = builder.into_inner()?;
+
+    let temp_dir = TempDir::new()?;
+    let tar_path = temp_dir.path().join("dump.tar");
+    fs::write(&tar_path, &archive)?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&tar_path, None, &None)?;
+    assert_eq!(parser.article_count(), 2);
+    assert!(parser.contains_article("Roman Empire"));
+    assert!(parser.contains_article("Computer Science"));
+
+    let gz_path = temp_dir.path().join("dump.tar.gz");
+    let mut encoder =
+        flate2::write::GzEncoder::new(fs::File::create(&gz_path)?, flate2::Compression::fast());
+    std::io::Write::write_all(&mut encoder, &archive)?;
+    encoder.finish()?;
+
+    let output_dir = temp_dir.path().join("output");
+    let stats = generate(Config::new(gz_path, output_dir.clone()), NoCategorizer)?;
+    assert_eq!(stats.articles_written, 2);
+    assert!(
+        output_dir
+            .join("tools/get_article/roman_empire.json")
+            .exists()
+    );
+    assert!(
+        output_dir
+            .join("tools/get_article/computer_science.json")
+            .exists()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_sample_every() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let pages: String = ["Alpha", "Beta", "Gamma", "Delta"]
+        .iter()
+        .enumerate()
+        .map(|(i, title)| {
+            format!(
+                "  <page>\n    <title>{title}</title>\n    <ns>0</ns>\n    <id>{}</id>\n    <revision>\n      <text>{title} is a test article.</text>\n    </revision>\n  </page>\n",
+                i + 1
+            )
+        })
+        .collect();
+    fs::write(&input_file, format!("<mediawiki>\n{pages}</mediawiki>"))?;
+
+    let mut parser = WikipediaParser::new("en".to_string()).sample_every(2);
+    parser.parse(&input_file, None, &None)?;
+    let mut titles: Vec<&String> = parser.articles.keys().collect();
+    titles.sort();
+    assert_eq!(titles, ["Alpha", "Gamma"]);
+
+    let mut streamed = Vec::new();
+    let file = fs::File::open(&input_file)?;
+    WikipediaParser::new("en".to_string())
+        .sample_every(2)
+        .parse_streaming(Box::new(file), false, &None, |title, _| {
+            streamed.push(title.to_string());
+            Ok(())
+        })?;
+    assert_eq!(streamed, ["Alpha", "Gamma"]);
+
+    let config = Config::new(input_file, output_dir)
+        .sample_every(2)
+        .max_articles(1);
+    let corpus = parse_only(config, NoCategorizer)?;
+    assert_eq!(corpus.articles.keys().collect::<Vec<_>>(), ["Alpha"]);
+
+    // The stride runs on across the shards of a tar archive
+    #[cfg(feature = "tar")]
+    {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (i, title) in ["Alpha", "Beta", "Gamma", "Delta"].iter().enumerate() {
+            let contents = format!(
+                "<mediawiki>\n  <page>\n    <title>{title}</title>\n    <ns>0</ns>\n    <id>{}</id>\n    <revision>\n      <text>{title} is a test article.</text>\n    </revision>\n  </page>\n</mediawiki>",
+                i + 1
+            );
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, format!("part-{i}.xml"), contents.as_bytes())?;
+        }
+        let tar_path = temp_dir.path().join("dump.tar");
+        fs::write(&tar_path, builder.into_inner()?)?;
+
+        let mut parser = WikipediaParser::new("en".to_string()).sample_every(2);
+        parser.parse(&tar_path, None, &None)?;
+        let mut titles: Vec<&String> = parser.articles.keys().collect();
+        titles.sort();
+        assert_eq!(titles, ["Alpha", "Gamma"]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_emit_see_also() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>Roman Empire</title>
+    <ns>0</ns>
+    <id>1</id>
+    <revision>
+      <text>The [[Byzantine Empire|eastern half]] outlived the west, unlike [[Atlantis]].</text>
+    </revision>
+  </page>
+  <page>
+    <title>Byzantine Empire</title>
+    <ns>0</ns>
+    <id>2</id>
+    <revision>
+      <text>The Byzantine Empire was ruled from Constantinople.</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+    fs::write(&input_file, test_xml)?;
+
+    let config = Config::new(input_file, output_dir.clone()).emit_see_also(true);
+    generate(config, NoCategorizer)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("tools/get_article/roman_empire.json"),
+    )?)?;
+    let text = response["content"][0]["text"].as_str().unwrap();
+    assert!(
+        text.ends_with("## See also\n\n- [Byzantine Empire](../get_article/byzantine_empire.json)")
+    );
+    assert!(!text.contains("atlantis.json"));
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("tools/get_article/byzantine_empire.json"),
+    )?)?;
+    assert!(
+        !response["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("See also")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_long_collision_variants_fit_filename_limit() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::ToolKind;
+    use wikipedia_core::filename_encoding::MAX_FILENAME_LENGTH;
+
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("output");
+
+    // All three encode to the same 200-character base filename
+    let stem = "Battle article ".repeat(13);
+    let titles = [
+        format!("{stem}x a"),
+        format!("{stem}x!a"),
+        format!("{stem}x?a"),
+    ];
+    let mut parser = WikipediaParser::new("en".to_string());
+    for (i, title) in titles.iter().enumerate() {
+        parser.articles.insert(
+            title.clone(),
+            Article {
+                id: i as u64 + 1,
+                title: title.clone(),
+                content: format!("Article {i}. {}", "Long body text. ".repeat(100)),
+                ..Default::default()
+            },
+        );
+    }
+    let config = Config::new(temp_dir.path().join("unused.xml"), output_dir.clone())
+        .enabled_tools(vec![ToolKind::GetArticle]);
+    generate_from_parser(config, parser, NoCategorizer)?;
+
+    let stems: Vec<String> = fs::read_dir(output_dir.join("tools/get_article"))?
+        .map(|entry| {
+            let name = entry.unwrap().file_name().to_string_lossy().into_owned();
+            name.strip_suffix(".json").unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(stems.len(), 4);
+    assert!(stems.iter().all(|stem| stem.len() <= MAX_FILENAME_LENGTH));
+    assert_eq!(
+        stems
+            .iter()
+            .filter(|stem| stem.contains("__disambig_"))
+            .count(),
+        3
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_manifests_share_builtin_tools() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::StaticMcpGeneratorBuilder;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let full_dir = temp_dir.path().join("full");
+    let streaming_dir = temp_dir.path().join("streaming");
+    fs::write(&input_file, create_test_xml())?;
+
+    generate(
+        Config::new(input_file.clone(), full_dir.clone()).search_index(true),
+        TestCategorizer,
+    )?;
+    let streaming = StaticMcpGeneratorBuilder::new(
+        Config::new(input_file, streaming_dir.clone()).search_index(true),
+    )
+    .build_streaming(TestCategorizer);
+    streaming.generate_metadata_only(false)?;
+
+    // Both manifests are serialized from the same `Tool` values, so the
+    // pretty-printed files can be compared byte for byte
+    let tools_json = |dir: &PathBuf| -> Result<String, Box<dyn std::error::Error>> {
+        let manifest = fs::read_to_string(dir.join("mcp.json"))?;
+        let start = manifest.find("\"tools\"").unwrap();
+        Ok(manifest[start..].to_string())
+    };
+    assert_eq!(tools_json(&full_dir)?, tools_json(&streaming_dir)?);
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(full_dir.join("mcp.json"))?)?;
+    assert_eq!(
+        manifest["capabilities"]["tools"],
+        serde_json::to_value(streaming.builtin_tools())?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_langlinks_captured() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>Roman Empire</title>
+    <ns>0</ns>
+    <id>25458</id>
+    <revision>
+      <text>The Roman Empire followed the [[Roman Republic]]. See also [[:fr:Rome]].
+
+[[fr:Empire romain]]
+[[de:Römisches Reich]]
+[[zh-yue:羅馬帝國]]</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+    fs::write(&input_file, test_xml)?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+    let article = &parser.articles["Roman Empire"];
+    assert_eq!(
+        article.langlinks,
+        [
+            ("fr".to_string(), "Empire romain".to_string()),
+            ("de".to_string(), "Römisches Reich".to_string()),
+            ("zh-yue".to_string(), "羅馬帝國".to_string()),
+        ]
+    );
+    assert!(!article.content.contains("Empire romain"));
+    assert!(!article.content.contains("Römisches Reich"));
+    assert!(article.content.contains("Roman Republic"));
+    assert!(article.content.contains("fr:Rome"));
+
+    let config = Config::new(input_file, output_dir.clone()).article_metadata(true);
+    generate(config, NoCategorizer)?;
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/article_metadata.json"),
+    )?)?;
+    let entries: serde_json::Value = serde_json::from_str(response["text"].as_str().unwrap())?;
+    assert_eq!(entries[0]["langlinks"]["fr"], "Empire romain");
+
+    Ok(())
+}
+
+#[test]
+fn test_require_nonempty() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::WikipediaError;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    let nothing_matches = Config::new(input_file.clone(), output_dir.clone())
+        .topic_filter(TopicFilter::Mathematics)
+        .require_nonempty(true);
+    let error = generate(nothing_matches, NoCategorizer).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<WikipediaError>(),
+        Some(WikipediaError::NoArticles)
+    ));
+    assert!(!output_dir.join("mcp.json").exists());
+
+    let permissive =
+        Config::new(input_file, output_dir.clone()).topic_filter(TopicFilter::Mathematics);
+    generate(permissive, NoCategorizer)?;
+    assert!(output_dir.join("mcp.json").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_category_entries_carry_summaries() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>World War II</title>
+    <ns>0</ns>
+    <id>32927</id>
+    <revision>
+      <text>{{Short description|1939–1945 global conflict}}World War II was a global war.</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+    fs::write(&input_file, test_xml)?;
+
+    let config = Config::new(input_file.clone(), output_dir.clone()).article_metadata(true);
+    generate(config, TestCategorizer)?;
+
+    let read_category = |dir: &PathBuf| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let response: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("tools/categories/war.json"))?)?;
+        Ok(serde_json::from_str(
+            response["content"][0]["text"].as_str().unwrap(),
+        )?)
+    };
+    let category = read_category(&output_dir)?;
+    assert_eq!(category["articles"][0], "World War II");
+    assert_eq!(category["entries"][0]["title"], "World War II");
+    assert_eq!(
+        category["entries"][0]["short_description"],
+        "1939–1945 global conflict"
+    );
+    assert_eq!(
+        category["entries"][0]["length"],
+        "World War II was a global war.".len()
+    );
+
+    let plain_dir = temp_dir.path().join("plain");
+    generate(Config::new(input_file, plain_dir.clone()), TestCategorizer)?;
+    assert!(read_category(&plain_dir)?.get("entries").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_legacy_manifest_compat() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::McpCompat;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file, output_dir.clone()).compat(McpCompat::Legacy);
+    generate(config, NoCategorizer)?;
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(output_dir.join("mcp.json"))?)?;
+    assert_eq!(manifest["protocol_version"], "2024-11-05");
+    assert!(manifest["server_info"]["name"].is_string());
+    assert!(manifest.get("protocolVersion").is_none());
+    assert!(manifest.get("serverInfo").is_none());
+    assert!(manifest.get("capabilities").is_none());
+
+    let get_article = manifest["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|tool| tool["name"] == "get_article")
+        .unwrap();
+    assert_eq!(get_article["input_schema"]["required"][0], "title");
+    assert!(get_article.get("inputSchema").is_none());
+    assert_eq!(manifest["resources"][0]["mime_type"], "application/json");
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_metrics_are_reported() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    fs::write(&input_file, create_test_xml())?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+
+    let metrics = parser.metrics;
+    assert_eq!(metrics.articles, 3);
+    assert!(metrics.bytes > 0);
+    assert!(metrics.elapsed > std::time::Duration::ZERO);
+    assert!(metrics.cleaning <= metrics.elapsed);
+    assert!(metrics.articles_per_sec() > 0.0);
+
+    let config = Config::new(input_file, temp_dir.path().join("output"));
+    let stats = generate(config, NoCategorizer)?;
+    assert_eq!(stats.parse.articles, 3);
+    assert!(stats.parse.elapsed > std::time::Duration::ZERO);
+
+    Ok(())
+}
+
+#[test]
+fn test_shard_depth_nests_article_files() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::layout;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file, output_dir.clone()).shard_depth(1);
+    generate(config, NoCategorizer)?;
+
+    let article = output_dir.join("tools/get_article/r/roman_empire.json");
+    assert!(article.exists());
+    assert!(
+        !output_dir
+            .join("tools/get_article/roman_empire.json")
+            .exists()
+    );
+    assert_eq!(
+        output_dir.join(layout::sharded_article_path("Roman Empire", 1)),
+        article
+    );
+    assert_eq!(
+        layout::sharded_article_path("Roman Empire", 2),
+        "tools/get_article/ro/ma/roman_empire.json"
+    );
+
+    let manifest = fs::read_to_string(output_dir.join("mcp.json"))?;
+    assert!(manifest.contains("sharded 1 level(s) deep"));
+
+    Ok(())
+}
+
+#[test]
+fn test_write_failure_reports_path_and_progress() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::WikipediaError;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    // A directory squatting on a file path fails the write, even for root
+    fs::create_dir_all(output_dir.join("tools/categories/war.json"))?;
+
+    let config = Config::new(input_file, output_dir.clone());
+    let error = generate(config, TestCategorizer).unwrap_err();
+    let Some(WikipediaError::Io {
+        path,
+        written_so_far,
+        ..
+    }) = error.downcast_ref::<WikipediaError>()
+    else {
+        panic!("expected WikipediaError::Io, got {error}");
+    };
+    assert_eq!(path, "tools/categories/war.json");
+    assert!(*written_so_far > 0);
+
+    fn count_files(dir: &std::path::Path) -> usize {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                if path.is_dir() { count_files(&path) } else { 1 }
+            })
+            .sum()
+    }
+    assert_eq!(count_files(&output_dir), *written_so_far);
+
+    assert!(output_dir.join("mcp.json").exists());
+    let stats_response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/stats.json"),
+    )?)?;
+    let stats: serde_json::Value = serde_json::from_str(stats_response["text"].as_str().unwrap())?;
+    assert_eq!(stats["partial"], true);
+    assert_eq!(stats["files_written"], *written_so_far);
+
+    Ok(())
+}
+
+#[test]
+fn test_topic_filter_output_unchanged_by_single_scan() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::RelevanceScorer;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>Battle of Hastings</title>
+    <ns>0</ns>
+    <id>1</id>
+    <revision>
+      <text>The battle was fought in 1066.</text>
+    </revision>
+  </page>
+  <page>
+    <title>Pottery</title>
+    <ns>0</ns>
+    <id>2</id>
+    <revision>
+      <text>Ancient pottery survived every war.</text>
+    </revision>
+  </page>
+  <page>
+    <title>Kingdom of Fife</title>
+    <ns>0</ns>
+    <id>3</id>
+    <revision>
+      <text>{{Infobox}}</text>
+    </revision>
+  </page>
+  <page>
+    <title>Empire of Rome</title>
+    <ns>0</ns>
+    <id>4</id>
+    <redirect title="Roman Empire" />
+    <revision>
+      <text>#REDIRECT [[Roman Empire]]</text>
+    </revision>
+  </page>
+  <page>
+    <title>Computer Science</title>
+    <ns>0</ns>
+    <id>5</id>
+    <revision>
+      <text>Computer science is the study of computation.</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+    fs::write(&input_file, test_xml)?;
+
+    let filter = Some(TopicFilter::History);
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &filter)?;
+
+    // Titles decide: content-only mentions and empty articles stay out
+    let titles: Vec<&String> = parser.articles.keys().collect();
+    assert_eq!(titles, vec!["Battle of Hastings"]);
+    assert_eq!(
+        parser.redirects.keys().collect::<Vec<_>>(),
+        vec!["Empire of Rome"]
+    );
+    assert_eq!(parser.count(&input_file, &filter)?, 2);
+
+    let history = TopicFilter::History;
+    assert_eq!(history.score("Battle of Hastings", ""), 1.0);
+    assert_eq!(history.score("Pottery", "Glazed bowls"), 0.0);
+    assert!(history.is_relevant_title("Kingdom of Fife"));
+    assert!(!history.is_relevant_title("Pottery"));
+    assert!(history.is_relevant("Pottery", "Ancient pottery"));
+
+    Ok(())
+}
+
+#[test]
+fn test_article_ids_resource() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file, output_dir.clone());
+    generate(config, NoCategorizer)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/article_ids.json"),
+    )?)?;
+    assert_eq!(response["uri"], "wikipedia://article_ids");
+    let ids: serde_json::Value = serde_json::from_str(response["text"].as_str().unwrap())?;
+    assert_eq!(ids["by_title"]["Roman Empire"], 25458);
+    assert_eq!(ids["by_id"]["25458"], "Roman Empire");
+
+    let manifest = fs::read_to_string(output_dir.join("mcp.json"))?;
+    assert!(manifest.contains("wikipedia://article_ids"));
+
+    Ok(())
+}
+
+#[test]
+fn test_stop_words_are_removed_from_content() -> Result<(), Box<dyn std::error::Error>> {
+    use std::collections::HashSet;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    let stop_words: HashSet<String> = ["the", "OF", "a", "was"]
+        .iter()
+        .map(|word| word.to_string())
+        .collect();
+    let config = Config::new(input_file, output_dir.clone()).stop_words(Some(stop_words));
+    generate(config, NoCategorizer)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("tools/get_article/roman_empire.json"),
+    )?)?;
+    let text = response["content"][0]["text"].as_str().unwrap();
+    let body = text.split_once("\n\n").map_or(text, |(_, body)| body);
+
+    let words: Vec<String> = body
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    for stop_word in ["the", "of", "a", "was"] {
+        assert!(
+            !words.iter().any(|word| word == stop_word),
+            "{stop_word} left in {body}"
+        );
+    }
+    assert!(body.starts_with("Roman Empire post-Republican period ancient Rome."));
+    assert!(body.contains("around Mediterranean Sea"));
+    assert!(body.contains("emperors"));
+
+    Ok(())
+}
+
+#[test]
+fn test_coordinates_from_coord_templates() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::parser::extract_coordinates;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>Paris</title>
+    <ns>0</ns>
+    <id>22989</id>
+    <revision>
+      <text>{{Coord|48|51|N|2|21|E|type:city|display=title}}
+Paris is the capital of France.</text>
+    </revision>
+  </page>
+  <page>
+    <title>Computer Science</title>
+    <ns>0</ns>
+    <id>5323</id>
+    <revision>
+      <text>Computer science is the study of computation.</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+    fs::write(&input_file, test_xml)?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+    let (lat, lon) = parser.articles["Paris"].coordinates.unwrap();
+    assert!((lat - 48.85).abs() < 0.001);
+    assert!((lon - 2.35).abs() < 0.001);
+    assert!(parser.articles["Computer Science"].coordinates.is_none());
+
+    let (lat, lon) = extract_coordinates("{{coord|33.9|S|18.4|W}}").unwrap();
+    assert_eq!((lat, lon), (-33.9, -18.4));
+    assert_eq!(extract_coordinates("{{Coord|1.5|2.5}}"), Some((1.5, 2.5)));
+    assert_eq!(extract_coordinates("{{Coord|missing}}"), None);
+
+    let config = Config::new(input_file, output_dir.clone())
+        .frontmatter(true)
+        .article_metadata(true);
+    generate(config, NoCategorizer)?;
+
+    let geo: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(output_dir.join("resources/geo.json"))?)?;
+    let entries: serde_json::Value = serde_json::from_str(geo["text"].as_str().unwrap())?;
+    assert_eq!(entries.as_array().unwrap().len(), 1);
+    assert_eq!(entries[0]["title"], "Paris");
+    assert!((entries[0]["lat"].as_f64().unwrap() - 48.85).abs() < 0.001);
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("tools/get_article/paris.json"),
+    )?)?;
+    assert!(
+        response["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("coordinates: [48.85")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_prebuilt_category_map_skips_categorizer() -> Result<(), Box<dyn std::error::Error>> {
+    use std::collections::HashMap;
+    use wikipedia_core::StaticMcpGeneratorBuilder;
+
+    struct PanickingCategorizer;
+
+    impl ArticleCategorizer for PanickingCategorizer {
+        fn categorize(&self, title: &str, _content: &str) -> Vec<String> {
+            panic!("categorize called for {title}");
+        }
+    }
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+
+    let categories = HashMap::from([
+        (
+            "conflicts".to_string(),
+            vec!["World War II".to_string(), "Roman Empire".to_string()],
+        ),
+        (
+            "computing".to_string(),
+            vec!["Computer Science".to_string()],
+        ),
+    ]);
+    let config = Config::new(input_file, output_dir.clone()).frontmatter(true);
+    let mut generator = StaticMcpGeneratorBuilder::new(config)
+        .categories(categories)
+        .build(parser, PanickingCategorizer);
+    generator.generate(true, None)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("tools/categories/conflicts.json"),
+    )?)?;
+    let payload: serde_json::Value =
+        serde_json::from_str(response["content"][0]["text"].as_str().unwrap())?;
+    let mut titles: Vec<&str> = payload["articles"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|title| title.as_str().unwrap())
+        .collect();
+    titles.sort();
+    assert_eq!(titles, vec!["Roman Empire", "World War II"]);
+    assert!(output_dir.join("tools/categories/computing.json").exists());
+
+    let article = fs::read_to_string(output_dir.join("tools/get_article/computer_science.json"))?;
+    assert!(article.contains(r#"categories: [\"computing\"]"#));
+
+    Ok(())
+}
+
+#[test]
+fn test_streaming_pagination_matches_full_parse() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::StaticMcpGeneratorBuilder;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let full_dir = temp_dir.path().join("full");
+    let streaming_dir = temp_dir.path().join("streaming");
+    let spill_dir = temp_dir.path().join("spill");
+    fs::write(&input_file, create_test_xml())?;
+
+    generate(
+        Config::new(input_file.clone(), full_dir.clone()),
+        NoCategorizer,
+    )?;
+
+    {
+        let config = Config::new(input_file.clone(), streaming_dir.clone()).spill_dir(&spill_dir);
+        let mut generator = StaticMcpGeneratorBuilder::new(config).build_streaming(NoCategorizer);
+        let parser = WikipediaParser::new("en".to_string());
+        parser.parse_streaming(
+            Box::new(fs::File::open(&input_file)?),
+            false,
+            &None,
+            |title, article| generator.write_article_with_collision_handling(title, article),
+        )?;
+        generator.generate_metadata_only(false)?;
+
+        // The sorted title list lives on disk until the generator is dropped
+        assert!(fs::read_dir(&spill_dir)?.next().is_some());
+    }
+    assert!(fs::read_dir(&spill_dir)?.next().is_none());
+
+    for path in ["tools/list_articles/1.json", "tools/list_articles.json"] {
+        assert_eq!(
+            fs::read_to_string(full_dir.join(path))?,
+            fs::read_to_string(streaming_dir.join(path))?,
+            "{path} differs"
+        );
+    }
+    let page: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        streaming_dir.join("tools/list_articles/1.json"),
+    )?)?;
+    let payload: serde_json::Value =
+        serde_json::from_str(page["content"][0]["text"].as_str().unwrap())?;
+    assert_eq!(
+        payload["articles"],
+        serde_json::json!(["Computer Science", "Roman Empire", "World War II"])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_body_only_redirects_become_redirects() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>Roman Empire</title>
+    <ns>0</ns>
+    <id>25458</id>
+    <revision>
+      <text>The Roman Empire was ruled by emperors.</text>
+    </revision>
+  </page>
+  <page>
+    <title>Rome Empire</title>
+    <ns>0</ns>
+    <id>25459</id>
+    <revision>
+      <text>#REDIRECT [[Roman Empire#History|the empire]]
+{{R from misspelling}}</text>
+    </revision>
+  </page>
+  <page>
+    <title>Roemisches Reich</title>
+    <ns>0</ns>
+    <id>25460</id>
+    <revision>
+      <text>#weiterleitung: [[Roman Empire]]</text>
+    </revision>
+  </page>
+  <page>
+    <title>Hashtag</title>
+    <ns>0</ns>
+    <id>25461</id>
+    <revision>
+      <text>#1 is a [[Number sign]] use.</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+    fs::write(&input_file, test_xml)?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.parse(&input_file, None, &None)?;
+
+    assert!(!parser.articles.contains_key("Rome Empire"));
+    let redirect = &parser.redirects["Rome Empire"];
+    assert_eq!(redirect.title, "Roman Empire");
+    assert_eq!(redirect.section.as_deref(), Some("History"));
+    assert_eq!(parser.redirects["Roemisches Reich"].title, "Roman Empire");
+    assert!(parser.articles.contains_key("Hashtag"));
+    assert!(parser.articles.contains_key("Roman Empire"));
+
+    let mut kept = WikipediaParser::new("en".to_string()).body_redirects(false);
+    kept.parse(&input_file, None, &None)?;
+    assert!(kept.articles.contains_key("Rome Empire"));
+    assert!(kept.redirects.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_outputs_combines_trees() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::merge_outputs;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let first_dir = temp_dir.path().join("first");
+    let second_dir = temp_dir.path().join("second");
+    let merged_dir = temp_dir.path().join("merged");
+    fs::write(&input_file, create_test_xml())?;
+    generate(Config::new(input_file, first_dir.clone()), TestCategorizer)?;
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    for (title, content) in [
+        ("Ancient Greece", "Ancient Greece was a civilization."),
+        ("World war II", "A differently cased title."),
+    ] {
+        parser.articles.insert(
+            title.to_string(),
+            Article {
+                title: title.to_string(),
+                content: content.to_string(),
+                ..Default::default()
+            },
+        );
+    }
+    generate_from_parser(
+        Config::new(PathBuf::new(), second_dir.clone()),
+        parser,
+        TestCategorizer,
+    )?;
+
+    let stats = merge_outputs(&[first_dir, second_dir], merged_dir.clone())?;
+    assert_eq!(stats.collisions, 1);
+
+    let page: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        merged_dir.join("tools/list_articles/1.json"),
+    )?)?;
+    let payload: serde_json::Value =
+        serde_json::from_str(page["content"][0]["text"].as_str().unwrap())?;
+    let titles: Vec<&str> = payload["articles"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|title| title.as_str().unwrap())
+        .collect();
+    for title in [
+        "Ancient Greece",
+        "Computer Science",
+        "Roman Empire",
+        "World War II",
+        "World war II",
+    ] {
+        assert!(titles.contains(&title), "{title} missing from {titles:?}");
+    }
+
+    let greece: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        merged_dir.join("tools/get_article/ancient_greece.json"),
+    )?)?;
+    assert_eq!(
+        greece["content"][0]["text"],
+        "# Ancient Greece\n\nAncient Greece was a civilization."
+    );
+
+    let war: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        merged_dir.join("tools/categories/war.json"),
+    )?)?;
+    let war: serde_json::Value = serde_json::from_str(war["content"][0]["text"].as_str().unwrap())?;
+    assert_eq!(war["articles"].as_array().unwrap().len(), 2);
+
+    let stats_json: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        merged_dir.join("resources/stats.json"),
+    )?)?;
+    let stats_text: serde_json::Value = serde_json::from_str(stats_json["text"].as_str().unwrap())?;
+    assert_eq!(stats_text["total_articles"], 5);
+
+    Ok(())
+}
+
+#[test]
+fn test_output_files_are_lf_only_without_bom() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("output");
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.articles.insert(
+        "Line Endings".to_string(),
+        Article {
+            title: "Line Endings".to_string(),
+            content: "First line\r\nSecond line\rThird line".to_string(),
+            ..Default::default()
+        },
+    );
+    let config = Config::new(PathBuf::new(), output_dir.clone()).frontmatter(true);
+    generate_from_parser(config, parser, NoCategorizer)?;
+
+    let bytes = fs::read(output_dir.join("tools/get_article/line_endings.json"))?;
+    assert!(!bytes.starts_with(b"\xef\xbb\xbf"));
+    assert!(!bytes.contains(&b'\r'));
+
+    let response: serde_json::Value = serde_json::from_slice(&bytes)?;
+    let text = response["content"][0]["text"].as_str().unwrap();
+    assert!(!text.contains('\r'));
+    assert!(text.ends_with("First line\nSecond line\nThird line"));
+
+    Ok(())
+}
+
+#[test]
+fn test_content_transform_rewrites_article_body() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    let config =
+        Config::new(input_file, output_dir.clone()).content_transform(|content, article| {
+            assert_eq!(content, article.content);
+            content.to_uppercase()
+        });
+    generate(config, NoCategorizer)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("tools/get_article/roman_empire.json"),
+    )?)?;
+    let text = response["content"][0]["text"].as_str().unwrap();
+    let (header, body) = text.split_once("\n\n").unwrap();
+    assert_eq!(header, "# Roman Empire");
+    assert!(body.contains("ROMAN EMPIRE"));
+    assert_eq!(body, body.to_uppercase());
+
+    Ok(())
+}
+
+#[test]
+fn test_category_order_by_size() -> Result<(), Box<dyn std::error::Error>> {
+    use std::collections::HashMap;
+    use wikipedia_core::{CategoryOrder, StaticMcpGeneratorBuilder};
+
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("output");
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    let titles: Vec<String> = ["A", "B", "C", "D"].iter().map(|t| t.to_string()).collect();
+    for title in &titles {
+        parser.articles.insert(
+            title.clone(),
+            Article {
+                title: title.clone(),
+                content: format!("Article {title}."),
+                ..Default::default()
+            },
+        );
+    }
+    let categories = HashMap::from([
+        ("alpha".to_string(), titles[..1].to_vec()),
+        ("beta".to_string(), titles[..3].to_vec()),
+        ("gamma".to_string(), titles[..2].to_vec()),
+    ]);
+
+    let list_categories = |dir: &PathBuf| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let response: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("tools/list_categories.json"))?)?;
+        let payload: serde_json::Value =
+            serde_json::from_str(response["content"][0]["text"].as_str().unwrap())?;
+        Ok(serde_json::from_value(payload["categories"].clone())?)
+    };
+
+    let config =
+        Config::new(PathBuf::new(), output_dir.clone()).category_order(CategoryOrder::BySize);
+    let mut generator = StaticMcpGeneratorBuilder::new(config)
+        .categories(categories)
+        .build(parser, NoCategorizer);
+    generator.generate(true, None)?;
+    assert_eq!(list_categories(&output_dir)?, ["beta", "gamma", "alpha"]);
+
+    generator.regenerate_category("alpha", titles.clone())?;
+    assert_eq!(list_categories(&output_dir)?, ["alpha", "beta", "gamma"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_follow_redirects_resolves_chains() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::RedirectTarget;
+
+    let build_parser = || {
+        let mut parser = WikipediaParser::new("en".to_string());
+        parser.articles.insert(
+            "Final".to_string(),
+            Article {
+                title: "Final".to_string(),
+                content: "The final article.".to_string(),
+                ..Default::default()
+            },
+        );
+        for (alias, target) in [
+            ("First Hop", "Second Hop"),
+            ("Second Hop", "Final"),
+            ("Selfish", "Selfish"),
+            ("Ping", "Pong"),
+            ("Pong", "Ping"),
+        ] {
+            parser
+                .redirects
+                .insert(alias.to_string(), RedirectTarget::parse(target));
+        }
+        parser
+    };
+    let article_text = |dir: &PathBuf, filename: &str| -> Option<String> {
+        let json =
+            fs::read_to_string(dir.join(format!("tools/get_article/{filename}.json"))).ok()?;
+        let response: serde_json::Value = serde_json::from_str(&json).ok()?;
+        Some(response["content"][0]["text"].as_str()?.to_string())
+    };
+
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("output");
+    let config = Config::new(PathBuf::new(), output_dir.clone()).follow_redirects(true);
+    generate_from_parser(config, build_parser(), NoCategorizer)?;
+
+    let final_text = article_text(&output_dir, "final").unwrap();
+    assert_eq!(final_text, "# Final\n\nThe final article.");
+    assert_eq!(article_text(&output_dir, "first_hop").unwrap(), final_text);
+    assert_eq!(article_text(&output_dir, "second_hop").unwrap(), final_text);
+    assert!(article_text(&output_dir, "selfish").is_none());
+    assert!(article_text(&output_dir, "ping").is_none());
+    assert!(article_text(&output_dir, "pong").is_none());
+
+    let shallow_dir = temp_dir.path().join("shallow");
+    let config = Config::new(PathBuf::new(), shallow_dir.clone())
+        .follow_redirects(true)
+        .max_redirect_depth(1);
+    generate_from_parser(config, build_parser(), NoCategorizer)?;
+    let first_hop = article_text(&shallow_dir, "first_hop").unwrap();
+    assert!(first_hop.starts_with("# First Hop\n\n"));
+    assert!(first_hop.contains("Use the get_article tool with title 'Second Hop'"));
+    assert_eq!(
+        article_text(&shallow_dir, "second_hop").unwrap(),
+        final_text
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "serve")]
+#[test]
+fn test_preview_server_serves_manifest() -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use wikipedia_core::serve::PreviewServer;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+    generate(Config::new(input_file, output_dir.clone()), NoCategorizer)?;
+
+    let server = PreviewServer::bind(&output_dir, "127.0.0.1:0")?;
+    let addr = server.local_addr()?;
+    std::thread::spawn(move || server.run());
+
+    let fetch = |path: &str| -> std::io::Result<String> {
+        let mut stream = TcpStream::connect(addr)?;
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n")?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        Ok(response)
+    };
+
+    let response = fetch("/mcp.json")?;
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    assert!(head.starts_with("HTTP/1.1 200 OK"));
+    assert!(head.contains("Content-Type: application/json"));
+    assert_eq!(body, fs::read_to_string(output_dir.join("mcp.json"))?);
+
+    let article = fetch("/tools/get_article/roman_empire")?;
+    assert!(article.starts_with("HTTP/1.1 200 OK"));
+    assert!(article.contains("Roman Empire"));
+
+    assert!(fetch("/../etc/passwd")?.starts_with("HTTP/1.1 404"));
+    assert!(fetch("/tools/get_article/missing.json")?.starts_with("HTTP/1.1 404"));
+
+    Ok(())
+}
+
+#[test]
+fn test_record_excluded_lists_skipped_titles() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file, output_dir.clone())
+        .topic_filter(TopicFilter::History)
+        .exclude_titles(["Roman Empire".to_string()].into_iter().collect())
+        .record_excluded(true);
+    generate(config, NoCategorizer)?;
+
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("resources/excluded.json"),
+    )?)?;
+    assert_eq!(response["uri"], "wikipedia://excluded");
+    let excluded: serde_json::Value = serde_json::from_str(response["text"].as_str().unwrap())?;
+    assert_eq!(
+        excluded["reasons"]["content_relevance_miss"],
+        serde_json::json!(["Computer Science"])
+    );
+    assert_eq!(
+        excluded["reasons"]["deny_list"],
+        serde_json::json!(["Roman Empire"])
+    );
+    assert_eq!(
+        excluded["reasons"]["namespace"],
+        serde_json::json!(["File:Example.jpg"])
+    );
+    assert_eq!(excluded["total"], 3);
+    assert_eq!(excluded["truncated"], false);
+
+    let manifest = fs::read_to_string(output_dir.join("mcp.json"))?;
+    assert!(manifest.contains("wikipedia://excluded"));
+
+    Ok(())
+}
+
+#[test]
+fn test_relevance_scorer_filters_articles() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::RelevanceScorer;
+
+    struct PhraseScorer(&'static str);
+
+    impl RelevanceScorer for PhraseScorer {
+        fn score(&self, _title: &str, content: &str) -> f32 {
+            if content.to_lowercase().contains(self.0) {
+                1.0
+            } else {
+                0.0
+            }
+        }
+    }
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file.clone(), temp_dir.path().join("unused"))
+        .relevance_scorer(PhraseScorer("data structures"), 0.5);
+    let corpus = parse_only(config, NoCategorizer)?;
+    let titles: Vec<&String> = corpus.articles.keys().collect();
+    assert_eq!(titles, ["Computer Science"]);
+
+    // The keyword filter is a scorer too
+    assert_eq!(
+        TopicFilter::History.score("Roman Empire", "An empire in antiquity"),
+        1.0
+    );
+    assert_eq!(
+        TopicFilter::History.score("Computer Science", "algorithms"),
+        0.0
+    );
+    let config = Config::new(input_file, temp_dir.path().join("unused"))
+        .relevance_scorer(TopicFilter::History, 0.5);
+    let mut titles: Vec<String> = parse_only(config, NoCategorizer)?
+        .articles
+        .into_keys()
+        .collect();
+    titles.sort();
+    assert_eq!(titles, ["Roman Empire", "World War II"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_chunk_articles_splits_long_articles_at_sections() -> Result<(), Box<dyn std::error::Error>>
+{
+    use wikipedia_core::StaticMcpGeneratorBuilder;
+
+    let temp_dir = TempDir::new()?;
+    let content = [
+        "Rome was an empire. ".repeat(5),
+        format!("\n## History\n{}", "It rose and fell. ".repeat(6)),
+        format!("\n## Legacy\n{}", "Its law survives. ".repeat(6)),
+        format!("\n## Sources\n{}", "Many. ".repeat(40)),
+    ]
+    .concat();
+    let build = |config: Config| {
+        let mut parser = WikipediaParser::new("en".to_string());
+        parser.articles.insert(
+            "Roman Empire".to_string(),
+            Article {
+                title: "Roman Empire".to_string(),
+                content: content.clone(),
+                ..Default::default()
+            },
+        );
+        StaticMcpGeneratorBuilder::new(config).build(parser, NoCategorizer)
+    };
+    let read_text = |path: PathBuf| -> Result<String, Box<dyn std::error::Error>> {
+        let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+        Ok(response["content"][0]["text"].as_str().unwrap().to_string())
+    };
+
+    let whole_dir = temp_dir.path().join("whole");
+    build(Config::new(PathBuf::new(), whole_dir.clone())).generate(true, None)?;
+    let whole = read_text(whole_dir.join("tools/get_article/roman_empire.json"))?;
+    let (_, body) = whole.split_once("\n\n").unwrap();
+
+    let output_dir = temp_dir.path().join("output");
+    build(Config::new(PathBuf::new(), output_dir.clone()).chunk_articles(150))
+        .generate(true, None)?;
+    let index = read_text(output_dir.join("tools/get_article/roman_empire.json"))?;
+    assert!(index.starts_with("# Roman Empire\n\nThis article is split into "));
+    assert!(index.contains("1. Introduction: tools/get_article/roman_empire/1.json"));
+    assert!(index.contains("2. History: tools/get_article/roman_empire/2.json"));
+
+    let mut parts = Vec::new();
+    for part in 1.. {
+        let path = output_dir.join(format!("tools/get_article/roman_empire/{part}.json"));
+        if !path.exists() {
+            break;
+        }
+        parts.push(read_text(path)?);
+    }
+    assert!(parts.len() > 3, "the long last section is split further");
+    assert!(parts.iter().all(|part| part.chars().count() <= 150));
+    assert!(parts[2].starts_with("## Legacy\n"));
+    assert_eq!(parts.concat(), body);
+
+    Ok(())
+}
+
+#[test]
+fn test_chunked_articles_follow_namespace_collisions() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::StaticMcpGeneratorBuilder;
+
+    let temp_dir = TempDir::new()?;
+    let pages = collision_fixture();
+    let (template, article) = (&pages[0], &pages[1]);
+
+    // Either page may be written first; the outcome must not depend on it
+    for (i, order) in [[template, article], [article, template]]
+        .iter()
+        .enumerate()
+    {
+        let output_dir = temp_dir.path().join(format!("output{i}"));
+        let config = Config::new(PathBuf::new(), output_dir.clone()).chunk_articles(20);
+        let mut generator = StaticMcpGeneratorBuilder::new(config)
+            .build(WikipediaParser::new("en".to_string()), NoCategorizer);
+        for page in order {
+            generator.write_article_with_collision_handling(&page.title, page)?;
+        }
+
+        for (filename, expected) in [
+            ("template_foo", "An article about template foo."),
+            ("template_foo__ns10", "A template called Foo."),
+        ] {
+            let article_dir = output_dir.join("tools/get_article");
+            let index = &response_texts(article_dir.join(format!("{filename}.json")))?[0];
+            assert!(index.contains(&format!("tools/get_article/{filename}/1.json")));
+            let mut body = String::new();
+            for part in 1.. {
+                let path = article_dir.join(format!("{filename}/{part}.json"));
+                if !path.exists() {
+                    break;
+                }
+                body.push_str(&response_texts(path)?[0]);
+            }
+            assert_eq!(body, expected);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_progress_callback_fires_at_interval() -> Result<(), Box<dyn std::error::Error>> {
+    use std::sync::{Arc, Mutex};
+    use wikipedia_core::Progress;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    fs::write(&input_file, create_test_xml())?;
+
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&reports);
+    let config = Config::new(input_file, temp_dir.path().join("output"))
+        .progress_interval(2)
+        .on_progress(move |progress| sink.lock().unwrap().push(progress));
+    generate(config, NoCategorizer)?;
+
+    // Three articles are kept and written, so each step reports once
+    assert_eq!(
+        *reports.lock().unwrap(),
+        [Progress::Parsed(2), Progress::Generated(2)]
+    );
+    assert_eq!(
+        Progress::Parsed(1000).to_string(),
+        "Processed 1000 articles..."
+    );
+
+    // The count runs on across the shards of a tar archive
+    #[cfg(feature = "tar")]
+    {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (i, title) in ["Alpha", "Beta", "Gamma", "Delta"].iter().enumerate() {
+            let contents = format!(
+                "<mediawiki>\n  <page>\n    <title>{title}</title>\n    <ns>0</ns>\n    <id>{}</id>\n    <revision>\n      <text>{title} is a test article.</text>\n    </revision>\n  </page>\n</mediawiki>",
+                i + 1
+            );
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, format!("part-{i}.xml"), contents.as_bytes())?;
+        }
+        let tar_path = temp_dir.path().join("dump.tar");
+        fs::write(&tar_path, builder.into_inner()?)?;
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&reports);
+        let mut parser = WikipediaParser::new("en".to_string())
+            .progress_interval(2)
+            .on_progress(wikipedia_core::ProgressCallback::new(move |progress| {
+                sink.lock().unwrap().push(progress)
+            }));
+        parser.parse(&tar_path, Some(3), &None)?;
+        assert_eq!(*reports.lock().unwrap(), [Progress::Parsed(2)]);
+        assert_eq!(parser.article_count(), 3);
+        assert_eq!(parser.metrics.articles, 3);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_category_names_cannot_escape_output_dir() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::WikipediaError;
+
+    struct EscapingCategorizer(&'static str);
+
+    impl ArticleCategorizer for EscapingCategorizer {
+        fn categorize(&self, _title: &str, _content: &str) -> Vec<String> {
+            vec![self.0.to_string()]
+        }
+    }
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    fs::write(&input_file, create_test_xml())?;
+    let entries = |dir: &std::path::Path| -> Result<Vec<String>, std::io::Error> {
+        let mut names: Vec<String> = fs::read_dir(dir)?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+            .collect::<Result<_, std::io::Error>>()?;
+        names.sort();
+        Ok(names)
+    };
+
+    for (i, name) in ["../evil", "../../../evil"].into_iter().enumerate() {
+        let output_dir = temp_dir.path().join(format!("output{i}"));
+        let config = Config::new(input_file.clone(), output_dir.clone());
+        let error = generate(config, EscapingCategorizer(name)).unwrap_err();
+        let Some(WikipediaError::UnsafePath { path }) = error.downcast_ref::<WikipediaError>()
+        else {
+            panic!("expected WikipediaError::UnsafePath, got {error}");
+        };
+        assert_eq!(path, &format!("tools/categories/{name}.json"));
+
+        assert!(!output_dir.join("tools/evil.json").exists());
+        assert!(!temp_dir.path().join("evil.json").exists());
+    }
+    assert_eq!(
+        entries(temp_dir.path())?,
+        ["output0", "output1", "test.xml"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_keyword_categorizer_matches_whole_words() {
+    use std::collections::HashMap;
+    use wikipedia_core::KeywordCategorizer;
+
+    let categorizer = KeywordCategorizer::new(HashMap::from([
+        (
+            "war".to_string(),
+            vec!["war".to_string(), "battle".to_string()],
+        ),
+        (
+            "20th century".to_string(),
+            vec!["1939".to_string(), "Cold War".to_string()],
+        ),
+        ("software".to_string(), vec!["software".to_string()]),
+    ]));
+
+    assert_eq!(
+        categorizer.categorize(
+            "World War II",
+            "World War II was a global war that lasted from 1939 to 1945."
+        ),
+        ["20th century", "war"]
+    );
+    // "war" inside "software" or "warfare" is not a match
+    assert_eq!(
+        categorizer.categorize("Software", "Software warfare."),
+        ["software"]
+    );
+    assert!(categorizer.categorize("Roman Empire", "").is_empty());
+}
+
+#[test]
+fn test_include_raw_serves_wikitext_next_to_cleaned_content()
+-> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>Roman Empire</title>
+    <id>1</id>
+    <revision>
+      <text>The empire fought the [[Punic Wars|Carthaginians]] and succeeded the [[Roman Republic]].</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+    fs::write(&input_file, test_xml)?;
+
+    let config = Config::new(input_file.clone(), temp_dir.path().join("unused")).include_raw(true);
+    let corpus = parse_only(config, NoCategorizer)?;
+    let article = &corpus.articles["Roman Empire"];
+    assert!(!article.content.contains("[["));
+    assert!(
+        article
+            .raw
+            .as_deref()
+            .unwrap()
+            .contains("[[Roman Republic]]")
+    );
+
+    let config = Config::new(input_file, output_dir.clone()).include_raw(true);
+    generate(config, NoCategorizer)?;
+    let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join("tools/get_article/roman_empire.json"),
+    )?)?;
+    let content = response["content"].as_array().unwrap();
+    assert_eq!(content.len(), 2);
+    let cleaned = content[0]["text"].as_str().unwrap();
+    assert!(cleaned.contains("Carthaginians") && !cleaned.contains("[["));
+    assert_eq!(content[1]["mimeType"], "text/x-wiki");
+    assert_eq!(
+        content[1]["text"],
+        "The empire fought the [[Punic Wars|Carthaginians]] and succeeded the [[Roman Republic]]."
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_include_raw_covers_collisions() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::StaticMcpGeneratorBuilder;
+
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("output");
+
+    let config = Config::new(PathBuf::new(), output_dir.clone()).include_raw(true);
+    let mut generator = StaticMcpGeneratorBuilder::new(config)
+        .build(WikipediaParser::new("en".to_string()), NoCategorizer);
+    for page in collision_fixture() {
+        generator.write_article_with_collision_handling(&page.title, &page)?;
+    }
+    let texts = |filename: &str| {
+        response_texts(output_dir.join(format!("tools/get_article/{filename}.json")))
+    };
+    let raw = |filename: &str| -> Result<String, Box<dyn std::error::Error>> {
+        Ok(texts(filename)?[1].clone())
+    };
+
+    assert_eq!(raw("template_foo")?, "An [[article]] about template foo.");
+    assert_eq!(raw("template_foo__ns10")?, "A [[template]] called Foo.");
+    // Merged articles' wikitext is sectioned like their rendered text
+    assert_eq!(
+        raw("war_article")?,
+        "Short content about [[war]].\n\n---\n\n## War/Article\n\n\
+         Another short article about [[war]]."
+    );
+    // A disambiguation list is no article, so it has no wikitext of its own
+    assert_eq!(texts("long_article")?.len(), 1);
+    assert_eq!(
+        raw("long_article__disambig_1")?,
+        "A long [[article]]. ".repeat(80)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_summary_sentences_limits_get_summary() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::StaticMcpGeneratorBuilder;
+
+    let temp_dir = TempDir::new()?;
+    let build = |config: Config| {
+        let mut parser = WikipediaParser::new("en".to_string());
+        parser.articles.insert(
+            "Rome".to_string(),
+            Article {
+                title: "Rome".to_string(),
+                content: "Rome is the capital of Italy. It was founded c. 753 BC by Romulus, \
+                          according to Dr. Livy. It has almost 2.9 million residents!\n\n\
+                          == History ==\nThe city grew."
+                    .to_string(),
+                ..Default::default()
+            },
+        );
+        StaticMcpGeneratorBuilder::new(config).build(parser, NoCategorizer)
+    };
+    let summary = |dir: &PathBuf| -> Result<String, Box<dyn std::error::Error>> {
+        let response: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+            dir.join("tools/get_summary/rome.json"),
+        )?)?;
+        Ok(response["content"][0]["text"].as_str().unwrap().to_string())
+    };
+
+    let lead_dir = temp_dir.path().join("lead");
+    build(Config::new(PathBuf::new(), lead_dir.clone()).emit_summaries(true))
+        .generate(true, None)?;
+    let lead = summary(&lead_dir)?;
+    assert!(lead.ends_with("It has almost 2.9 million residents!"));
+    assert!(!lead.contains("History"));
+
+    let output_dir = temp_dir.path().join("output");
+    build(
+        Config::new(PathBuf::new(), output_dir.clone())
+            .emit_summaries(true)
+            .summary_sentences(2),
+    )
+    .generate(true, None)?;
+    assert_eq!(
+        summary(&output_dir)?,
+        "# Rome\n\nRome is the capital of Italy. It was founded c. 753 BC by Romulus, \
+         according to Dr. Livy."
+    );
+    let manifest = fs::read_to_string(output_dir.join("mcp.json"))?;
+    assert!(manifest.contains("\"get_summary\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_summaries_follow_collisions() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::StaticMcpGeneratorBuilder;
+
+    let temp_dir = TempDir::new()?;
+    let pages = collision_fixture();
+    let mut swapped = pages.clone();
+    swapped.swap(0, 1);
+    let summary = |dir: &PathBuf, filename: &str| -> Result<String, Box<dyn std::error::Error>> {
+        Ok(response_texts(dir.join(format!("tools/get_summary/{filename}.json")))?[0].clone())
+    };
+
+    for (i, order) in [pages, swapped].iter().enumerate() {
+        let output_dir = temp_dir.path().join(format!("output{i}"));
+        let config = Config::new(PathBuf::new(), output_dir.clone()).emit_summaries(true);
+        let mut generator = StaticMcpGeneratorBuilder::new(config)
+            .build(WikipediaParser::new("en".to_string()), NoCategorizer);
+        for page in order {
+            generator.write_article_with_collision_handling(&page.title, page)?;
+        }
+
+        assert_eq!(
+            summary(&output_dir, "template_foo")?,
+            "# Template Foo\n\nAn article about template foo."
+        );
+        assert_eq!(
+            summary(&output_dir, "template_foo__ns10")?,
+            "# Template:Foo\n\nA template called Foo."
+        );
+        // The merged file is summarised by the lead of the article first written there
+        assert_eq!(
+            summary(&output_dir, "war_article")?,
+            "# War Article\n\nShort content about war."
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_emit_well_known_points_at_manifest() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    generate(
+        Config::new(input_file.clone(), temp_dir.path().join("plain")),
+        NoCategorizer,
+    )?;
+    assert!(!temp_dir.path().join("plain/.well-known").exists());
+
+    generate(
+        Config::new(input_file, output_dir.clone()).emit_well_known(true),
+        TestCategorizer,
+    )?;
+    let document: serde_json::Value = serde_json::from_str(&fs::read_to_string(
+        output_dir.join(".well-known/mcp.json"),
+    )?)?;
+    assert_eq!(document["manifest"], "/mcp.json");
+    assert!(output_dir.join("mcp.json").exists());
+
+    let url_of = |list: &str, key: &str, name: &str| {
+        document[list]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|entry| entry[key] == name)
+            .map(|entry| entry["url"].as_str().unwrap().to_string())
+    };
+    assert_eq!(
+        url_of("resources", "uri", "wikipedia://stats").as_deref(),
+        Some("/resources/stats.json")
+    );
+    assert_eq!(
+        url_of("tools", "name", "get_article").as_deref(),
+        Some("/tools/get_article/")
+    );
+    assert_eq!(
+        url_of("tools", "name", "list_categories").as_deref(),
+        Some("/tools/list_categories.json")
+    );
+    for url in ["/resources/stats.json", "/tools/list_categories.json"] {
+        assert!(output_dir.join(url.trim_start_matches('/')).exists());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_id_range_keeps_pages_in_range() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+
+    let pages: String = [(10, "Alpha"), (20, "Beta"), (30, "Gamma"), (40, "Delta")]
+        .iter()
+        .map(|(id, title)| {
+            format!(
+                "  <page>\n    <title>{title}</title>\n    <ns>0</ns>\n    <id>{id}</id>\n    \
+                 <revision>\n      <id>9{id}</id>\n      <text>{title} is a letter.</text>\n    \
+                 </revision>\n  </page>\n"
+            )
+        })
+        .collect();
+    fs::write(&input_file, format!("<mediawiki>\n{pages}</mediawiki>"))?;
+
+    let titles = |config: Config| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut titles: Vec<String> = parse_only(config, NoCategorizer)?
+            .articles
+            .into_keys()
+            .collect();
+        titles.sort();
+        Ok(titles)
+    };
+    let config = || Config::new(input_file.clone(), temp_dir.path().join("unused"));
+
+    assert_eq!(
+        titles(config().id_range(Some((15, 30))))?,
+        ["Beta", "Gamma"]
+    );
+    // The categorized parse path filters the same way
+    assert_eq!(
+        titles(
+            config()
+                .id_range(Some((15, 30)))
+                .categorize_during_parse(true)
+        )?,
+        ["Beta", "Gamma"]
+    );
+    assert_eq!(titles(config().id_range(Some((40, 40))))?, ["Delta"]);
+    assert_eq!(titles(config())?.len(), 4);
+
+    Ok(())
+}
+
+#[test]
+fn test_cancellation_stops_generation() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::{CancellationToken, Progress, WikipediaError};
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    fs::write(&input_file, create_test_xml())?;
+
+    let cancelled_stats =
+        |error: Box<dyn std::error::Error>| match error.downcast::<WikipediaError>() {
+            Ok(error) => match *error {
+                WikipediaError::Cancelled { stats } => stats,
+                error => panic!("expected WikipediaError::Cancelled, got {error}"),
+            },
+            Err(error) => panic!("expected WikipediaError::Cancelled, got {error}"),
+        };
+
+    // Cancelled while parsing, once the first article is kept
+    let token = CancellationToken::new();
+    let flag = token.clone();
+    let config = Config::new(input_file.clone(), temp_dir.path().join("parse"))
+        .cancellation(token)
+        .progress_interval(1)
+        .on_progress(move |progress| {
+            if progress == Progress::Parsed(1) {
+                flag.cancel();
+            }
+        });
+    let stats = cancelled_stats(generate(config, NoCategorizer).unwrap_err());
+    assert_eq!(stats.parse.articles, 1);
+    assert_eq!(stats.articles_written, 0);
+    assert!(!temp_dir.path().join("parse/mcp.json").exists());
+
+    // Cancelled while writing, once the first article response is out
+    let token = CancellationToken::new();
+    let flag = token.clone();
+    let output_dir = temp_dir.path().join("generate");
+    let config = Config::new(input_file, output_dir.clone())
+        .cancellation(token)
+        .progress_interval(1)
+        .on_progress(move |progress| {
+            if progress == Progress::Generated(1) {
+                flag.cancel();
+            }
+        });
+    let error = generate(config, NoCategorizer).unwrap_err();
+    assert!(error.to_string().starts_with("Cancelled"));
+    let stats = cancelled_stats(error);
+    assert_eq!(stats.articles_written, 1);
+    let written = fs::read_dir(output_dir.join("tools/get_article"))?.count();
+    assert_eq!(written, 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_chunking_covers_content_with_overlap() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::{ChunkConfig, StaticMcpGeneratorBuilder};
+
+    let temp_dir = TempDir::new()?;
+    let output_dir = temp_dir.path().join("output");
+    let content: String = (1..=12)
+        .map(|n| format!("Sentence number {n} has exactly six words."))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut parser = WikipediaParser::new("en".to_string());
+    parser.articles.insert(
+        "Long".to_string(),
+        Article {
+            title: "Long".to_string(),
+            content: content.clone(),
+            ..Default::default()
+        },
+    );
+    let config = Config::new(PathBuf::new(), output_dir.clone()).chunking(Some(ChunkConfig {
+        size: 20,
+        overlap: 5,
+    }));
+    StaticMcpGeneratorBuilder::new(config)
+        .build(parser, NoCategorizer)
+        .generate(true, None)?;
+
+    let read_payload = |path: PathBuf| -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_str(&response_texts(path)?[0])?)
+    };
+    let overview = read_payload(output_dir.join("tools/chunks/long.json"))?;
+    let total = overview["chunks"].as_u64().unwrap() as usize;
+    assert!(total > 3);
+
+    let chars: Vec<char> = content.chars().collect();
+    let mut chunks = Vec::new();
+    for index in 1..=total {
+        let chunk = read_payload(output_dir.join(format!("tools/chunks/long/{index}.json")))?;
+        assert_eq!(chunk["title"], "Long");
+        assert_eq!(chunk["index"], index);
+        let (start, end) = (
+            chunk["start"].as_u64().unwrap() as usize,
+            chunk["end"].as_u64().unwrap() as usize,
+        );
+        let text = chunk["text"].as_str().unwrap().to_string();
+        assert_eq!(chars[start..end].iter().collect::<String>(), text);
+        chunks.push((start, end, text));
+    }
+
+    // The chunks span the content from end to end
+    assert_eq!(chunks[0].0, 0);
+    assert_eq!(chunks[total - 1].1, chars.len());
+    for pair in chunks.windows(2) {
+        let (previous, next) = (&pair[0].2, &pair[1].2);
+        let words: Vec<&str> = previous.split_whitespace().collect();
+        assert!(words.len() <= 20);
+        // Each chunk repeats exactly the last five words of the one before
+        let repeated = words[words.len() - 5..].join(" ");
+        assert!(
+            next.starts_with(&repeated),
+            "{next:?} should start with {repeated:?}"
+        );
+        assert!(pair[1].0 < pair[0].1);
+    }
+    // Windows end on sentences where they can
+    assert!(
+        chunks[..total - 1]
+            .iter()
+            .all(|(_, _, text)| text.ends_with('.'))
+    );
+
+    // Chunks follow articles that collision handling moves, merges or lists
+    let collisions_dir = temp_dir.path().join("collisions");
+    let config = Config::new(PathBuf::new(), collisions_dir.clone()).chunking(Some(ChunkConfig {
+        size: 20,
+        overlap: 5,
+    }));
+    let mut generator = StaticMcpGeneratorBuilder::new(config)
+        .build(WikipediaParser::new("en".to_string()), NoCategorizer);
+    for page in collision_fixture() {
+        generator.write_article_with_collision_handling(&page.title, &page)?;
+    }
+    let chunk = |filename: &str, index: usize| {
+        read_payload(collisions_dir.join(format!("tools/chunks/{filename}/{index}.json")))
+    };
+    assert_eq!(
+        chunk("template_foo", 1)?["text"],
+        "An article about template foo."
+    );
+    assert_eq!(
+        chunk("template_foo__ns10", 1)?["text"],
+        "A template called Foo."
+    );
+    let merged = chunk("war_article", 1)?;
+    assert_eq!(merged["title"], "War Article");
+    assert!(
+        merged["text"]
+            .as_str()
+            .unwrap()
+            .ends_with("Another short article about war.")
+    );
+    assert!(
+        !collisions_dir
+            .join("tools/chunks/long_article.json")
+            .exists()
+    );
+    assert_eq!(
+        chunk("long_article__disambig_1", 1)?["title"],
+        "Long Article__disambig_1"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_case_insensitive_titles_collapse_variants() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+
+    let test_xml = r#"<mediawiki>
+  <page>
+    <title>iPhone</title>
+    <id>1</id>
+    <revision>
+      <text>The iPhone is a smartphone.</text>
+    </revision>
+  </page>
+  <page>
+    <title>IPhone</title>
+    <id>2</id>
+    <revision>
+      <text>A later copy under another case.</text>
+    </revision>
+  </page>
+  <page>
+    <title>Android</title>
+    <id>3</id>
+    <revision>
+      <text>Android is an operating system.</text>
+    </revision>
+  </page>
+  <page>
+    <title>IPHONE</title>
+    <id>4</id>
+    <redirect title="iPhone" />
+    <revision>
+      <text>#REDIRECT [[iPhone]]</text>
+    </revision>
+  </page>
+  <page>
+    <title>Apple phone</title>
+    <id>5</id>
+    <redirect title="iPhone" />
+    <revision>
+      <text>#REDIRECT [[iPhone]]</text>
+    </revision>
+  </page>
+  <page>
+    <title>Apple Phone</title>
+    <id>6</id>
+    <redirect title="iPhone" />
+    <revision>
+      <text>#REDIRECT [[iPhone]]</text>
+    </revision>
+  </page>
+</mediawiki>"#;
+    fs::write(&input_file, test_xml)?;
+
+    let config = Config::new(input_file.clone(), temp_dir.path().join("unused"));
+    assert_eq!(parse_only(config, NoCategorizer)?.articles.len(), 3);
+
+    let config = Config::new(input_file.clone(), temp_dir.path().join("unused"))
+        .case_insensitive_titles(true);
+    let corpus = parse_only(config, NoCategorizer)?;
+    let mut titles: Vec<&String> = corpus.articles.keys().collect();
+    titles.sort();
+    assert_eq!(titles, ["Android", "iPhone"]);
+    assert_eq!(
+        corpus.articles["iPhone"].content,
+        "The iPhone is a smartphone."
+    );
+
+    let mut parser = WikipediaParser::new("en".to_string()).case_insensitive_titles(true);
+    parser.parse(&input_file, None, &None)?;
+    let found = parser.find_article("IPHONE").unwrap();
+    assert_eq!(found.title, "iPhone");
+    let mut redirects: Vec<&String> = parser.redirects.keys().collect();
+    redirects.sort();
+    assert_eq!(redirects, ["Apple phone"]);
+
+    // Dropped variants don't count towards `max_articles`
+    let mut parser = WikipediaParser::new("en".to_string()).case_insensitive_titles(true);
+    parser.parse(&input_file, Some(2), &None)?;
+    let mut titles: Vec<&String> = parser.articles.keys().collect();
+    titles.sort();
+    assert_eq!(titles, ["Android", "iPhone"]);
+
+    // Streaming and the JSON Lines export fold titles the same way
+    let mut streamed = Vec::new();
+    WikipediaParser::new("en".to_string())
+        .case_insensitive_titles(true)
+        .parse_streaming(
+            Box::new(fs::File::open(&input_file)?),
+            false,
+            &None,
+            |title, _| {
+                streamed.push(title.to_string());
+                Ok(())
+            },
+        )?;
+    assert_eq!(streamed, ["iPhone", "Android", "Apple phone"]);
+
+    let output_dir = temp_dir.path().join("output");
+    let config = Config::new(input_file, output_dir.clone())
+        .case_insensitive_titles(true)
+        .output_format(wikipedia_core::OutputFormat::JsonLines);
+    generate(config, NoCategorizer)?;
+    let jsonl = fs::read_to_string(output_dir.join("articles.jsonl"))?;
+    assert_eq!(jsonl.lines().count(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_json_lines_export() -> Result<(), Box<dyn std::error::Error>> {
+    use wikipedia_core::OutputFormat;
+
+    let temp_dir = TempDir::new()?;
+    let input_file = temp_dir.path().join("test.xml");
+    let output_dir = temp_dir.path().join("output");
+    fs::write(&input_file, create_test_xml())?;
+
+    let config = Config::new(input_file, output_dir.clone()).output_format(OutputFormat::JsonLines);
+    let stats = generate(config, NoCategorizer)?;
+    assert_eq!(stats.articles_written, 3);
+    assert!(!output_dir.join("mcp.json").exists());
+
+    let jsonl = fs::read_to_string(output_dir.join("articles.jsonl"))?;
+    let mut titles = Vec::new();
+    for line in jsonl.lines() {
+        let article: serde_json::Value = serde_json::from_str(line)?;
+        assert!(article["content"].as_str().is_some_and(|c| !c.is_empty()));
+        assert!(article["id"].as_u64().is_some());
+        titles.push(article["title"].as_str().unwrap().to_string());
+    }
+    titles.sort();
+    assert_eq!(titles, ["Computer Science", "Roman Empire", "World War II"]);
+
+    Ok(())
+}